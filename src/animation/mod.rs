@@ -0,0 +1,499 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  mod.rs
+** Module       :  animation
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the animation scheduler, which tweens
+**                 view properties as the window renders frames.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, debug::*};
+
+pub use crate::{Animation, View};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::mem::discriminant;
+use std::time::{Duration, Instant};
+
+/***************************************************************
+* MARK: CHILD MODULES
+***************************************************************/
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// a view property and the value it is animated to, the animation starts from the value the view has when its delay ends
+#[derive(Debug, Clone, Copy)]
+pub enum AnimatedProperty
+{
+    Size(Size),
+    BackgroundColor(Color),
+    Opacity(f32),
+
+    // the view's translation, which moves it without changing the layout
+    Offset(Point)
+}
+
+// how progress through an animation maps to progress between its values, the presets match CSS
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing
+{
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+
+    // the control points of a curve from 0, 0 to 1, 1, x values are kept between 0 and 1
+    CubicBezier(f32, f32, f32, f32)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Repeat
+{
+    // the number of times the animation plays, at least once
+    Count(u32),
+    Forever
+}
+
+// called once an animation ends, finished is false when it was cancelled or replaced before it got there
+pub type AnimationCompletionHandler = Box<dyn FnMut(Animation, bool)>;
+
+struct AnimationObject
+{
+    pub view: View,
+    pub target: AnimatedProperty,
+
+    // taken from the view when the delay ends
+    pub from: Option<AnimatedProperty>,
+
+    pub start: Instant,
+    pub duration: Duration,
+    pub delay: Duration,
+    pub easing: Easing,
+    pub repeat: Repeat,
+
+    // plays every other repeat backwards, so the view swings between the two values
+    pub auto_reverse: bool,
+
+    pub completion_handler: Option<AnimationCompletionHandler>
+}
+
+struct AnimationState
+{
+    pub next_id: Animation,
+    pub animations: HashMap<Animation, AnimationObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static ANIMATION_STATE: RefCell<AnimationState> = RefCell::new(AnimationState {
+        next_id: 1,
+        animations: HashMap::new()
+    });
+}
+
+// steps used to find the point on a bezier easing curve for a given progress
+const BEZIER_NEWTON_STEPS: usize = 8;
+const BEZIER_BISECTION_STEPS: usize = 24;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+impl Easing
+{
+    // maps progress from 0 to 1 through the curve
+    pub fn apply(&self, progress: f32) -> f32
+    {
+        let progress = progress.clamp(0.0, 1.0);
+
+        return match *self
+        {
+            Easing::Linear => progress,
+            Easing::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, progress),
+            Easing::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, progress),
+            Easing::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, progress),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1.clamp(0.0, 1.0), y1, x2.clamp(0.0, 1.0), y2, progress)
+        };
+    }
+}
+
+// starts animating a property of the view from its current value to the target on the next frame,
+// once its delay ends it replaces the animation of the same property of the view that is running by then
+pub fn animate(view: View, target: AnimatedProperty, duration: Duration) -> Animation
+{
+    let new_animation = ANIMATION_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let new_animation = state.next_id;
+        state.next_id += 1;
+
+        state.animations.insert(new_animation, AnimationObject {
+            view: view,
+            target: target,
+            from: None,
+            start: crate::app::clock::now(),
+            duration: duration,
+            delay: Duration::ZERO,
+            easing: Easing::EaseInOut,
+            repeat: Repeat::Count(1),
+            auto_reverse: false,
+            completion_handler: None
+        });
+
+        return new_animation;
+    });
+
+    // the first frame starts the animation, the window asks for the next ones while anything is running
+    if let Some(window) = crate::view::get_window(view)
+    {
+        crate::window::request_redraw(window);
+    }
+
+    return new_animation;
+}
+
+pub fn set_easing(animation: Animation, easing: Easing)
+{
+    with_animation(animation, |animation_object| animation_object.easing = easing);
+}
+
+// waits this long after the animation was created before it starts
+pub fn set_delay(animation: Animation, delay: Duration)
+{
+    with_animation(animation, |animation_object| animation_object.delay = delay);
+}
+
+pub fn set_repeat(animation: Animation, repeat: Repeat)
+{
+    with_animation(animation, |animation_object| animation_object.repeat = repeat);
+}
+
+pub fn set_auto_reverse(animation: Animation, auto_reverse: bool)
+{
+    with_animation(animation, |animation_object| animation_object.auto_reverse = auto_reverse);
+}
+
+pub fn set_completion_handler<F: FnMut(Animation, bool) + 'static>(animation: Animation, handler: F)
+{
+    with_animation(animation, |animation_object| animation_object.completion_handler = Some(Box::new(handler)));
+}
+
+// stops the animation where it is and calls its completion handler as unfinished
+pub fn cancel(animation: Animation)
+{
+    let removed = ANIMATION_STATE.with(|state| state.borrow_mut().animations.remove(&animation));
+
+    if let Some(animation_object) = removed
+    {
+        if let Some(mut handler) = animation_object.completion_handler
+        {
+            handler(animation, false);
+        }
+    }
+}
+
+// stops every animation of the view, such as when it is about to be destroyed
+pub fn cancel_view(view: View)
+{
+    let animations: Vec<Animation> = ANIMATION_STATE.with(|state| {
+        return state.borrow().animations.iter()
+            .filter(|(_, animation_object)| animation_object.view == view)
+            .map(|(animation, _)| *animation)
+            .collect();
+    });
+
+    for animation in animations
+    {
+        cancel(animation);
+    }
+}
+
+pub fn is_running(animation: Animation) -> bool
+{
+    return ANIMATION_STATE.with(|state| state.borrow().animations.contains_key(&animation));
+}
+
+// true while anything is animating, so the loop knows to keep drawing frames
+pub fn has_running() -> bool
+{
+    return ANIMATION_STATE.with(|state| !state.borrow().animations.is_empty());
+}
+
+// moves every animation to the clock's current time, called by windows before they render a frame
+pub fn update()
+{
+    let now = crate::app::clock::now();
+
+    // in the order they were created, so of two animations that start in the same frame the later one wins
+    let mut animations: Vec<Animation> = ANIMATION_STATE.with(|state| state.borrow().animations.keys().copied().collect());
+    animations.sort();
+
+    let mut finished: Vec<Animation> = Vec::new();
+    let mut resized_parents: Vec<View> = Vec::new();
+
+    for animation in animations
+    {
+        let frame = ANIMATION_STATE.with(|state| {
+            return state.borrow().animations.get(&animation).map(|animation_object| {
+                (animation_object.view, animation_object.target, animation_object.from, frame_progress(animation_object, now))
+            });
+        });
+
+        let (view, target, from, (progress, done)) = match frame
+        {
+            Some((view, target, from, Some(progress))) => (view, target, from, progress),
+            _ => continue
+        };
+
+        // the starting value is read when the delay ends, so a chain of delayed animations picks up where the last one left off
+        let from = match from
+        {
+            Some(from) => from,
+            None =>
+            {
+                replace_started(animation, view, &target, &finished);
+
+                let from = current_value(view, &target);
+
+                with_animation(animation, |animation_object| animation_object.from = Some(from));
+                from
+            }
+        };
+
+        apply_value(view, interpolate(&from, &target, progress));
+
+        if let AnimatedProperty::Size(_) = target
+        {
+            if let Some(parent) = crate::view::get_parent(view)
+            {
+                if !resized_parents.contains(&parent)
+                {
+                    resized_parents.push(parent);
+                }
+            }
+        }
+
+        if done
+        {
+            finished.push(animation);
+        }
+    }
+
+    // a view that changes size moves its siblings, so the parent lays them out again in its current rect
+    for parent in resized_parents
+    {
+        crate::view::layout_down(parent, crate::view::get_rect(parent));
+    }
+
+    for animation in finished
+    {
+        let removed = ANIMATION_STATE.with(|state| state.borrow_mut().animations.remove(&animation));
+
+        if let Some(mut handler) = removed.and_then(|animation_object| animation_object.completion_handler)
+        {
+            handler(animation, true);
+        }
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn with_animation<T, F: FnOnce(&mut AnimationObject) -> T>(animation: Animation, action: F) -> Option<T>
+{
+    return ANIMATION_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(animation_object) = state.animations.get_mut(&animation)
+        {
+            return Some(action(animation_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Animation not found!"));
+            return None;
+        }
+    });
+}
+
+// cancels the animations of the same property of the view that have already started, the ones still in their delay
+// wait their turn so delayed animations can be chained, and ones that reached their end this frame still finish
+fn replace_started(animation: Animation, view: View, target: &AnimatedProperty, finished: &[Animation])
+{
+    let replaced: Vec<Animation> = ANIMATION_STATE.with(|state| {
+        return state.borrow().animations.iter()
+            .filter(|(other, animation_object)| {
+                return **other != animation && !finished.contains(*other) && animation_object.view == view && animation_object.from.is_some()
+                    && discriminant(&animation_object.target) == discriminant(target);
+            })
+            .map(|(other, _)| *other)
+            .collect();
+    });
+
+    for other in replaced
+    {
+        cancel(other);
+    }
+}
+
+// eased progress between the values at time now and whether the animation has ended, none while it is still delayed
+fn frame_progress(animation_object: &AnimationObject, now: Instant) -> Option<(f32, bool)>
+{
+    let elapsed = now.saturating_duration_since(animation_object.start);
+
+    if elapsed < animation_object.delay
+    {
+        return None;
+    }
+
+    let running = elapsed - animation_object.delay;
+    let duration = animation_object.duration.as_secs_f32();
+
+    let iterations = match animation_object.repeat
+    {
+        Repeat::Count(count) => Some(count.max(1)),
+        Repeat::Forever => None
+    };
+
+    // an instant animation jumps straight to where its last iteration ends
+    let (iteration, local, done) = if duration <= 0.0
+    {
+        let last = iterations.unwrap_or(1) - 1;
+        (last, 1.0, iterations.is_some())
+    }
+    else
+    {
+        let position = running.as_secs_f32() / duration;
+
+        match iterations
+        {
+            Some(count) if position >= count as f32 => (count - 1, 1.0, true),
+            _ => (position.floor() as u32, position.fract(), false)
+        }
+    };
+
+    let reversed = animation_object.auto_reverse && iteration % 2 == 1;
+    let local = if reversed { 1.0 - local } else { local };
+
+    return Some((animation_object.easing.apply(local), done));
+}
+
+// solves the curve for the point whose x is the progress and returns its y
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, progress: f32) -> f32
+{
+    if progress <= 0.0 || progress >= 1.0
+    {
+        return progress;
+    }
+
+    let curve = |p1: f32, p2: f32, t: f32| -> f32 {
+        let mt = 1.0 - t;
+        return 3.0 * mt * mt * t * p1 + 3.0 * mt * t * t * p2 + t * t * t;
+    };
+
+    let slope = |p1: f32, p2: f32, t: f32| -> f32 {
+        let mt = 1.0 - t;
+        return 3.0 * mt * mt * p1 + 6.0 * mt * t * (p2 - p1) + 3.0 * t * t * (1.0 - p2);
+    };
+
+    // newton's method converges quickly on most curves
+    let mut t = progress;
+
+    for _ in 0..BEZIER_NEWTON_STEPS
+    {
+        let error = curve(x1, x2, t) - progress;
+
+        if error.abs() < 1e-5
+        {
+            return curve(y1, y2, t);
+        }
+
+        let derivative = slope(x1, x2, t);
+
+        if derivative.abs() < 1e-6
+        {
+            break;
+        }
+
+        t = (t - error / derivative).clamp(0.0, 1.0);
+    }
+
+    // bisection for the flat spots where it does not
+    let mut low: f32 = 0.0;
+    let mut high: f32 = 1.0;
+    t = progress;
+
+    for _ in 0..BEZIER_BISECTION_STEPS
+    {
+        if curve(x1, x2, t) < progress { low = t; } else { high = t; }
+        t = (low + high) / 2.0;
+    }
+
+    return curve(y1, y2, t);
+}
+
+fn current_value(view: View, target: &AnimatedProperty) -> AnimatedProperty
+{
+    return match target
+    {
+        AnimatedProperty::Size(_) => AnimatedProperty::Size(crate::view::get_size(view)),
+        AnimatedProperty::BackgroundColor(_) => AnimatedProperty::BackgroundColor(crate::view::get_background_color(view)),
+        AnimatedProperty::Opacity(_) => AnimatedProperty::Opacity(crate::view::get_opacity(view)),
+        AnimatedProperty::Offset(_) => AnimatedProperty::Offset(crate::view::get_translation(view))
+    };
+}
+
+fn apply_value(view: View, value: AnimatedProperty)
+{
+    match value
+    {
+        AnimatedProperty::Size(size) => crate::view::set_size(view, size),
+        AnimatedProperty::BackgroundColor(color) => crate::view::set_background_color(view, color),
+        AnimatedProperty::Opacity(opacity) => crate::view::set_opacity(view, opacity),
+        AnimatedProperty::Offset(offset) => crate::view::set_translation(view, offset)
+    }
+}
+
+// eased progress can overshoot 0 and 1, so values are not clamped except where they must be
+fn interpolate(from: &AnimatedProperty, to: &AnimatedProperty, progress: f32) -> AnimatedProperty
+{
+    let mix = |a: f32, b: f32| -> f32 { a + (b - a) * progress };
+
+    return match (*from, *to)
+    {
+        (AnimatedProperty::Size(a), AnimatedProperty::Size(b)) =>
+        {
+            AnimatedProperty::Size(Size { width: mix(a.width, b.width).max(0.0), height: mix(a.height, b.height).max(0.0) })
+        },
+        (AnimatedProperty::BackgroundColor(a), AnimatedProperty::BackgroundColor(b)) =>
+        {
+            // mixed premultiplied, so fading in from transparent does not pass through black
+            let alpha = mix(a.a, b.a).clamp(0.0, 1.0);
+            let channel = |from: f32, to: f32| -> f32 {
+                if alpha <= 0.0 { return to; }
+                return (mix(from * a.a, to * b.a) / alpha).clamp(0.0, 1.0);
+            };
+
+            AnimatedProperty::BackgroundColor(Color { r: channel(a.r, b.r), g: channel(a.g, b.g), b: channel(a.b, b.b), a: alpha })
+        },
+        (AnimatedProperty::Opacity(a), AnimatedProperty::Opacity(b)) => AnimatedProperty::Opacity(mix(a, b)),
+        (AnimatedProperty::Offset(a), AnimatedProperty::Offset(b)) => AnimatedProperty::Offset(Point { x: mix(a.x, b.x), y: mix(a.y, b.y) }),
+        _ => *to
+    };
+}
@@ -0,0 +1,92 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  clock.rs
+** Module       :  app
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the application clock, which follows
+**                 real time or a manual time advanced by tests.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::debug::*;
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+struct ClockState
+{
+    // the current time while the clock is manual, none while it follows real time
+    pub manual_time: Option<Instant>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static CLOCK_STATE: RefCell<ClockState> = const { RefCell::new(ClockState {
+        manual_time: None
+    }) };
+}
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+// the time everything scheduled on the application goes by
+pub fn now() -> Instant
+{
+    return CLOCK_STATE.with(|state| state.borrow().manual_time.unwrap_or_else(Instant::now));
+}
+
+// a manual clock stands still at the time it was switched on until it is advanced, so tests can step time exactly
+pub fn set_manual(manual: bool)
+{
+    CLOCK_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if manual
+        {
+            state.manual_time.get_or_insert_with(Instant::now);
+        }
+        else
+        {
+            state.manual_time = None;
+        }
+    });
+}
+
+pub fn is_manual() -> bool
+{
+    return CLOCK_STATE.with(|state| state.borrow().manual_time.is_some());
+}
+
+// moves a manual clock forward, real time cannot be advanced
+pub fn advance(duration: Duration)
+{
+    CLOCK_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        match state.manual_time.as_mut()
+        {
+            Some(manual_time) => *manual_time += duration,
+            None => log(LogLevel::WARN, &format!("Only a manual clock can be advanced!"))
+        }
+    });
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
@@ -0,0 +1,242 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  command.rs
+** Module       :  app
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the application command registry and
+**                 the key chords bound to its commands.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::debug::*;
+
+pub use crate::Window;
+
+use crate::event::{Key, KeyChord, Modifiers};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// called with the window the command was invoked from, if any
+pub type CommandHandler = Box<dyn FnMut(Option<Window>)>;
+pub type CommandEnabledPredicate = Box<dyn FnMut() -> bool>;
+
+// window bindings take precedence over global ones for the same chord
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandScope
+{
+    Global,
+    Window(Window)
+}
+
+struct CommandObject
+{
+    pub handler: Option<CommandHandler>,
+    pub enabled_predicate: Option<CommandEnabledPredicate>
+}
+
+struct CommandState
+{
+    pub commands: HashMap<String, CommandObject>,
+    pub bindings: HashMap<(CommandScope, KeyChord), String>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static COMMAND_STATE: RefCell<CommandState> = RefCell::new(CommandState {
+        commands: HashMap::new(),
+        bindings: HashMap::new()
+    });
+}
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+// registers a named command such as "file.save", replacing the handler of an existing one
+pub fn register<F: FnMut(Option<Window>) + 'static>(name: &str, handler: F)
+{
+    COMMAND_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        match state.commands.get_mut(name)
+        {
+            Some(command_object) => command_object.handler = Some(Box::new(handler)),
+            None =>
+            {
+                state.commands.insert(String::from(name), CommandObject {
+                    handler: Some(Box::new(handler)),
+                    enabled_predicate: None
+                });
+            }
+        }
+    });
+}
+
+// removes the command along with every key chord bound to it
+pub fn unregister(name: &str)
+{
+    COMMAND_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if state.commands.remove(name).is_none()
+        {
+            log(LogLevel::FAIL, &format!("Command not found!"));
+        }
+
+        state.bindings.retain(|_, command| command != name);
+    });
+}
+
+pub fn is_registered(name: &str) -> bool
+{
+    return COMMAND_STATE.with(|state| state.borrow().commands.contains_key(name));
+}
+
+// commands without a predicate are always enabled
+pub fn set_enabled_predicate<F: FnMut() -> bool + 'static>(name: &str, predicate: F)
+{
+    with_command(name, |command_object| command_object.enabled_predicate = Some(Box::new(predicate)));
+}
+
+pub fn is_enabled(name: &str) -> bool
+{
+    if !is_registered(name)
+    {
+        log(LogLevel::FAIL, &format!("Command not found!"));
+        return false;
+    }
+
+    return crate::widget::call_taken(|slot| with_command(name, |command_object| slot(&mut command_object.enabled_predicate)), |predicate| predicate()).unwrap_or(true);
+}
+
+// runs the command if it is enabled, returns false when it is disabled or unknown
+pub fn execute(name: &str, window: Option<Window>) -> bool
+{
+    if !is_enabled(name)
+    {
+        return false;
+    }
+
+    crate::widget::call_taken(|slot| with_command(name, |command_object| slot(&mut command_object.handler)), |handler| handler(window));
+
+    return true;
+}
+
+// binds a chord to a command, refusing when the chord already runs a different command in that scope
+pub fn bind_key(name: &str, chord: KeyChord, scope: CommandScope) -> bool
+{
+    let chord = KeyChord::new(chord.key, chord.modifiers);
+
+    return COMMAND_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if !state.commands.contains_key(name)
+        {
+            log(LogLevel::FAIL, &format!("Command not found!"));
+            return false;
+        }
+
+        if let Some(existing) = state.bindings.get(&(scope, chord))
+        {
+            if existing != name
+            {
+                log(LogLevel::WARN, &format!("Key chord {} is already bound to {}!", chord.to_text(), existing));
+                return false;
+            }
+        }
+
+        state.bindings.insert((scope, chord), String::from(name));
+        return true;
+    });
+}
+
+pub fn unbind_key(chord: KeyChord, scope: CommandScope)
+{
+    let chord = KeyChord::new(chord.key, chord.modifiers);
+
+    COMMAND_STATE.with(|state| {
+        state.borrow_mut().bindings.remove(&(scope, chord));
+    });
+}
+
+// the command a chord is bound to in exactly this scope, used to check for conflicts before binding
+pub fn get_binding(chord: KeyChord, scope: CommandScope) -> Option<String>
+{
+    let chord = KeyChord::new(chord.key, chord.modifiers);
+
+    return COMMAND_STATE.with(|state| state.borrow().bindings.get(&(scope, chord)).cloned());
+}
+
+pub fn get_bindings(name: &str) -> Vec<(KeyChord, CommandScope)>
+{
+    return COMMAND_STATE.with(|state| {
+        return state.borrow().bindings.iter()
+            .filter(|(_, command)| *command == name)
+            .map(|((scope, chord), _)| (*chord, *scope))
+            .collect();
+    });
+}
+
+// the chord shown for a command in a window, preferring the window's own binding
+pub fn get_display_chord(name: &str, window: Option<Window>) -> Option<KeyChord>
+{
+    let bindings = get_bindings(name);
+
+    let window_binding = window.and_then(|window| bindings.iter().find(|(_, scope)| *scope == CommandScope::Window(window)));
+    let global_binding = bindings.iter().find(|(_, scope)| *scope == CommandScope::Global);
+
+    return window_binding.or(global_binding).map(|(chord, _)| *chord);
+}
+
+// the command a key press in the window would run, checking the window's bindings first
+pub fn find_command(window: Window, chord: KeyChord) -> Option<String>
+{
+    return get_binding(chord, CommandScope::Window(window)).or_else(|| get_binding(chord, CommandScope::Global));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn with_command<T, F: FnOnce(&mut CommandObject) -> T>(name: &str, action: F) -> Option<T>
+{
+    return COMMAND_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(command_object) = state.commands.get_mut(name)
+        {
+            return Some(action(command_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Command not found!"));
+            return None;
+        }
+    });
+}
+
+// runs the command bound to a key press that the focused view did not handle
+pub(crate) fn handle_key(window: Window, key: Key, modifiers: Modifiers) -> bool
+{
+    return match find_command(window, KeyChord::new(key, modifiers))
+    {
+        Some(name) => execute(&name, Some(window)),
+        None => false
+    };
+}
@@ -0,0 +1,138 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  dispatch.rs
+** Module       :  app
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the Dispatcher, which lets other threads
+**                 queue work to run on the UI thread.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::debug::*;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+pub type DispatchedCallback = Box<dyn FnOnce() + Send>;
+pub type WakeupHandler = Arc<dyn Fn() + Send + Sync>;
+
+struct DispatchQueue
+{
+    pub callbacks: Mutex<VecDeque<DispatchedCallback>>,
+
+    // set by the first dispatch after the queue was drained, so a burst of dispatches wakes the loop once
+    pub wakeup_requested: AtomicBool,
+
+    pub wakeup_handler: Mutex<Option<WakeupHandler>>
+}
+
+// a handle to a UI thread's queue that can be cloned and sent to any thread
+#[derive(Clone)]
+pub struct Dispatcher
+{
+    queue: Arc<DispatchQueue>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static DISPATCH_QUEUE: Arc<DispatchQueue> = Arc::new(DispatchQueue {
+        callbacks: Mutex::new(VecDeque::new()),
+        wakeup_requested: AtomicBool::new(false),
+        wakeup_handler: Mutex::new(Some(Arc::new(crate::platform::wake)))
+    });
+}
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+// the dispatcher for the calling thread, call this on the UI thread and hand the result to workers
+pub fn dispatcher() -> Dispatcher
+{
+    return DISPATCH_QUEUE.with(|queue| Dispatcher { queue: queue.clone() });
+}
+
+// replaces what wakes this thread's event loop when work is dispatched to it from another thread,
+// by default NanoWin's loop is woken and runs the callbacks
+pub fn set_wakeup_handler<F: Fn() + Send + Sync + 'static>(handler: F)
+{
+    DISPATCH_QUEUE.with(|queue| *lock(&queue.wakeup_handler) = Some(Arc::new(handler)));
+}
+
+// without a wakeup handler dispatched callbacks wait until the loop next renders a frame
+pub fn clear_wakeup_handler()
+{
+    DISPATCH_QUEUE.with(|queue| *lock(&queue.wakeup_handler) = None);
+}
+
+impl Dispatcher
+{
+    // queues the callback to run on the UI thread the next time the loop processes pending work, callbacks run in the order they were dispatched
+    pub fn dispatch<F: FnOnce() + Send + 'static>(&self, callback: F)
+    {
+        lock(&self.queue.callbacks).push_back(Box::new(callback));
+
+        if !self.queue.wakeup_requested.swap(true, Ordering::AcqRel)
+        {
+            // the handler is cloned out so it is not called with the lock held
+            let wakeup_handler = lock(&self.queue.wakeup_handler).clone();
+
+            if let Some(wakeup_handler) = wakeup_handler
+            {
+                wakeup_handler();
+            }
+        }
+    }
+
+    // true when called on the thread the dispatcher runs callbacks on
+    pub fn is_current_thread(&self) -> bool
+    {
+        return DISPATCH_QUEUE.with(|queue| Arc::ptr_eq(queue, &self.queue));
+    }
+}
+
+// runs the callbacks dispatched to this thread, called by the application as it processes pending work
+pub(crate) fn run_dispatched()
+{
+    let callbacks: Vec<DispatchedCallback> = DISPATCH_QUEUE.with(|queue| {
+        // cleared before draining so a dispatch racing with this one still wakes the loop
+        queue.wakeup_requested.store(false, Ordering::Release);
+        return lock(&queue.callbacks).drain(..).collect();
+    });
+
+    for callback in callbacks
+    {
+        callback();
+    }
+}
+
+pub(crate) fn has_dispatched() -> bool
+{
+    return DISPATCH_QUEUE.with(|queue| !lock(&queue.callbacks).is_empty());
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// a callback that panicked elsewhere never holds these locks, so a poisoned lock still holds good data
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T>
+{
+    return mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+}
@@ -15,15 +15,26 @@
 * MARK: EXTERNAL MODULES
 ***************************************************************/
 
+pub use nanowin::debug::*;
+
 pub use crate::Application;
 pub use crate::Window;
+pub use crate::Timer;
 
+use std::collections::{HashMap, VecDeque};
 use std::cell::RefCell;
+use std::time::{Duration, Instant};
 
 /***************************************************************
 * MARK: CHILD MODULES
 ***************************************************************/
 
+pub mod command;
+pub mod clock;
+pub mod dispatch;
+
+pub use dispatch::Dispatcher;
+
 /***************************************************************
 * MARK: TYPE DEFINITIONS
 ***************************************************************/
@@ -50,13 +61,30 @@ pub enum ApplicationQuitMode
     OnManualQuit
 }
 
+pub type TimerHandler = Box<dyn FnMut()>;
+pub type PostedCallback = Box<dyn FnOnce()>;
+
+struct TimerObject
+{
+    pub due: Instant,
+
+    // repeating timers are due again this long after they fire
+    pub interval: Option<Duration>,
+
+    pub handler: Option<TimerHandler>
+}
+
 struct ApplicationState
 {
     pub title: String,
     pub description: String,
     pub developer: String,
 
-    pub quit_mode: ApplicationQuitMode
+    pub quit_mode: ApplicationQuitMode,
+
+    pub next_timer: Timer,
+    pub timers: HashMap<Timer, TimerObject>,
+    pub posted: VecDeque<PostedCallback>
 }
 
 /***************************************************************
@@ -69,7 +97,10 @@ thread_local! {
             title: String::from(""),
             description: String::from(""),
             developer: String::from(""),
-            quit_mode: ApplicationQuitMode::OnLastWindowClose
+            quit_mode: ApplicationQuitMode::OnLastWindowClose,
+            next_timer: 1,
+            timers: HashMap::new(),
+            posted: VecDeque::new()
         }
     );
 }
@@ -78,12 +109,219 @@ thread_local! {
 * MARK: PUBLIC FUNCTIONS
 ***************************************************************/
 
+// runs the loop, which processes timers and posted callbacks whenever it is woken for them and before each frame
 pub fn run() -> i32
 {
+    crate::platform::set_wake_handler(wake);
+
     return nanowin::platform::platform::run();
 }
 
+// calls the handler once after delay, timers fire as the loop renders frames or is woken for them
+pub fn set_timeout<F: FnOnce() + 'static>(delay: Duration, handler: F) -> Timer
+{
+    let mut handler = Some(handler);
+
+    return add_timer(delay, None, Box::new(move || {
+        if let Some(handler) = handler.take()
+        {
+            handler();
+        }
+    }));
+}
+
+// calls the handler every interval until the timer is cancelled, a loop that falls behind skips the missed calls
+pub fn set_interval<F: FnMut() + 'static>(interval: Duration, handler: F) -> Timer
+{
+    return add_timer(interval, Some(interval), Box::new(handler));
+}
+
+// stops a timer, which can be done from inside its own handler
+pub fn cancel(timer: Timer)
+{
+    let removed = APP_STATE.with(|state| state.borrow_mut().timers.remove(&timer));
+
+    if removed.is_none()
+    {
+        log(LogLevel::WARN, &format!("Timer not found!"));
+    }
+}
+
+// runs the callback on the next turn of the loop, after the current event or frame has been handled,
+// other threads queue work through a Dispatcher instead
+pub fn post_to_main_thread<F: FnOnce() + 'static>(callback: F)
+{
+    APP_STATE.with(|state| state.borrow_mut().posted.push_back(Box::new(callback)));
+
+    crate::platform::wake();
+}
+
+// runs the callbacks dispatched from other threads, then the posted callbacks and the timers that are due,
+// called by windows before they render a frame and by the loop when it is woken
+pub fn process_pending()
+{
+    dispatch::run_dispatched();
+
+    // callbacks posted while these run wait for the next turn, so a callback that posts itself cannot stall the loop
+    let posted: Vec<PostedCallback> = APP_STATE.with(|state| state.borrow_mut().posted.drain(..).collect());
+
+    for callback in posted
+    {
+        callback();
+    }
+
+    let now = clock::now();
+
+    // timers due in this turn fire in order, ones added while they run wait for the next turn
+    let mut due: Vec<(Instant, Timer)> = APP_STATE.with(|state| {
+        return state.borrow().timers.iter()
+            .filter(|(_, timer_object)| timer_object.due <= now)
+            .map(|(timer, timer_object)| (timer_object.due, *timer))
+            .collect();
+    });
+
+    due.sort();
+
+    for (_, timer) in due
+    {
+        fire_timer(timer, now);
+    }
+
+    schedule_wake();
+}
+
+// true while timers are waiting or callbacks are posted or dispatched
+pub fn has_pending() -> bool
+{
+    let waiting = APP_STATE.with(|state| {
+        let state = state.borrow();
+        return !state.timers.is_empty() || !state.posted.is_empty();
+    });
+
+    return waiting || dispatch::has_dispatched();
+}
+
+// in virtual time, moves the manual clock forward and fires every timer due on the way at exactly the time it is due,
+// so tests run the same however long they take
+pub fn advance_time(duration: Duration)
+{
+    if !clock::is_manual()
+    {
+        log(LogLevel::WARN, &format!("Time can only be advanced while the clock is manual!"));
+        return;
+    }
+
+    let target = clock::now() + duration;
+
+    loop
+    {
+        let next_due = APP_STATE.with(|state| state.borrow().timers.values().map(|timer_object| timer_object.due).min());
+
+        match next_due
+        {
+            Some(next_due) if next_due <= target =>
+            {
+                clock::advance(next_due.saturating_duration_since(clock::now()));
+                process_pending();
+            },
+            _ => break
+        }
+    }
+
+    clock::advance(target.saturating_duration_since(clock::now()));
+    process_pending();
+}
+
 
 /***************************************************************
 * MARK: LOCAL FUNCTIONS
-***************************************************************/
\ No newline at end of file
+***************************************************************/
+
+fn add_timer(delay: Duration, interval: Option<Duration>, handler: TimerHandler) -> Timer
+{
+    let due = clock::now() + delay;
+
+    let new_timer = APP_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let new_timer = state.next_timer;
+        state.next_timer += 1;
+
+        state.timers.insert(new_timer, TimerObject {
+            due: due,
+            interval: interval,
+            handler: Some(handler)
+        });
+
+        return new_timer;
+    });
+
+    schedule_wake();
+
+    return new_timer;
+}
+
+// called by NanoWin's loop when it is woken, what the callbacks change is drawn in the frames this asks for
+fn wake()
+{
+    process_pending();
+
+    crate::window::request_redraw_all();
+}
+
+// has the loop woken when the next timer is due, virtual time is only moved on by advance_time
+fn schedule_wake()
+{
+    if clock::is_manual()
+    {
+        return;
+    }
+
+    let next_due = APP_STATE.with(|state| state.borrow().timers.values().map(|timer_object| timer_object.due).min());
+
+    if let Some(next_due) = next_due
+    {
+        crate::platform::wake_after(next_due.saturating_duration_since(clock::now()));
+    }
+}
+
+fn fire_timer(timer: Timer, now: Instant)
+{
+    // the handler is taken out while it runs so it can add and cancel timers
+    let handler = APP_STATE.with(|state| {
+        return state.borrow_mut().timers.get_mut(&timer).and_then(|timer_object| timer_object.handler.take());
+    });
+
+    let mut handler = match handler
+    {
+        Some(handler) => handler,
+        None => return
+    };
+
+    handler();
+
+    APP_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let interval = state.timers.get(&timer).map(|timer_object| timer_object.interval);
+
+        match interval
+        {
+            Some(Some(interval)) =>
+            {
+                if let Some(timer_object) = state.timers.get_mut(&timer)
+                {
+                    // an interval that fell behind picks up from now instead of firing the missed calls in a burst
+                    let next_due = timer_object.due + interval.max(Duration::from_millis(1));
+                    timer_object.due = if next_due <= now { now + interval } else { next_due };
+                    timer_object.handler = Some(handler);
+                }
+            },
+            Some(None) =>
+            {
+                state.timers.remove(&timer);
+            },
+            None => {}
+        }
+    });
+}
\ No newline at end of file
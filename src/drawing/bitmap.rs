@@ -0,0 +1,408 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  bitmap.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the software render target bitmap.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Color, Rect};
+
+use crate::drawing::raster::Mask;
+use crate::drawing::image::ImageSampling;
+use crate::drawing::brush::Shader;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// premultiplied RGBA8 pixel buffer used as the software render target
+pub struct Bitmap
+{
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>
+}
+
+// a width x height window of a single channel coverage buffer with the given row stride
+pub(crate) struct Coverage<'a>
+{
+    pub data: &'a [u8],
+    pub stride: usize,
+    pub width: usize,
+    pub height: usize
+}
+
+// integer pixel bounds, x1 and y1 are exclusive
+#[derive(Debug, Clone, Copy)]
+pub struct PixelBox
+{
+    pub x0: i32,
+    pub y0: i32,
+    pub x1: i32,
+    pub y1: i32
+}
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+impl Bitmap
+{
+    pub fn new(width: usize, height: usize) -> Bitmap
+    {
+        return Bitmap {
+            width: width,
+            height: height,
+            pixels: vec![0; width * height * 4]
+        };
+    }
+
+    pub fn bounds(&self) -> PixelBox
+    {
+        return PixelBox { x0: 0, y0: 0, x1: self.width as i32, y1: self.height as i32 };
+    }
+
+    pub fn clear(&mut self, color: Color)
+    {
+        let pixel = premultiply(color, 1.0);
+
+        for chunk in self.pixels.chunks_exact_mut(4)
+        {
+            chunk.copy_from_slice(&pixel);
+        }
+    }
+
+    // fills a rect with anti-aliased edges, only touching pixels inside the clip
+    pub fn fill_rect(&mut self, rect: Rect, color: Color, clip: PixelBox)
+    {
+        let left = rect.origin.x;
+        let top = rect.origin.y;
+        let right = rect.origin.x + rect.size.width;
+        let bottom = rect.origin.y + rect.size.height;
+
+        let bounds = PixelBox {
+            x0: left.floor() as i32,
+            y0: top.floor() as i32,
+            x1: right.ceil() as i32,
+            y1: bottom.ceil() as i32
+        }.intersect(clip).intersect(self.bounds());
+
+        for y in bounds.y0..bounds.y1
+        {
+            let coverage_y = span_coverage(top, bottom, y as f32);
+
+            for x in bounds.x0..bounds.x1
+            {
+                let coverage = coverage_y * span_coverage(left, right, x as f32);
+                self.blend_pixel(x, y, color, coverage);
+            }
+        }
+    }
+
+    // blends a coverage mask whose top left corner sits at (x, y)
+    pub(crate) fn fill_mask(&mut self, mask: &Mask, x: i32, y: i32, color: Color, clip: PixelBox)
+    {
+        self.fill_coverage(&Coverage { data: &mask.data, stride: mask.width, width: mask.width, height: mask.height }, x, y, color, clip);
+    }
+
+    // blends coverage whose top left corner sits at (x, y)
+    pub(crate) fn fill_coverage(&mut self, coverage: &Coverage, x: i32, y: i32, color: Color, clip: PixelBox)
+    {
+        let bounds = PixelBox {
+            x0: x,
+            y0: y,
+            x1: x + coverage.width as i32,
+            y1: y + coverage.height as i32
+        }.intersect(clip).intersect(self.bounds());
+
+        for pixel_y in bounds.y0..bounds.y1
+        {
+            let row = (pixel_y - y) as usize * coverage.stride;
+
+            for pixel_x in bounds.x0..bounds.x1
+            {
+                let coverage = coverage.data[row + (pixel_x - x) as usize];
+
+                if coverage > 0
+                {
+                    self.blend_pixel(pixel_x, pixel_y, color, coverage as f32 / 255.0);
+                }
+            }
+        }
+    }
+
+    // source-over blends source with its top left corner at x, y, faded by opacity
+    pub(crate) fn composite(&mut self, source: &Bitmap, x: i32, y: i32, opacity: f32)
+    {
+        let area = source.bounds().offset(x, y).intersect(self.bounds());
+        let opacity = opacity.clamp(0.0, 1.0);
+
+        for target_y in area.y0..area.y1
+        {
+            for target_x in area.x0..area.x1
+            {
+                let (source_x, source_y) = (target_x - x, target_y - y);
+                let index = (source_y as usize * source.width + source_x as usize) * 4;
+
+                if source.pixels[index + 3] > 0
+                {
+                    self.blend_premultiplied(target_x, target_y, source.premultiplied_at(source_x, source_y), opacity);
+                }
+            }
+        }
+    }
+
+    // like fill_mask, but each pixel takes its color from the shader
+    pub(crate) fn fill_mask_shaded(&mut self, mask: &Mask, x: i32, y: i32, shader: &Shader, clip: PixelBox)
+    {
+        let bounds = PixelBox {
+            x0: x,
+            y0: y,
+            x1: x + mask.width as i32,
+            y1: y + mask.height as i32
+        }.intersect(clip).intersect(self.bounds());
+
+        for pixel_y in bounds.y0..bounds.y1
+        {
+            let row = (pixel_y - y) as usize * mask.width;
+
+            for pixel_x in bounds.x0..bounds.x1
+            {
+                let coverage = mask.data[row + (pixel_x - x) as usize];
+
+                if coverage > 0
+                {
+                    let color = shader.shade(pixel_x as f32 + 0.5, pixel_y as f32 + 0.5);
+                    self.blend_premultiplied(pixel_x, pixel_y, color, coverage as f32 / 255.0);
+                }
+            }
+        }
+    }
+
+    // source-over blends a straight alpha color scaled by coverage
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: Color, coverage: f32)
+    {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32
+        {
+            return;
+        }
+
+        let alpha = (color.a * coverage).clamp(0.0, 1.0);
+
+        if alpha <= 0.0
+        {
+            return;
+        }
+
+        let index = (y as usize * self.width + x as usize) * 4;
+        let inverse = 1.0 - alpha;
+
+        let pixel = &mut self.pixels[index..index + 4];
+
+        pixel[0] = (color.r * alpha * 255.0 + pixel[0] as f32 * inverse).round().clamp(0.0, 255.0) as u8;
+        pixel[1] = (color.g * alpha * 255.0 + pixel[1] as f32 * inverse).round().clamp(0.0, 255.0) as u8;
+        pixel[2] = (color.b * alpha * 255.0 + pixel[2] as f32 * inverse).round().clamp(0.0, 255.0) as u8;
+        pixel[3] = (alpha * 255.0 + pixel[3] as f32 * inverse).round().clamp(0.0, 255.0) as u8;
+    }
+
+    // scales a premultiplied source bitmap into rect, with anti-aliased edges
+    pub(crate) fn draw_bitmap(&mut self, source: &Bitmap, rect: Rect, sampling: ImageSampling, clip: PixelBox)
+    {
+        if source.width == 0 || source.height == 0 || rect.size.width <= 0.0 || rect.size.height <= 0.0
+        {
+            return;
+        }
+
+        let left = rect.origin.x;
+        let top = rect.origin.y;
+        let right = rect.origin.x + rect.size.width;
+        let bottom = rect.origin.y + rect.size.height;
+
+        let bounds = PixelBox::from_rect(rect).intersect(clip).intersect(self.bounds());
+
+        // source pixels per destination pixel
+        let scale_x = source.width as f32 / rect.size.width;
+        let scale_y = source.height as f32 / rect.size.height;
+
+        for y in bounds.y0..bounds.y1
+        {
+            let coverage_y = span_coverage(top, bottom, y as f32);
+            let source_y = (y as f32 + 0.5 - top) * scale_y;
+
+            for x in bounds.x0..bounds.x1
+            {
+                let coverage = coverage_y * span_coverage(left, right, x as f32);
+
+                if coverage <= 0.0
+                {
+                    continue;
+                }
+
+                let source_x = (x as f32 + 0.5 - left) * scale_x;
+
+                let pixel = match sampling
+                {
+                    ImageSampling::Nearest => source.premultiplied_at(source_x.floor() as i32, source_y.floor() as i32),
+                    ImageSampling::Bilinear => source.sample_bilinear(source_x - 0.5, source_y - 0.5)
+                };
+
+                self.blend_premultiplied(x, y, pixel, coverage);
+            }
+        }
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Color
+    {
+        if x >= self.width || y >= self.height
+        {
+            return Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+        }
+
+        let index = (y * self.width + x) * 4;
+        let alpha = self.pixels[index + 3] as f32 / 255.0;
+
+        if alpha <= 0.0
+        {
+            return Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+        }
+
+        return Color {
+            r: self.pixels[index] as f32 / 255.0 / alpha,
+            g: self.pixels[index + 1] as f32 / 255.0 / alpha,
+            b: self.pixels[index + 2] as f32 / 255.0 / alpha,
+            a: alpha
+        };
+    }
+}
+
+impl PixelBox
+{
+    pub fn from_rect(rect: Rect) -> PixelBox
+    {
+        return PixelBox {
+            x0: rect.origin.x.floor() as i32,
+            y0: rect.origin.y.floor() as i32,
+            x1: (rect.origin.x + rect.size.width).ceil() as i32,
+            y1: (rect.origin.y + rect.size.height).ceil() as i32
+        };
+    }
+
+    pub fn intersect(&self, other: PixelBox) -> PixelBox
+    {
+        let x0 = self.x0.max(other.x0);
+        let y0 = self.y0.max(other.y0);
+
+        return PixelBox {
+            x0: x0,
+            y0: y0,
+            x1: self.x1.min(other.x1).max(x0),
+            y1: self.y1.min(other.y1).max(y0)
+        };
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        return self.x1 <= self.x0 || self.y1 <= self.y0;
+    }
+
+    pub fn offset(&self, x: i32, y: i32) -> PixelBox
+    {
+        return PixelBox { x0: self.x0 + x, y0: self.y0 + y, x1: self.x1 + x, y1: self.y1 + y };
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+impl Bitmap
+{
+    // premultiplied channels in 0..1, coordinates outside the bitmap are clamped to its edge
+    pub(crate) fn premultiplied_at(&self, x: i32, y: i32) -> [f32; 4]
+    {
+        let x = x.clamp(0, self.width as i32 - 1) as usize;
+        let y = y.clamp(0, self.height as i32 - 1) as usize;
+
+        let index = (y * self.width + x) * 4;
+        let pixel = &self.pixels[index..index + 4];
+
+        return [pixel[0] as f32 / 255.0, pixel[1] as f32 / 255.0, pixel[2] as f32 / 255.0, pixel[3] as f32 / 255.0];
+    }
+
+    // interpolates the four pixels around a point in pixel centre coordinates
+    pub(crate) fn sample_bilinear(&self, x: f32, y: f32) -> [f32; 4]
+    {
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let fraction_x = x - x0;
+        let fraction_y = y - y0;
+
+        let top_left = self.premultiplied_at(x0 as i32, y0 as i32);
+        let top_right = self.premultiplied_at(x0 as i32 + 1, y0 as i32);
+        let bottom_left = self.premultiplied_at(x0 as i32, y0 as i32 + 1);
+        let bottom_right = self.premultiplied_at(x0 as i32 + 1, y0 as i32 + 1);
+
+        let mut result = [0.0; 4];
+
+        for channel in 0..4
+        {
+            let top = top_left[channel] + (top_right[channel] - top_left[channel]) * fraction_x;
+            let bottom = bottom_left[channel] + (bottom_right[channel] - bottom_left[channel]) * fraction_x;
+            result[channel] = top + (bottom - top) * fraction_y;
+        }
+
+        return result;
+    }
+
+    // source-over blends a premultiplied color scaled by coverage
+    fn blend_premultiplied(&mut self, x: i32, y: i32, color: [f32; 4], coverage: f32)
+    {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32
+        {
+            return;
+        }
+
+        if color[3] * coverage <= 0.0
+        {
+            return;
+        }
+
+        let inverse = 1.0 - color[3] * coverage;
+
+        let index = (y as usize * self.width + x as usize) * 4;
+        let pixel = &mut self.pixels[index..index + 4];
+
+        for channel in 0..4
+        {
+            pixel[channel] = (color[channel] * coverage * 255.0 + pixel[channel] as f32 * inverse).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+fn premultiply(color: Color, coverage: f32) -> [u8; 4]
+{
+    let alpha = (color.a * coverage).clamp(0.0, 1.0);
+
+    return [
+        (color.r * alpha * 255.0).round().clamp(0.0, 255.0) as u8,
+        (color.g * alpha * 255.0).round().clamp(0.0, 255.0) as u8,
+        (color.b * alpha * 255.0).round().clamp(0.0, 255.0) as u8,
+        (alpha * 255.0).round() as u8
+    ];
+}
+
+// fraction of the pixel span [pixel, pixel + 1) covered by [start, end)
+fn span_coverage(start: f32, end: f32, pixel: f32) -> f32
+{
+    return (end.min(pixel + 1.0) - start.max(pixel)).clamp(0.0, 1.0);
+}
@@ -0,0 +1,317 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  brush.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains brushes, the solid colors, gradients and
+**                 image patterns that shapes are filled with.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Point, Color, Rect};
+
+pub use crate::Image;
+
+use crate::drawing::bitmap::Bitmap;
+use crate::drawing::image::ImageSampling;
+use crate::drawing::transform::Transform;
+
+use std::rc::Rc;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// a color at a position from 0 to 1 along a gradient
+#[derive(Clone, Copy)]
+pub struct ColorStop
+{
+    pub offset: f32,
+    pub color: Color
+}
+
+// gradients keep their end colors past their ends, points are in the coordinates of the shape being filled
+#[derive(Clone)]
+pub enum Brush
+{
+    Solid(Color),
+
+    // the gradient runs along the line from start to end and is constant across it
+    LinearGradient { start: Point, end: Point, stops: Vec<ColorStop> },
+
+    // the gradient runs outwards from the center and reaches its last stop at radius
+    RadialGradient { center: Point, radius: f32, stops: Vec<ColorStop> },
+
+    // one copy of the image fills tile, repeating in both directions
+    ImagePattern { image: Image, tile: Rect, sampling: ImageSampling }
+}
+
+// a brush prepared for shading many pixels, colors are premultiplied
+pub(crate) enum Shader
+{
+    Solid([f32; 4]),
+    Linear { start: Point, direction: Point, table: Vec<[f32; 4]> },
+    Radial { center: Point, radius: f32, table: Vec<[f32; 4]> },
+    Pattern { bitmap: Rc<Bitmap>, tile: Rect, sampling: ImageSampling, repeat: bool },
+
+    // shades with the inner shader at the point mapped back through a drawing transform
+    Transformed { shader: Box<Shader>, inverse: Transform }
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+// gradients are looked up in a table with this many entries
+const GRADIENT_TABLE_SIZE: usize = 256;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+impl Brush
+{
+    // the same brush with its points moved by offset, used to place brushes given relative to a view
+    pub fn translated(&self, offset: Point) -> Brush
+    {
+        let shift = |point: Point| -> Point { Point { x: point.x + offset.x, y: point.y + offset.y } };
+
+        return match self
+        {
+            Brush::Solid(color) => Brush::Solid(*color),
+            Brush::LinearGradient { start, end, stops } => Brush::LinearGradient { start: shift(*start), end: shift(*end), stops: stops.clone() },
+            Brush::RadialGradient { center, radius, stops } => Brush::RadialGradient { center: shift(*center), radius: *radius, stops: stops.clone() },
+            Brush::ImagePattern { image, tile, sampling } =>
+            {
+                Brush::ImagePattern { image: *image, tile: Rect { origin: shift(tile.origin), size: tile.size }, sampling: *sampling }
+            }
+        };
+    }
+
+    // true when nothing drawn with the brush can show
+    pub fn is_transparent(&self) -> bool
+    {
+        return match self
+        {
+            Brush::Solid(color) => color.a <= 0.0,
+            Brush::LinearGradient { stops, .. } | Brush::RadialGradient { stops, .. } => stops.iter().all(|stop| stop.color.a <= 0.0),
+            Brush::ImagePattern { .. } => false
+        };
+    }
+}
+
+impl From<Color> for Brush
+{
+    fn from(color: Color) -> Brush
+    {
+        return Brush::Solid(color);
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+impl Shader
+{
+    // none when the brush cannot draw, such as a gradient without stops or a missing image
+    pub(crate) fn new(brush: &Brush) -> Option<Shader>
+    {
+        match brush
+        {
+            Brush::Solid(color) => return Some(Shader::Solid(premultiply(*color))),
+            Brush::LinearGradient { start, end, stops } =>
+            {
+                let delta = Point { x: end.x - start.x, y: end.y - start.y };
+                let length_squared = delta.x * delta.x + delta.y * delta.y;
+
+                // a gradient without length shows its last color everywhere
+                if length_squared <= f32::EPSILON
+                {
+                    return stops.last().map(|stop| Shader::Solid(premultiply(stop.color)));
+                }
+
+                return Some(Shader::Linear {
+                    start: *start,
+                    direction: Point { x: delta.x / length_squared, y: delta.y / length_squared },
+                    table: build_table(stops)?
+                });
+            },
+            Brush::RadialGradient { center, radius, stops } =>
+            {
+                if *radius <= 0.0
+                {
+                    return stops.last().map(|stop| Shader::Solid(premultiply(stop.color)));
+                }
+
+                return Some(Shader::Radial { center: *center, radius: *radius, table: build_table(stops)? });
+            },
+            Brush::ImagePattern { image, tile, sampling } =>
+            {
+                if tile.size.width <= 0.0 || tile.size.height <= 0.0
+                {
+                    return None;
+                }
+
+                let bitmap = crate::drawing::image::get_bitmap(*image)?;
+
+                if bitmap.width == 0 || bitmap.height == 0
+                {
+                    return None;
+                }
+
+                return Some(Shader::Pattern { bitmap: bitmap, tile: *tile, sampling: *sampling, repeat: true });
+            }
+        }
+    }
+
+    // one copy of the bitmap stretched over rect with its edges clamped, used to draw images and layers under a transform
+    pub(crate) fn for_bitmap(bitmap: Rc<Bitmap>, rect: Rect, sampling: ImageSampling) -> Shader
+    {
+        return Shader::Pattern { bitmap: bitmap, tile: rect, sampling: sampling, repeat: false };
+    }
+
+    // the shader for points that went through transform, solid colors look the same everywhere
+    pub(crate) fn transformed(self, transform: &Transform) -> Option<Shader>
+    {
+        if let Shader::Solid(_) = self
+        {
+            return Some(self);
+        }
+
+        if transform.is_identity()
+        {
+            return Some(self);
+        }
+
+        return Some(Shader::Transformed { shader: Box::new(self), inverse: transform.invert()? });
+    }
+
+    // the premultiplied color at a point, pixels are shaded at their centers
+    pub(crate) fn shade(&self, x: f32, y: f32) -> [f32; 4]
+    {
+        match self
+        {
+            Shader::Solid(color) => return *color,
+            Shader::Linear { start, direction, table } =>
+            {
+                let t = (x - start.x) * direction.x + (y - start.y) * direction.y;
+                return lookup(table, t);
+            },
+            Shader::Radial { center, radius, table } =>
+            {
+                let t = ((x - center.x) * (x - center.x) + (y - center.y) * (y - center.y)).sqrt() / radius;
+                return lookup(table, t);
+            },
+            Shader::Pattern { bitmap, tile, sampling, repeat } =>
+            {
+                let u = (x - tile.origin.x) / tile.size.width;
+                let v = (y - tile.origin.y) / tile.size.height;
+
+                // position inside the tile in source pixels, wrapped when the pattern repeats
+                let (u, v) = if *repeat { (u.rem_euclid(1.0), v.rem_euclid(1.0)) } else { (u, v) };
+                let (u, v) = (u * bitmap.width as f32, v * bitmap.height as f32);
+
+                return match (sampling, repeat)
+                {
+                    (ImageSampling::Nearest, _) => bitmap.premultiplied_at(u.floor() as i32, v.floor() as i32),
+                    (ImageSampling::Bilinear, true) => sample_wrapped(bitmap, u - 0.5, v - 0.5),
+                    (ImageSampling::Bilinear, false) => bitmap.sample_bilinear(u - 0.5, v - 0.5)
+                };
+            },
+            Shader::Transformed { shader, inverse } =>
+            {
+                let point = inverse.apply(Point { x: x, y: y });
+                return shader.shade(point.x, point.y);
+            }
+        }
+    }
+}
+
+fn premultiply(color: Color) -> [f32; 4]
+{
+    let alpha = color.a.clamp(0.0, 1.0);
+    return [color.r * alpha, color.g * alpha, color.b * alpha, alpha];
+}
+
+// samples the gradient at evenly spaced positions, interpolating premultiplied colors so transparent stops do not darken their neighbours
+fn build_table(stops: &[ColorStop]) -> Option<Vec<[f32; 4]>>
+{
+    if stops.is_empty()
+    {
+        return None;
+    }
+
+    // stops out of order are moved up to the one before, as CSS does
+    let mut sorted: Vec<(f32, [f32; 4])> = Vec::with_capacity(stops.len());
+    let mut last_offset: f32 = 0.0;
+
+    for stop in stops
+    {
+        last_offset = stop.offset.clamp(0.0, 1.0).max(last_offset);
+        sorted.push((last_offset, premultiply(stop.color)));
+    }
+
+    let table = (0..GRADIENT_TABLE_SIZE).map(|index| {
+        let t = index as f32 / (GRADIENT_TABLE_SIZE - 1) as f32;
+
+        let next = sorted.iter().position(|(offset, _)| *offset > t);
+
+        return match next
+        {
+            None => sorted[sorted.len() - 1].1,
+            Some(0) => sorted[0].1,
+            Some(next) =>
+            {
+                let (from_offset, from) = sorted[next - 1];
+                let (to_offset, to) = sorted[next];
+                let amount = (t - from_offset) / (to_offset - from_offset);
+
+                [0, 1, 2, 3].map(|channel| from[channel] + (to[channel] - from[channel]) * amount)
+            }
+        };
+    }).collect();
+
+    return Some(table);
+}
+
+fn lookup(table: &[[f32; 4]], t: f32) -> [f32; 4]
+{
+    let index = (t.clamp(0.0, 1.0) * (table.len() - 1) as f32).round() as usize;
+    return table[index];
+}
+
+// bilinear sampling that wraps around the edges so tiles join without seams
+fn sample_wrapped(bitmap: &Bitmap, x: f32, y: f32) -> [f32; 4]
+{
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let fraction_x = x - x0;
+    let fraction_y = y - y0;
+
+    let wrap = |value: f32, size: usize| -> i32 { (value as i32).rem_euclid(size as i32) };
+
+    let left = wrap(x0, bitmap.width);
+    let right = wrap(x0 + 1.0, bitmap.width);
+    let top = wrap(y0, bitmap.height);
+    let bottom = wrap(y0 + 1.0, bitmap.height);
+
+    let top_left = bitmap.premultiplied_at(left, top);
+    let top_right = bitmap.premultiplied_at(right, top);
+    let bottom_left = bitmap.premultiplied_at(left, bottom);
+    let bottom_right = bitmap.premultiplied_at(right, bottom);
+
+    return [0, 1, 2, 3].map(|channel| {
+        let upper = top_left[channel] + (top_right[channel] - top_left[channel]) * fraction_x;
+        let lower = bottom_left[channel] + (bottom_right[channel] - bottom_left[channel]) * fraction_x;
+        upper + (lower - upper) * fraction_y
+    });
+}
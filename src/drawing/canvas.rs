@@ -0,0 +1,168 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  canvas.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the Canvas, which custom-drawn views
+**                 paint into using their own coordinates.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect};
+
+pub use crate::{Image, Svg};
+
+use crate::drawing::{DrawingContext, Brush, Path, StrokeStyle, FillRule, FontDescriptor, AttributedString, ImageSampling, CornerRadii, Transform};
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// draws into a view with its top left corner at 0, 0, clipped to the view
+pub struct Canvas<'a>
+{
+    context: &'a mut DrawingContext,
+    rect: Rect,
+
+    // clips pushed through the canvas, popped when it is dropped so a paint handler cannot leave the stack unbalanced
+    clip_depth: usize
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+impl<'a> Canvas<'a>
+{
+    // rect is the view's rect in the context's current coordinates
+    pub(crate) fn new(context: &'a mut DrawingContext, rect: Rect) -> Canvas<'a>
+    {
+        context.push_clip(rect);
+        context.push_transform(Transform::translation(rect.origin.x, rect.origin.y));
+
+        return Canvas {
+            context: context,
+            rect: rect,
+            clip_depth: 0
+        };
+    }
+
+    pub fn size(&self) -> Size
+    {
+        return self.rect.size;
+    }
+
+    // the whole view in canvas coordinates
+    pub fn bounds(&self) -> Rect
+    {
+        return Rect { origin: Point { x: 0.0, y: 0.0 }, size: self.rect.size };
+    }
+
+    // where the view was laid out, for the rare drawing that needs it
+    pub fn view_rect(&self) -> Rect
+    {
+        return self.rect;
+    }
+
+    // the part of the view that can currently be drawn to, anything outside it can be skipped
+    pub fn clip_rect(&self) -> Rect
+    {
+        return self.context.clip_rect();
+    }
+
+    pub fn push_clip(&mut self, rect: Rect)
+    {
+        self.context.push_clip(rect);
+        self.clip_depth += 1;
+    }
+
+    pub fn pop_clip(&mut self)
+    {
+        if self.clip_depth > 0
+        {
+            self.context.pop_clip();
+            self.clip_depth -= 1;
+        }
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, brush: impl Into<Brush>)
+    {
+        match brush.into()
+        {
+            Brush::Solid(color) => self.context.fill_rect(rect, color),
+            brush =>
+            {
+                let mut path = Path::new();
+                path.add_rect(rect);
+                self.context.fill_path(&path, FillRule::NonZero, brush);
+            }
+        }
+    }
+
+    pub fn fill_path(&mut self, path: &Path, fill_rule: FillRule, brush: impl Into<Brush>)
+    {
+        self.context.fill_path(path, fill_rule, brush);
+    }
+
+    pub fn stroke_path(&mut self, path: &Path, style: &StrokeStyle, brush: impl Into<Brush>)
+    {
+        self.context.stroke_path(path, style, brush);
+    }
+
+    // draws text with the top of its first line at origin, returns the pen position after the last glyph
+    pub fn draw_text(&mut self, origin: Point, text: &str, descriptor: &FontDescriptor, color: Color) -> Point
+    {
+        return self.context.draw_text(origin, text, descriptor, color);
+    }
+
+    pub fn draw_attributed_text(&mut self, origin: Point, text: &AttributedString, base_font: &FontDescriptor, base_color: Color)
+    {
+        self.context.draw_attributed_text(origin, text, base_font, base_color);
+    }
+
+    pub fn draw_image(&mut self, rect: Rect, image: Image, sampling: ImageSampling)
+    {
+        self.context.draw_image(rect, image, sampling);
+    }
+
+    pub fn draw_svg(&mut self, rect: Rect, svg: Svg, color: Color)
+    {
+        self.context.draw_svg(rect, svg, color);
+    }
+
+    pub fn draw_shadow(&mut self, rect: Rect, radii: CornerRadii, blur: f32, color: Color)
+    {
+        self.context.draw_shadow(rect, radii, blur, color, None);
+    }
+}
+
+impl Drop for Canvas<'_>
+{
+    fn drop(&mut self)
+    {
+        while self.clip_depth > 0
+        {
+            self.pop_clip();
+        }
+
+        // the translation and clip to the view itself
+        self.context.pop_transform();
+        self.context.pop_clip();
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
@@ -0,0 +1,651 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  font.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains font loading, glyph rasterization and
+**                 the glyph atlas cache.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, debug::*};
+
+pub use crate::Font;
+
+use crate::drawing::bitmap::{Bitmap, PixelBox, Coverage};
+use crate::drawing::raster::{Rasterizer, FillRule};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FontWeight
+{
+    Thin,
+    ExtraLight,
+    Light,
+    Regular,
+    Medium,
+    SemiBold,
+    Bold,
+    ExtraBold,
+    Black
+}
+
+// describes which font a view wants, resolved against the loaded fonts at draw time
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontDescriptor
+{
+    pub family: String,
+    pub size: f32,
+    pub weight: FontWeight,
+    pub italic: bool
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FontMetrics
+{
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_height: f32,
+
+    // decoration offsets are measured from the baseline, positive values point down
+    pub underline_offset: f32,
+    pub strikeout_offset: f32,
+    pub line_thickness: f32
+}
+
+struct FontObject
+{
+    // parsed once when the font is loaded, fonts stay loaded for the life of the application so the face can borrow its data forever
+    pub face: ttf_parser::Face<'static>,
+
+    pub family: String,
+    pub weight: u16,
+    pub italic: bool
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey
+{
+    pub font: Font,
+    pub glyph: u16,
+    pub size: u32
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AtlasEntry
+{
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+
+    // offset of the bitmap from the pen position on the baseline
+    pub left: i32,
+    pub top: i32
+}
+
+// single channel coverage texture packed with shelves of glyphs
+struct GlyphAtlas
+{
+    pub data: Vec<u8>,
+    pub entries: HashMap<GlyphKey, AtlasEntry>,
+
+    pub shelf_x: usize,
+    pub shelf_y: usize,
+    pub shelf_height: usize
+}
+
+struct FontState
+{
+    pub font_id_allocator: Font,
+    pub fonts: HashMap<Font, FontObject>,
+    pub default_font: Option<Font>,
+    pub atlas: GlyphAtlas
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static FONT_STATE: RefCell<FontState> = RefCell::new(FontState {
+        font_id_allocator: 1,
+        fonts: HashMap::new(),
+        default_font: None,
+        atlas: GlyphAtlas {
+            data: vec![0; ATLAS_SIZE * ATLAS_SIZE],
+            entries: HashMap::new(),
+            shelf_x: 0,
+            shelf_y: 0,
+            shelf_height: 0
+        }
+    });
+}
+
+pub const DEFAULT_FONT_SIZE: f32 = 14.0;
+
+const ATLAS_SIZE: usize = 1024;
+const ATLAS_PADDING: usize = 1;
+
+// glyph sizes are cached in quarter pixel steps
+const SIZE_QUANTIZATION: f32 = 4.0;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+impl FontWeight
+{
+    pub fn to_number(&self) -> u16
+    {
+        return match self
+        {
+            FontWeight::Thin => 100,
+            FontWeight::ExtraLight => 200,
+            FontWeight::Light => 300,
+            FontWeight::Regular => 400,
+            FontWeight::Medium => 500,
+            FontWeight::SemiBold => 600,
+            FontWeight::Bold => 700,
+            FontWeight::ExtraBold => 800,
+            FontWeight::Black => 900
+        };
+    }
+}
+
+impl Default for FontDescriptor
+{
+    fn default() -> Self
+    {
+        return FontDescriptor {
+            family: String::from(""),
+            size: DEFAULT_FONT_SIZE,
+            weight: FontWeight::Regular,
+            italic: false
+        };
+    }
+}
+
+pub fn load_file(path: &str) -> Option<Font>
+{
+    match std::fs::read(path)
+    {
+        Ok(data) => return load_bytes(data),
+        Err(error) =>
+        {
+            log(LogLevel::FAIL, &format!("Failed to read font file {}: {}", path, error));
+            return None;
+        }
+    }
+}
+
+// loads a TTF or OTF font, the first font loaded becomes the default
+pub fn load_bytes(data: Vec<u8>) -> Option<Font>
+{
+    if let Err(error) = ttf_parser::Face::parse(&data, 0)
+    {
+        log(LogLevel::FAIL, &format!("Failed to parse font: {}", error));
+        return None;
+    }
+
+    let data: &'static [u8] = Box::leak(data.into_boxed_slice());
+
+    let face = match ttf_parser::Face::parse(data, 0)
+    {
+        Ok(face) => face,
+        Err(_) => return None
+    };
+
+    let family = face.names()
+        .into_iter()
+        .filter(|name| name.name_id == ttf_parser::name_id::FAMILY)
+        .find_map(|name| name.to_string())
+        .unwrap_or(String::from(""));
+
+    let weight = face.weight().to_number();
+    let italic = face.is_italic();
+
+    let new_font = FONT_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let new_font_id = state.font_id_allocator;
+        state.font_id_allocator += 1;
+
+        state.fonts.insert(new_font_id, FontObject {
+            face: face,
+            family: family.clone(),
+            weight: weight,
+            italic: italic
+        });
+
+        if state.default_font.is_none()
+        {
+            state.default_font = Some(new_font_id);
+        }
+
+        return new_font_id;
+    });
+
+    log(LogLevel::INFO, &format!("Loaded font {} ({} {}) with ID: {}", family, weight, if italic { "italic" } else { "upright" }, new_font));
+
+    return Some(new_font);
+}
+
+pub fn set_default(font: Font)
+{
+    FONT_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if state.fonts.contains_key(&font)
+        {
+            state.default_font = Some(font);
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Font not found!"));
+        }
+    });
+}
+
+pub fn get_default() -> Option<Font>
+{
+    return FONT_STATE.with(|state| {
+        return state.borrow().default_font;
+    });
+}
+
+pub fn get_family(font: Font) -> String
+{
+    return FONT_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(font_object) = state.fonts.get(&font)
+        {
+            return font_object.family.clone();
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Font not found!"));
+            return String::from("");
+        }
+    });
+}
+
+// picks the loaded font closest to the descriptor, falling back to the default font
+pub fn find(descriptor: &FontDescriptor) -> Option<Font>
+{
+    return FONT_STATE.with(|state| {
+        let state = state.borrow();
+
+        let requested_weight = descriptor.weight.to_number() as i32;
+
+        // an empty family selects other weights and styles of the default font's family
+        let family = if descriptor.family.is_empty()
+        {
+            state.default_font
+                .and_then(|font| state.fonts.get(&font))
+                .map(|font_object| font_object.family.clone())
+                .unwrap_or(String::from(""))
+        }
+        else
+        {
+            descriptor.family.clone()
+        };
+
+        let best = state.fonts.iter()
+            .filter(|(_, font_object)| font_object.family.eq_ignore_ascii_case(&family))
+            .min_by_key(|(id, font_object)| {
+                let italic_penalty = if font_object.italic == descriptor.italic { 0 } else { 1000 };
+                (italic_penalty + (font_object.weight as i32 - requested_weight).abs(), **id)
+            })
+            .map(|(id, _)| *id);
+
+        return best.or(state.default_font);
+    });
+}
+
+pub fn get_metrics(descriptor: &FontDescriptor) -> FontMetrics
+{
+    let empty = FontMetrics {
+        ascent: descriptor.size,
+        descent: 0.0,
+        line_height: descriptor.size,
+        underline_offset: descriptor.size / 10.0,
+        strikeout_offset: -descriptor.size / 3.0,
+        line_thickness: descriptor.size / 14.0
+    };
+
+    let font = match find(descriptor)
+    {
+        Some(font) => font,
+        None => return empty
+    };
+
+    return FONT_STATE.with(|state| {
+        let state = state.borrow();
+
+        return match state.fonts.get(&font)
+        {
+            Some(font_object) => face_metrics(&font_object.face, descriptor.size),
+            None => empty
+        };
+    });
+}
+
+pub fn measure_text(text: &str, descriptor: &FontDescriptor) -> Size
+{
+    let metrics = get_metrics(descriptor);
+
+    let mut width: f32 = 0.0;
+    let mut lines = 0;
+
+    for line in text.split('\n')
+    {
+        width = width.max(measure_run(line, descriptor));
+        lines += 1;
+    }
+
+    return Size { width: width, height: metrics.line_height * lines as f32 };
+}
+
+// measures the advance of a single line of text
+pub fn measure_run(text: &str, descriptor: &FontDescriptor) -> f32
+{
+    let font = match find(descriptor)
+    {
+        Some(font) => font,
+        None => return 0.0
+    };
+
+    return FONT_STATE.with(|state| {
+        let state = state.borrow();
+
+        let face = match state.fonts.get(&font)
+        {
+            Some(font_object) => &font_object.face,
+            None => return 0.0
+        };
+
+        let scale = descriptor.size / face.units_per_em() as f32;
+
+        return text.chars().map(|character| glyph_advance(face, character) * scale).sum();
+    });
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// draws text with its first baseline placed one ascent below origin, returns the pen position
+pub(crate) fn draw_text(bitmap: &mut Bitmap, clip: PixelBox, origin: Point, text: &str, descriptor: &FontDescriptor, color: Color) -> Point
+{
+    let metrics = get_metrics(descriptor);
+
+    let mut pen = Point { x: origin.x, y: origin.y + metrics.ascent };
+
+    for (index, line) in text.split('\n').enumerate()
+    {
+        if index > 0
+        {
+            pen.x = origin.x;
+            pen.y += metrics.line_height;
+        }
+
+        pen.x += draw_run(bitmap, clip, pen, line, descriptor, color);
+    }
+
+    return pen;
+}
+
+// draws a single line of text starting at a pen position on the baseline, returns its advance
+pub(crate) fn draw_run(bitmap: &mut Bitmap, clip: PixelBox, baseline: Point, text: &str, descriptor: &FontDescriptor, color: Color) -> f32
+{
+    let font = match find(descriptor)
+    {
+        Some(font) => font,
+        None =>
+        {
+            log(LogLevel::WARN, &format!("No font loaded, text will not be drawn!"));
+            return 0.0;
+        }
+    };
+
+    return FONT_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let state = &mut *state;
+
+        let face = match state.fonts.get(&font)
+        {
+            Some(font_object) => &font_object.face,
+            None => return 0.0
+        };
+
+        let scale = descriptor.size / face.units_per_em() as f32;
+
+        // glyphs are shared by every size that rounds to the same key, so they are drawn at that size, while pens advance at the exact one
+        let size_key = (descriptor.size * SIZE_QUANTIZATION).round() as u32;
+        let glyph_scale = size_key as f32 / SIZE_QUANTIZATION / face.units_per_em() as f32;
+
+        let mut pen_x = baseline.x;
+
+        for character in text.chars()
+        {
+            let glyph = face.glyph_index(character).unwrap_or(ttf_parser::GlyphId(0));
+            let key = GlyphKey { font: font, glyph: glyph.0, size: size_key };
+
+            if let Some(entry) = state.atlas.get_or_insert(key, face, glyph_scale)
+            {
+                let offset = entry.y * ATLAS_SIZE + entry.x;
+
+                let coverage = Coverage { data: &state.atlas.data[offset..], stride: ATLAS_SIZE, width: entry.width, height: entry.height };
+
+                bitmap.fill_coverage(
+                    &coverage,
+                    pen_x.round() as i32 + entry.left,
+                    baseline.y.round() as i32 + entry.top,
+                    color,
+                    clip
+                );
+            }
+
+            pen_x += glyph_advance(face, character) * scale;
+        }
+
+        return pen_x - baseline.x;
+    });
+}
+
+fn face_metrics(face: &ttf_parser::Face, size: f32) -> FontMetrics
+{
+    let scale = size / face.units_per_em() as f32;
+
+    let ascent = face.ascender() as f32 * scale;
+    let descent = -(face.descender() as f32) * scale;
+
+    let thickness = face.underline_metrics()
+        .map(|metrics| metrics.thickness as f32 * scale)
+        .unwrap_or(size / 14.0)
+        .max(1.0);
+
+    let underline_offset = face.underline_metrics()
+        .map(|metrics| -(metrics.position as f32) * scale)
+        .unwrap_or(descent / 2.0);
+
+    let strikeout_offset = face.strikeout_metrics()
+        .map(|metrics| -(metrics.position as f32) * scale)
+        .unwrap_or(-ascent / 3.0);
+
+    return FontMetrics {
+        ascent: ascent,
+        descent: descent,
+        line_height: ascent + descent + face.line_gap() as f32 * scale,
+        underline_offset: underline_offset,
+        strikeout_offset: strikeout_offset,
+        line_thickness: thickness
+    };
+}
+
+fn glyph_advance(face: &ttf_parser::Face, character: char) -> f32
+{
+    let glyph = face.glyph_index(character).unwrap_or(ttf_parser::GlyphId(0));
+
+    return face.glyph_hor_advance(glyph).unwrap_or(0) as f32;
+}
+
+impl GlyphAtlas
+{
+    fn get_or_insert(&mut self, key: GlyphKey, face: &ttf_parser::Face, scale: f32) -> Option<AtlasEntry>
+    {
+        if let Some(entry) = self.entries.get(&key)
+        {
+            return Some(*entry);
+        }
+
+        let bounds = face.glyph_bounding_box(ttf_parser::GlyphId(key.glyph))?;
+
+        let left = (bounds.x_min as f32 * scale).floor() as i32;
+        let top = (-bounds.y_max as f32 * scale).floor() as i32;
+        let right = (bounds.x_max as f32 * scale).ceil() as i32;
+        let bottom = (-bounds.y_min as f32 * scale).ceil() as i32;
+
+        let width = (right - left).max(0) as usize;
+        let height = (bottom - top).max(0) as usize;
+
+        if width == 0 || height == 0 || width + ATLAS_PADDING > ATLAS_SIZE || height + ATLAS_PADDING > ATLAS_SIZE
+        {
+            return None;
+        }
+
+        let (x, y) = match self.allocate(width, height)
+        {
+            Some(position) => position,
+            None =>
+            {
+                // the atlas is full, evict everything and re-rasterize glyphs on demand
+                self.clear();
+                self.allocate(width, height)?
+            }
+        };
+
+        let mut builder = GlyphBuilder {
+            rasterizer: Rasterizer::new(width, height),
+            scale: scale,
+            offset_x: left as f32,
+            offset_y: top as f32
+        };
+
+        face.outline_glyph(ttf_parser::GlyphId(key.glyph), &mut builder);
+
+        let mask = builder.rasterizer.rasterize(FillRule::NonZero);
+
+        for row in 0..height
+        {
+            let source = row * width;
+            let destination = (y + row) * ATLAS_SIZE + x;
+
+            self.data[destination..destination + width].copy_from_slice(&mask.data[source..source + width]);
+        }
+
+        let entry = AtlasEntry { x: x, y: y, width: width, height: height, left: left, top: top };
+        self.entries.insert(key, entry);
+
+        return Some(entry);
+    }
+
+    fn allocate(&mut self, width: usize, height: usize) -> Option<(usize, usize)>
+    {
+        if self.shelf_x + width + ATLAS_PADDING > ATLAS_SIZE
+        {
+            self.shelf_x = 0;
+            self.shelf_y += self.shelf_height;
+            self.shelf_height = 0;
+        }
+
+        if self.shelf_y + height + ATLAS_PADDING > ATLAS_SIZE
+        {
+            return None;
+        }
+
+        let position = (self.shelf_x, self.shelf_y);
+
+        self.shelf_x += width + ATLAS_PADDING;
+        self.shelf_height = self.shelf_height.max(height + ATLAS_PADDING);
+
+        return Some(position);
+    }
+
+    fn clear(&mut self)
+    {
+        self.data.iter_mut().for_each(|value| *value = 0);
+        self.entries.clear();
+        self.shelf_x = 0;
+        self.shelf_y = 0;
+        self.shelf_height = 0;
+    }
+}
+
+// converts font units with y pointing up into atlas pixels with y pointing down
+struct GlyphBuilder
+{
+    pub rasterizer: Rasterizer,
+    pub scale: f32,
+    pub offset_x: f32,
+    pub offset_y: f32
+}
+
+impl GlyphBuilder
+{
+    fn point(&self, x: f32, y: f32) -> Point
+    {
+        return Point { x: x * self.scale - self.offset_x, y: -y * self.scale - self.offset_y };
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphBuilder
+{
+    fn move_to(&mut self, x: f32, y: f32)
+    {
+        let point = self.point(x, y);
+        self.rasterizer.move_to(point);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32)
+    {
+        let point = self.point(x, y);
+        self.rasterizer.line_to(point);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32)
+    {
+        let control = self.point(x1, y1);
+        let point = self.point(x, y);
+        self.rasterizer.quad_to(control, point);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32)
+    {
+        let control1 = self.point(x1, y1);
+        let control2 = self.point(x2, y2);
+        let point = self.point(x, y);
+        self.rasterizer.cubic_to(control1, control2, point);
+    }
+
+    fn close(&mut self)
+    {
+        self.rasterizer.close();
+    }
+}
@@ -0,0 +1,227 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  image.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains image decoding and the cache of decoded
+**                 images used by the software renderer.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Rect, debug::*};
+
+pub use crate::{Image, Window};
+
+use crate::drawing::bitmap::Bitmap;
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// how the renderer picks source pixels when an image is drawn at a different size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageSampling
+{
+    Nearest,
+    Bilinear
+}
+
+struct ImageObject
+{
+    // decoded once into premultiplied RGBA, shared with draw calls while they run
+    pub bitmap: Rc<Bitmap>,
+
+    // the cache key the image was loaded under, removed again when it is unloaded
+    pub source: ImageSource
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ImageSource
+{
+    File(String),
+
+    // the encoded bytes themselves, so a lookup that matches a hash still compares every byte
+    Bytes(Rc<[u8]>)
+}
+
+struct ImageState
+{
+    pub image_id_allocator: Image,
+    pub images: HashMap<Image, ImageObject>,
+    pub cache: HashMap<ImageSource, Image>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static IMAGE_STATE: RefCell<ImageState> = RefCell::new(ImageState {
+        image_id_allocator: 1,
+        images: HashMap::new(),
+        cache: HashMap::new()
+    });
+}
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+// loads a PNG or JPEG file, loading the same path again returns the cached image
+pub fn load_file(path: &str) -> Option<Image>
+{
+    let source = ImageSource::File(String::from(path));
+
+    if let Some(image) = find_cached(&source)
+    {
+        return Some(image);
+    }
+
+    match std::fs::read(path)
+    {
+        Ok(data) => return decode(&data, source),
+        Err(error) =>
+        {
+            log(LogLevel::FAIL, &format!("Failed to read image file {}: {}", path, error));
+            return None;
+        }
+    }
+}
+
+// loads a PNG or JPEG from memory, identical bytes return the cached image
+pub fn load_bytes(data: &[u8]) -> Option<Image>
+{
+    let source = ImageSource::Bytes(Rc::from(data));
+
+    if let Some(image) = find_cached(&source)
+    {
+        return Some(image);
+    }
+
+    return decode(data, source);
+}
+
+// frees the decoded pixels, views still showing the image draw nothing
+pub fn unload(image: Image)
+{
+    IMAGE_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        match state.images.remove(&image)
+        {
+            Some(image_object) =>
+            {
+                state.cache.remove(&image_object.source);
+            },
+            None => log(LogLevel::FAIL, &format!("Image not found!"))
+        }
+    });
+}
+
+pub fn is_loaded(image: Image) -> bool
+{
+    return IMAGE_STATE.with(|state| state.borrow().images.contains_key(&image));
+}
+
+// the size of the image in pixels
+pub fn get_size(image: Image) -> Size
+{
+    return match get_bitmap(image)
+    {
+        Some(bitmap) => Size { width: bitmap.width as f32, height: bitmap.height as f32 },
+        None => Size { width: 0.0, height: 0.0 }
+    };
+}
+
+// sends the image straight to nanowin, stretched over rect, without going through a drawing context
+pub fn draw_to_window(window: Window, rect: Rect, image: Image)
+{
+    if let Some(bitmap) = get_bitmap(image)
+    {
+        crate::platform::draw_bitmap(window, rect, &bitmap);
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn find_cached(source: &ImageSource) -> Option<Image>
+{
+    return IMAGE_STATE.with(|state| state.borrow().cache.get(source).copied());
+}
+
+fn decode(data: &[u8], source: ImageSource) -> Option<Image>
+{
+    let decoded = match image::load_from_memory(data)
+    {
+        Ok(decoded) => decoded.to_rgba8(),
+        Err(error) =>
+        {
+            log(LogLevel::FAIL, &format!("Failed to decode image: {}", error));
+            return None;
+        }
+    };
+
+    let width = decoded.width() as usize;
+    let height = decoded.height() as usize;
+
+    let mut pixels = decoded.into_raw();
+
+    for pixel in pixels.chunks_exact_mut(4)
+    {
+        let alpha = pixel[3] as u32;
+
+        for value in pixel[..3].iter_mut()
+        {
+            *value = ((*value as u32 * alpha + 127) / 255) as u8;
+        }
+    }
+
+    let new_image = IMAGE_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let new_image_id = state.image_id_allocator;
+        state.image_id_allocator += 1;
+
+        state.images.insert(new_image_id, ImageObject {
+            bitmap: Rc::new(Bitmap { width: width, height: height, pixels: pixels }),
+            source: source.clone()
+        });
+
+        state.cache.insert(source, new_image_id);
+
+        return new_image_id;
+    });
+
+    log(LogLevel::INFO, &format!("Loaded {}x{} image with ID: {}", width, height, new_image));
+
+    return Some(new_image);
+}
+
+pub(crate) fn get_bitmap(image: Image) -> Option<Rc<Bitmap>>
+{
+    return IMAGE_STATE.with(|state| {
+        if let Some(image_object) = state.borrow().images.get(&image)
+        {
+            return Some(image_object.bitmap.clone());
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Image not found!"));
+            return None;
+        }
+    });
+}
@@ -15,22 +15,647 @@
 * MARK: EXTERNAL MODULES
 ***************************************************************/
 
+pub use nanowin::{Size, Point, Color, Rect};
+
+pub use crate::{Image, Svg, Window};
+
+use std::rc::Rc;
+
 /***************************************************************
 * MARK: CHILD MODULES
 ***************************************************************/
 
+pub mod bitmap;
+pub mod raster;
+pub mod font;
+pub mod text;
+pub mod image;
+pub mod transform;
+pub mod path;
+pub mod svg;
+pub mod brush;
+pub mod canvas;
+
+pub use bitmap::{Bitmap, PixelBox};
+pub use raster::{FillRule, Rasterizer, Mask};
+pub use font::{FontDescriptor, FontWeight, FontMetrics};
+pub use text::{AttributedString, TextAttributes, TextRun};
+pub use image::ImageSampling;
+pub use transform::Transform;
+pub use path::{Path, PathCommand, StrokeStyle, LineJoin, LineCap, CornerRadii};
+pub use brush::{Brush, ColorStop};
+pub use canvas::Canvas;
+
 /***************************************************************
 * MARK: TYPE DEFINITIONS
 ***************************************************************/
 
+// software render target handed down the view tree while rendering
+pub struct DrawingContext
+{
+    bitmap: Bitmap,
+    clip_stack: Vec<PixelBox>,
+
+    // each entry maps drawing coordinates to pixels, combined with the entries below it
+    transform_stack: Vec<Transform>,
+
+    // the targets that were being drawn to when each layer was pushed, with the layer's opacity and the target's origin
+    layer_stack: Vec<(Bitmap, f32, (i32, i32))>,
+
+    // where pixel 0, 0 of the current bitmap sits in the window, layers only cover the clip they were pushed with
+    origin: (i32, i32)
+}
+
 /***************************************************************
 * MARK: STATIC VARIABLES & CONSTANTS
 ***************************************************************/
 
+// transparent pixels kept around layers so their edges resample cleanly
+const LAYER_PADDING: usize = 2;
+
+// largest side of a layer in pixels, deeper zooms draw the layer at a lower resolution
+const MAX_LAYER_SIZE: f32 = 4096.0;
+
 /***************************************************************
 * MARK: PUBLIC FUNCTIONS
 ***************************************************************/
 
+impl DrawingContext
+{
+    pub fn new(size: Size) -> DrawingContext
+    {
+        let bitmap = Bitmap::new(size.width.max(0.0).ceil() as usize, size.height.max(0.0).ceil() as usize);
+
+        return DrawingContext {
+            clip_stack: vec![bitmap.bounds()],
+            transform_stack: vec![Transform::identity()],
+            layer_stack: Vec::new(),
+            origin: (0, 0),
+            bitmap: bitmap
+        };
+    }
+
+    pub fn size(&self) -> Size
+    {
+        let bitmap = self.window_bitmap();
+        return Size { width: bitmap.width as f32, height: bitmap.height as f32 };
+    }
+
+    pub fn bitmap(&self) -> &Bitmap
+    {
+        return self.window_bitmap();
+    }
+
+    pub fn clear(&mut self, color: Color)
+    {
+        self.bitmap.clear(color);
+    }
+
+    // restricts drawing to the intersection of rect and the current clip, under a rotation this is the box around the turned rect
+    pub fn push_clip(&mut self, rect: Rect)
+    {
+        let clip = self.window_clip().intersect(PixelBox::from_rect(transform_bounds(&self.window_transform(), rect)));
+        self.clip_stack.push(clip);
+    }
+
+    pub fn pop_clip(&mut self)
+    {
+        if self.clip_stack.len() > 1
+        {
+            self.clip_stack.pop();
+        }
+    }
+
+    // the area drawing is currently restricted to, in the current drawing coordinates
+    pub fn clip_rect(&self) -> Rect
+    {
+        let clip = self.current_clip();
+
+        let rect = Rect {
+            origin: Point { x: clip.x0 as f32, y: clip.y0 as f32 },
+            size: Size { width: (clip.x1 - clip.x0).max(0) as f32, height: (clip.y1 - clip.y0).max(0) as f32 }
+        };
+
+        return match self.current_transform().invert()
+        {
+            Some(inverse) => transform_bounds(&inverse, rect),
+            None => Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 0.0, height: 0.0 } }
+        };
+    }
+
+    // draws everything that follows through transform, applied before the transforms already pushed
+    pub fn push_transform(&mut self, transform: Transform)
+    {
+        let combined = transform.then(&self.window_transform());
+        self.transform_stack.push(combined);
+    }
+
+    pub fn pop_transform(&mut self)
+    {
+        if self.transform_stack.len() > 1
+        {
+            self.transform_stack.pop();
+        }
+    }
+
+    // draws everything up to the matching pop_layer into a transparent layer, which is then blended in as a whole with opacity,
+    // so overlapping content inside the layer does not show through itself
+    pub fn push_layer(&mut self, opacity: f32)
+    {
+        let area = self.window_clip();
+
+        let layer = Bitmap::new((area.x1 - area.x0).max(0) as usize, (area.y1 - area.y0).max(0) as usize);
+        let target = std::mem::replace(&mut self.bitmap, layer);
+
+        self.layer_stack.push((target, opacity, self.origin));
+        self.origin = (area.x0, area.y0);
+    }
+
+    pub fn pop_layer(&mut self)
+    {
+        if let Some((target, opacity, origin)) = self.layer_stack.pop()
+        {
+            let layer = std::mem::replace(&mut self.bitmap, target);
+            let (x, y) = (self.origin.0 - origin.0, self.origin.1 - origin.1);
+
+            self.origin = origin;
+            self.bitmap.composite(&layer, x, y, opacity);
+        }
+    }
+
+    // maps the current drawing coordinates to pixels
+    pub fn transform(&self) -> Transform
+    {
+        return self.current_transform();
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, color: Color)
+    {
+        if color.a <= 0.0
+        {
+            return;
+        }
+
+        if is_axis_aligned(&self.current_transform())
+        {
+            let clip = self.current_clip();
+            let rect = self.transform_bounds(rect);
+
+            self.bitmap.fill_rect(rect, color, clip);
+        }
+        else
+        {
+            let mut path = Path::new();
+            path.add_rect(rect);
+
+            self.fill_path(&path, FillRule::NonZero, color);
+        }
+    }
+
+    // draws text with the top of its first line at origin, returns the pen position after the last glyph
+    pub fn draw_text(&mut self, origin: Point, text: &str, descriptor: &FontDescriptor, color: Color) -> Point
+    {
+        let transform = self.current_transform();
+
+        if is_translation(&transform)
+        {
+            let clip = self.current_clip();
+            let end = font::draw_text(&mut self.bitmap, clip, transform.apply(origin), text, descriptor, color);
+
+            return Point { x: end.x - transform.e, y: end.y - transform.f };
+        }
+
+        // scaled and rotated text is drawn at its final size into a layer that is mapped into place
+        let bounds = Rect { origin: origin, size: font::measure_text(text, descriptor) };
+        let mut end = origin;
+
+        self.draw_layer(bounds, |bitmap, clip, layer_origin, scale| {
+            let scaled = FontDescriptor { size: descriptor.size * scale, ..descriptor.clone() };
+            let layer_end = font::draw_text(bitmap, clip, layer_origin, text, &scaled, color);
+
+            end = Point { x: origin.x + (layer_end.x - layer_origin.x) / scale, y: origin.y + (layer_end.y - layer_origin.y) / scale };
+        });
+
+        return end;
+    }
+
+    // fills the shape built by the closure, points are in the current drawing coordinates
+    pub(crate) fn fill_shape<F: FnOnce(&mut Rasterizer)>(&mut self, bounds: Rect, fill_rule: FillRule, color: Color, build: F)
+    {
+        self.fill_shape_with_brush(bounds, fill_rule, &Brush::Solid(color), build);
+    }
+
+    // fills the shape built by the closure with a brush, brush points are in the same coordinates as the shape
+    pub(crate) fn fill_shape_with_brush<F: FnOnce(&mut Rasterizer)>(&mut self, bounds: Rect, fill_rule: FillRule, brush: &Brush, build: F)
+    {
+        let clip = self.current_clip();
+        let area = PixelBox::from_rect(self.transform_bounds(bounds)).intersect(clip);
+
+        if area.is_empty()
+        {
+            return;
+        }
+
+        let mut rasterizer = Rasterizer::new_at(
+            Point { x: area.x0 as f32, y: area.y0 as f32 },
+            (area.x1 - area.x0) as usize,
+            (area.y1 - area.y0) as usize
+        );
+
+        rasterizer.set_transform(self.current_transform());
+        build(&mut rasterizer);
+
+        let mask = rasterizer.rasterize(fill_rule);
+        self.fill_mask_with_brush(&mask, area, brush, clip);
+    }
+
+    pub(crate) fn fill_polygon(&mut self, points: &[Point], color: Color)
+    {
+        if points.len() < 3
+        {
+            return;
+        }
+
+        let bounds = bounding_rect(points);
+
+        self.fill_shape(bounds, FillRule::NonZero, color, |rasterizer| {
+            rasterizer.move_to(points[0]);
+
+            for point in points.iter().skip(1)
+            {
+                rasterizer.line_to(*point);
+            }
+
+            rasterizer.close();
+        });
+    }
+
+    pub(crate) fn fill_rounded_rect(&mut self, rect: Rect, radius: f32, color: Color)
+    {
+        self.fill_shape(rect, FillRule::NonZero, color, |rasterizer| {
+            add_rounded_rect(rasterizer, rect, radius);
+        });
+    }
+
+    // outlines the inside of a rounded rect with a line of the given width
+    pub(crate) fn stroke_rounded_rect(&mut self, rect: Rect, radius: f32, width: f32, color: Color)
+    {
+        let inner = Rect {
+            origin: Point { x: rect.origin.x + width, y: rect.origin.y + width },
+            size: Size { width: (rect.size.width - 2.0 * width).max(0.0), height: (rect.size.height - 2.0 * width).max(0.0) }
+        };
+
+        self.fill_shape(rect, FillRule::EvenOdd, color, |rasterizer| {
+            add_rounded_rect(rasterizer, rect, radius);
+            add_rounded_rect(rasterizer, inner, (radius - width).max(0.0));
+        });
+    }
+
+    pub(crate) fn fill_ellipse(&mut self, rect: Rect, color: Color)
+    {
+        let radius = rect.size.width.min(rect.size.height) / 2.0;
+        self.fill_rounded_rect(rect, radius, color);
+    }
+
+    // draws attributed text with the top of its first line at origin, unset attributes fall back to the given font and color
+    pub fn draw_attributed_text(&mut self, origin: Point, text: &AttributedString, base_font: &FontDescriptor, base_color: Color)
+    {
+        let transform = self.current_transform();
+
+        if is_translation(&transform)
+        {
+            let clip = self.current_clip();
+            text::draw_attributed_text(&mut self.bitmap, clip, transform.apply(origin), text, base_font, base_color);
+            return;
+        }
+
+        let bounds = Rect { origin: origin, size: text::measure_attributed_text(text, base_font) };
+
+        self.draw_layer(bounds, |bitmap, clip, layer_origin, scale| {
+            let mut scaled = text.clone();
+
+            for run in scaled.runs.iter_mut()
+            {
+                run.attributes.size = run.attributes.size.map(|size| size * scale);
+            }
+
+            let scaled_font = FontDescriptor { size: base_font.size * scale, ..base_font.clone() };
+            text::draw_attributed_text(bitmap, clip, layer_origin, &scaled, &scaled_font, base_color);
+        });
+    }
+
+    // fills every contour of the path, closing open ones
+    pub fn fill_path(&mut self, path: &Path, fill_rule: FillRule, brush: impl Into<Brush>)
+    {
+        self.fill_shape_with_brush(path.bounds(), fill_rule, &brush.into(), |rasterizer| {
+            path.add_to_rasterizer(rasterizer);
+        });
+    }
+
+    // draws the outline of the path with the style's width, joins, caps and dashes
+    pub fn stroke_path(&mut self, path: &Path, style: &StrokeStyle, brush: impl Into<Brush>)
+    {
+        // miter joins can reach further out than half the line width
+        let reach = if style.join == LineJoin::Miter { style.width / 2.0 * style.miter_limit.max(1.0) } else { style.width };
+
+        let bounds = path.bounds();
+        let bounds = Rect {
+            origin: Point { x: bounds.origin.x - reach, y: bounds.origin.y - reach },
+            size: Size { width: bounds.size.width + reach * 2.0, height: bounds.size.height + reach * 2.0 }
+        };
+
+        self.fill_shape_with_brush(bounds, FillRule::NonZero, &brush.into(), |rasterizer| {
+            path.add_stroke_to_rasterizer(rasterizer, style);
+        });
+    }
+
+    // fills the parts of the path that are also inside clip_path
+    pub(crate) fn fill_path_clipped(&mut self, path: &Path, fill_rule: FillRule, clip_path: &Path, brush: impl Into<Brush>)
+    {
+        let clip = self.current_clip();
+        let transform = self.current_transform();
+        let area = PixelBox::from_rect(self.transform_bounds(path.bounds())).intersect(PixelBox::from_rect(self.transform_bounds(clip_path.bounds()))).intersect(clip);
+
+        if area.is_empty()
+        {
+            return;
+        }
+
+        let mut mask = rasterize_path(path, fill_rule, area, &transform);
+        mask.intersect(&rasterize_path(clip_path, FillRule::NonZero, area, &transform));
+
+        self.fill_mask_with_brush(&mask, area, &brush.into(), clip);
+    }
+
+    // draws a rounded rect blurred by the CSS blur radius, leaving out the inside of cutout so a shadow only shows around its shape
+    pub fn draw_shadow(&mut self, rect: Rect, radii: CornerRadii, blur: f32, color: Color, cutout: Option<(Rect, CornerRadii)>)
+    {
+        let transform = self.current_transform();
+
+        // the blur is measured in pixels, so it grows and shrinks with the transform
+        let sigma = blur.max(0.0) / 2.0 * transform.scale_factor();
+        let margin = (sigma * 3.0).ceil();
+
+        let clip = self.current_clip();
+        let reach = PixelBox { x0: clip.x0 - margin as i32, y0: clip.y0 - margin as i32, x1: clip.x1 + margin as i32, y1: clip.y1 + margin as i32 };
+
+        let bounds = self.transform_bounds(rect);
+        let area = PixelBox::from_rect(Rect {
+            origin: Point { x: bounds.origin.x - margin, y: bounds.origin.y - margin },
+            size: Size { width: bounds.size.width + margin * 2.0, height: bounds.size.height + margin * 2.0 }
+        }).intersect(reach);
+
+        if area.is_empty() || rect.size.width <= 0.0 || rect.size.height <= 0.0
+        {
+            return;
+        }
+
+        let mut shape = Path::new();
+        shape.add_rounded_rect_corners(rect, radii);
+
+        let mut mask = rasterize_path(&shape, FillRule::NonZero, area, &transform);
+        mask.blur(sigma);
+
+        if let Some((cutout_rect, cutout_radii)) = cutout
+        {
+            let mut cutout_shape = Path::new();
+            cutout_shape.add_rounded_rect_corners(cutout_rect, cutout_radii);
+
+            mask.subtract(&rasterize_path(&cutout_shape, FillRule::NonZero, area, &transform));
+        }
+
+        self.bitmap.fill_mask(&mask, area.x0, area.y0, color, clip);
+    }
+
+    // renders a loaded SVG scaled into rect, currentColor in the document draws with color
+    pub fn draw_svg(&mut self, rect: Rect, svg: Svg, color: Color)
+    {
+        svg::draw(self, rect, svg, color);
+    }
+
+    // draws a loaded image scaled to fill rect
+    pub fn draw_image(&mut self, rect: Rect, image: Image, sampling: ImageSampling)
+    {
+        let transform = self.current_transform();
+        let clip = self.current_clip();
+
+        if let Some(source) = image::get_bitmap(image)
+        {
+            if is_axis_aligned(&transform) && transform.a > 0.0 && transform.d > 0.0
+            {
+                let rect = self.transform_bounds(rect);
+                self.bitmap.draw_bitmap(&source, rect, sampling, clip);
+            }
+            else
+            {
+                // flipped, rotated and skewed images are sampled back through the transform
+                self.fill_rect_with_bitmap(rect, source, sampling);
+            }
+        }
+    }
+}
+
+// hands a finished frame to nanowin for display
+pub fn present(window: Window, context: &DrawingContext)
+{
+    crate::platform::draw_bitmap(window, Rect { origin: Point { x: 0.0, y: 0.0 }, size: context.size() }, context.bitmap());
+}
+
 /***************************************************************
 * MARK: LOCAL FUNCTIONS
-***************************************************************/
\ No newline at end of file
+***************************************************************/
+
+impl DrawingContext
+{
+    // the clip in pixels of the current bitmap, which is offset from the window inside a layer
+    fn current_clip(&self) -> PixelBox
+    {
+        return self.window_clip().offset(-self.origin.0, -self.origin.1);
+    }
+
+    fn current_transform(&self) -> Transform
+    {
+        return self.window_transform().then(&Transform::translation(-self.origin.0 as f32, -self.origin.1 as f32));
+    }
+
+    fn window_clip(&self) -> PixelBox
+    {
+        return *self.clip_stack.last().unwrap_or(&self.window_bitmap().bounds());
+    }
+
+    fn window_transform(&self) -> Transform
+    {
+        return *self.transform_stack.last().unwrap_or(&Transform::identity());
+    }
+
+    fn window_bitmap(&self) -> &Bitmap
+    {
+        return match self.layer_stack.first()
+        {
+            Some((target, _, _)) => target,
+            None => &self.bitmap
+        };
+    }
+
+    // the pixel box around rect once it has gone through the current transform
+    fn transform_bounds(&self, rect: Rect) -> Rect
+    {
+        return transform_bounds(&self.current_transform(), rect);
+    }
+
+    // solid colors keep the plain blend, other brushes are shaded per pixel
+    fn fill_mask_with_brush(&mut self, mask: &Mask, area: PixelBox, brush: &Brush, clip: PixelBox)
+    {
+        if let Brush::Solid(color) = brush
+        {
+            self.bitmap.fill_mask(mask, area.x0, area.y0, *color, clip);
+        }
+        else if let Some(shader) = brush::Shader::new(brush).and_then(|shader| shader.transformed(&self.current_transform()))
+        {
+            self.bitmap.fill_mask_shaded(mask, area.x0, area.y0, &shader, clip);
+        }
+    }
+
+    // covers rect with one stretched copy of the bitmap through the current transform
+    fn fill_rect_with_bitmap(&mut self, rect: Rect, source: Rc<Bitmap>, sampling: ImageSampling)
+    {
+        let transform = self.current_transform();
+        let clip = self.current_clip();
+        let area = PixelBox::from_rect(self.transform_bounds(rect)).intersect(clip);
+
+        if area.is_empty() || source.width == 0 || source.height == 0
+        {
+            return;
+        }
+
+        let mut path = Path::new();
+        path.add_rect(rect);
+
+        let mask = rasterize_path(&path, FillRule::NonZero, area, &transform);
+
+        if let Some(shader) = brush::Shader::for_bitmap(source, rect, sampling).transformed(&transform)
+        {
+            self.bitmap.fill_mask_shaded(&mask, area.x0, area.y0, &shader, clip);
+        }
+    }
+
+    // draws content that only renders in pixel space, such as text, into a layer at the transform's scale and maps the layer into place,
+    // the closure gets the layer, its clip, where bounds.origin lands in the layer and the scale
+    fn draw_layer<F: FnOnce(&mut Bitmap, PixelBox, Point, f32)>(&mut self, bounds: Rect, draw: F)
+    {
+        let transform = self.current_transform();
+        let mut scale = transform.scale_factor();
+
+        if scale <= f32::EPSILON || bounds.size.width <= 0.0 || bounds.size.height <= 0.0
+        {
+            return;
+        }
+
+        scale = scale.min(MAX_LAYER_SIZE / bounds.size.width.max(bounds.size.height));
+
+        let padding = LAYER_PADDING as f32;
+        let mut layer = Bitmap::new(
+            (bounds.size.width * scale).ceil() as usize + LAYER_PADDING * 2,
+            (bounds.size.height * scale).ceil() as usize + LAYER_PADDING * 2
+        );
+
+        let layer_clip = layer.bounds();
+        draw(&mut layer, layer_clip, Point { x: padding, y: padding }, scale);
+
+        // the part of the drawing coordinates the whole layer covers
+        let layer_rect = Rect {
+            origin: Point { x: bounds.origin.x - padding / scale, y: bounds.origin.y - padding / scale },
+            size: Size { width: layer.width as f32 / scale, height: layer.height as f32 / scale }
+        };
+
+        self.fill_rect_with_bitmap(layer_rect, Rc::new(layer), ImageSampling::Bilinear);
+    }
+}
+
+// coverage of the path over the pixels of area, with the path's points mapped through transform
+fn rasterize_path(path: &Path, fill_rule: FillRule, area: PixelBox, transform: &Transform) -> Mask
+{
+    let mut rasterizer = Rasterizer::new_at(
+        Point { x: area.x0 as f32, y: area.y0 as f32 },
+        (area.x1 - area.x0) as usize,
+        (area.y1 - area.y0) as usize
+    );
+
+    rasterizer.set_transform(*transform);
+    path.add_to_rasterizer(&mut rasterizer);
+
+    return rasterizer.rasterize(fill_rule);
+}
+
+fn transform_bounds(transform: &Transform, rect: Rect) -> Rect
+{
+    if transform.is_identity()
+    {
+        return rect;
+    }
+
+    let left = rect.origin.x;
+    let top = rect.origin.y;
+    let right = rect.origin.x + rect.size.width;
+    let bottom = rect.origin.y + rect.size.height;
+
+    return bounding_rect(&[
+        transform.apply(Point { x: left, y: top }),
+        transform.apply(Point { x: right, y: top }),
+        transform.apply(Point { x: right, y: bottom }),
+        transform.apply(Point { x: left, y: bottom })
+    ]);
+}
+
+// only moves, so pixel aligned drawing like text can be offset directly
+fn is_translation(transform: &Transform) -> bool
+{
+    return transform.a == 1.0 && transform.b == 0.0 && transform.c == 0.0 && transform.d == 1.0;
+}
+
+// keeps rects as rects, any scale but no rotation or skew
+fn is_axis_aligned(transform: &Transform) -> bool
+{
+    return transform.b == 0.0 && transform.c == 0.0;
+}
+
+// approximates the corners with cubic arcs, the radius is clamped to half the shorter side
+fn add_rounded_rect(rasterizer: &mut Rasterizer, rect: Rect, radius: f32)
+{
+    const KAPPA: f32 = 0.552_284_8;
+
+    let radius = radius.clamp(0.0, rect.size.width.min(rect.size.height) / 2.0);
+    let handle = radius * (1.0 - KAPPA);
+
+    let left = rect.origin.x;
+    let top = rect.origin.y;
+    let right = rect.origin.x + rect.size.width;
+    let bottom = rect.origin.y + rect.size.height;
+
+    rasterizer.move_to(Point { x: left + radius, y: top });
+    rasterizer.line_to(Point { x: right - radius, y: top });
+    rasterizer.cubic_to(Point { x: right - handle, y: top }, Point { x: right, y: top + handle }, Point { x: right, y: top + radius });
+    rasterizer.line_to(Point { x: right, y: bottom - radius });
+    rasterizer.cubic_to(Point { x: right, y: bottom - handle }, Point { x: right - handle, y: bottom }, Point { x: right - radius, y: bottom });
+    rasterizer.line_to(Point { x: left + radius, y: bottom });
+    rasterizer.cubic_to(Point { x: left + handle, y: bottom }, Point { x: left, y: bottom - handle }, Point { x: left, y: bottom - radius });
+    rasterizer.line_to(Point { x: left, y: top + radius });
+    rasterizer.cubic_to(Point { x: left, y: top + handle }, Point { x: left + handle, y: top }, Point { x: left + radius, y: top });
+    rasterizer.close();
+}
+
+fn bounding_rect(points: &[Point]) -> Rect
+{
+    let mut min = Point { x: f32::MAX, y: f32::MAX };
+    let mut max = Point { x: f32::MIN, y: f32::MIN };
+
+    for point in points
+    {
+        min.x = min.x.min(point.x);
+        min.y = min.y.min(point.y);
+        max.x = max.x.max(point.x);
+        max.y = max.y.max(point.y);
+    }
+
+    return Rect { origin: min, size: Size { width: max.x - min.x, height: max.y - min.y } };
+}
@@ -0,0 +1,1078 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  path.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the vector path builder and the stroker
+**                 that turns its outlines into fillable shapes.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect};
+
+use crate::drawing::raster::Rasterizer;
+use crate::drawing::transform::Transform;
+
+use std::f32::consts::{TAU, FRAC_PI_2};
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+#[derive(Clone, Copy)]
+pub enum PathCommand
+{
+    MoveTo(Point),
+    LineTo(Point),
+    QuadTo(Point, Point),
+    CubicTo(Point, Point, Point),
+    Close
+}
+
+// a sequence of contours, each started by a move and optionally closed
+#[derive(Clone)]
+pub struct Path
+{
+    commands: Vec<PathCommand>,
+
+    // where the current contour started and where the last command ended
+    start: Point,
+    current: Option<Point>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineJoin
+{
+    Miter,
+    Round,
+    Bevel
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineCap
+{
+    Butt,
+    Round,
+    Square
+}
+
+// a part of an ellipse in center form, angles are in radians before rotation is applied
+#[derive(Clone, Copy)]
+struct EllipseArc
+{
+    pub center: Point,
+    pub radius_x: f32,
+    pub radius_y: f32,
+    pub rotation: f32,
+    pub start_angle: f32,
+    pub sweep_angle: f32
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle
+{
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+
+    // miter joins longer than this many line widths fall back to bevels
+    pub miter_limit: f32,
+
+    // alternating dash and gap lengths, an empty pattern draws a solid line
+    pub dash_pattern: Vec<f32>,
+
+    // how far into the pattern the line starts
+    pub dash_offset: f32
+}
+
+// the radius of each corner of a rounded rect, in clockwise order from the top left
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CornerRadii
+{
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32
+}
+
+// a contour flattened into line segments
+struct Polyline
+{
+    pub points: Vec<Point>,
+    pub closed: bool
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+// points closer than this are merged before stroking
+const POINT_EPSILON: f32 = 1e-4;
+
+// maximum distance in pixels between a round join or cap and its polygon
+const ROUND_TOLERANCE: f32 = 0.1;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+impl Path
+{
+    pub fn new() -> Path
+    {
+        return Path {
+            commands: Vec::new(),
+            start: Point { x: 0.0, y: 0.0 },
+            current: None
+        };
+    }
+
+    pub fn move_to(&mut self, point: Point)
+    {
+        self.push(PathCommand::MoveTo(point));
+    }
+
+    // a path that does not start with a move starts at the origin
+    pub fn line_to(&mut self, point: Point)
+    {
+        self.push(PathCommand::LineTo(point));
+    }
+
+    pub fn quad_to(&mut self, control: Point, point: Point)
+    {
+        self.push(PathCommand::QuadTo(control, point));
+    }
+
+    pub fn cubic_to(&mut self, control1: Point, control2: Point, point: Point)
+    {
+        self.push(PathCommand::CubicTo(control1, control2, point));
+    }
+
+    // adds a circular arc around center, angles are in radians and increase clockwise from the positive x axis,
+    // a line joins the current point to the start of the arc
+    pub fn arc(&mut self, center: Point, radius: f32, start_angle: f32, end_angle: f32, counter_clockwise: bool)
+    {
+        let radius = radius.abs();
+
+        // a difference of a full turn or more draws a whole circle
+        let sweep_angle = if counter_clockwise
+        {
+            if start_angle - end_angle >= TAU { -TAU } else { -(start_angle - end_angle).rem_euclid(TAU) }
+        }
+        else
+        {
+            if end_angle - start_angle >= TAU { TAU } else { (end_angle - start_angle).rem_euclid(TAU) }
+        };
+
+        let start = Point { x: center.x + radius * start_angle.cos(), y: center.y + radius * start_angle.sin() };
+
+        if self.current.is_some()
+        {
+            self.line_to(start);
+        }
+        else
+        {
+            self.move_to(start);
+        }
+
+        self.add_arc_curves(EllipseArc { center: center, radius_x: radius, radius_y: radius, rotation: 0.0, start_angle: start_angle, sweep_angle: sweep_angle }, None);
+    }
+
+    // adds an SVG style elliptical arc from the current point to point, rotation is in radians,
+    // of the four arcs that fit, the flags pick the larger or smaller one and the clockwise or counter-clockwise one
+    pub fn elliptical_arc_to(&mut self, radius_x: f32, radius_y: f32, rotation: f32, large_arc: bool, clockwise: bool, point: Point)
+    {
+        let from = self.current.unwrap_or(Point { x: 0.0, y: 0.0 });
+
+        if from.x == point.x && from.y == point.y
+        {
+            return;
+        }
+
+        let mut radius_x = radius_x.abs();
+        let mut radius_y = radius_y.abs();
+
+        if radius_x <= 0.0 || radius_y <= 0.0
+        {
+            self.line_to(point);
+            return;
+        }
+
+        let (sin, cos) = rotation.sin_cos();
+
+        let half_x = (from.x - point.x) / 2.0;
+        let half_y = (from.y - point.y) / 2.0;
+
+        let x1 = cos * half_x + sin * half_y;
+        let y1 = -sin * half_x + cos * half_y;
+
+        // radii too small to reach the end point are scaled up until they just do
+        let lambda = (x1 * x1) / (radius_x * radius_x) + (y1 * y1) / (radius_y * radius_y);
+
+        if lambda > 1.0
+        {
+            radius_x *= lambda.sqrt();
+            radius_y *= lambda.sqrt();
+        }
+
+        let numerator = radius_x * radius_x * radius_y * radius_y - radius_x * radius_x * y1 * y1 - radius_y * radius_y * x1 * x1;
+        let denominator = radius_x * radius_x * y1 * y1 + radius_y * radius_y * x1 * x1;
+
+        let sign = if large_arc == clockwise { -1.0 } else { 1.0 };
+        let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+
+        let center_x1 = coefficient * radius_x * y1 / radius_y;
+        let center_y1 = -coefficient * radius_y * x1 / radius_x;
+
+        let center = Point {
+            x: cos * center_x1 - sin * center_y1 + (from.x + point.x) / 2.0,
+            y: sin * center_x1 + cos * center_y1 + (from.y + point.y) / 2.0
+        };
+
+        let angle = |ux: f32, uy: f32, vx: f32, vy: f32| -> f32 { (ux * vy - uy * vx).atan2(ux * vx + uy * vy) };
+
+        let start_angle = angle(1.0, 0.0, (x1 - center_x1) / radius_x, (y1 - center_y1) / radius_y);
+        let mut sweep_angle = angle((x1 - center_x1) / radius_x, (y1 - center_y1) / radius_y, (-x1 - center_x1) / radius_x, (-y1 - center_y1) / radius_y);
+
+        if !clockwise && sweep_angle > 0.0
+        {
+            sweep_angle -= TAU;
+        }
+        else if clockwise && sweep_angle < 0.0
+        {
+            sweep_angle += TAU;
+        }
+
+        self.add_arc_curves(EllipseArc { center: center, radius_x: radius_x, radius_y: radius_y, rotation: rotation, start_angle: start_angle, sweep_angle: sweep_angle }, Some(point));
+    }
+
+    pub fn close(&mut self)
+    {
+        self.push(PathCommand::Close);
+    }
+
+    pub fn add_rect(&mut self, rect: Rect)
+    {
+        let right = rect.origin.x + rect.size.width;
+        let bottom = rect.origin.y + rect.size.height;
+
+        self.move_to(rect.origin);
+        self.line_to(Point { x: right, y: rect.origin.y });
+        self.line_to(Point { x: right, y: bottom });
+        self.line_to(Point { x: rect.origin.x, y: bottom });
+        self.close();
+    }
+
+    // the corner radii are clamped to half the width and height of the rect
+    pub fn add_rounded_rect(&mut self, rect: Rect, radius_x: f32, radius_y: f32)
+    {
+        let radius = Size { width: radius_x.clamp(0.0, rect.size.width / 2.0), height: radius_y.clamp(0.0, rect.size.height / 2.0) };
+        self.add_elliptical_corners(rect, [radius; 4]);
+    }
+
+    // corners that would overlap are scaled down together, as CSS does
+    pub fn add_rounded_rect_corners(&mut self, rect: Rect, radii: CornerRadii)
+    {
+        let corners = [radii.top_left, radii.top_right, radii.bottom_right, radii.bottom_left]
+            .map(|radius| Size { width: radius.max(0.0), height: radius.max(0.0) });
+
+        self.add_elliptical_corners(rect, corners);
+    }
+
+    pub fn add_ellipse(&mut self, rect: Rect)
+    {
+        self.add_rounded_rect(rect, rect.size.width / 2.0, rect.size.height / 2.0);
+    }
+
+    pub fn commands(&self) -> &[PathCommand]
+    {
+        return &self.commands;
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        return self.commands.is_empty();
+    }
+
+    // the end point of the last command
+    pub fn current_point(&self) -> Option<Point>
+    {
+        return self.current;
+    }
+
+    // bounding box of all points including curve control points, so it always contains the curves
+    pub fn bounds(&self) -> Rect
+    {
+        let mut min = Point { x: f32::MAX, y: f32::MAX };
+        let mut max = Point { x: f32::MIN, y: f32::MIN };
+
+        for command in self.commands.iter()
+        {
+            let points: &[Point] = match command
+            {
+                PathCommand::MoveTo(point) | PathCommand::LineTo(point) => std::slice::from_ref(point),
+                PathCommand::QuadTo(control, point) => &[*control, *point],
+                PathCommand::CubicTo(control1, control2, point) => &[*control1, *control2, *point],
+                PathCommand::Close => &[]
+            };
+
+            for point in points
+            {
+                min.x = min.x.min(point.x);
+                min.y = min.y.min(point.y);
+                max.x = max.x.max(point.x);
+                max.y = max.y.max(point.y);
+            }
+        }
+
+        if min.x > max.x
+        {
+            return Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 0.0, height: 0.0 } };
+        }
+
+        return Rect { origin: min, size: Size { width: max.x - min.x, height: max.y - min.y } };
+    }
+
+    pub fn transformed(&self, transform: &Transform) -> Path
+    {
+        let commands: Vec<PathCommand> = self.commands.iter().map(|command| {
+            match command
+            {
+                PathCommand::MoveTo(point) => PathCommand::MoveTo(transform.apply(*point)),
+                PathCommand::LineTo(point) => PathCommand::LineTo(transform.apply(*point)),
+                PathCommand::QuadTo(control, point) => PathCommand::QuadTo(transform.apply(*control), transform.apply(*point)),
+                PathCommand::CubicTo(control1, control2, point) =>
+                {
+                    PathCommand::CubicTo(transform.apply(*control1), transform.apply(*control2), transform.apply(*point))
+                },
+                PathCommand::Close => PathCommand::Close
+            }
+        }).collect();
+
+        return Path {
+            commands: commands,
+            start: transform.apply(self.start),
+            current: self.current.map(|current| transform.apply(current))
+        };
+    }
+}
+
+impl Default for Path
+{
+    fn default() -> Self
+    {
+        return Path::new();
+    }
+}
+
+impl CornerRadii
+{
+    pub fn uniform(radius: f32) -> CornerRadii
+    {
+        return CornerRadii { top_left: radius, top_right: radius, bottom_right: radius, bottom_left: radius };
+    }
+
+    pub fn is_zero(&self) -> bool
+    {
+        return self.top_left <= 0.0 && self.top_right <= 0.0 && self.bottom_right <= 0.0 && self.bottom_left <= 0.0;
+    }
+}
+
+impl StrokeStyle
+{
+    // miter joins and butt caps, the SVG defaults
+    pub fn new(width: f32) -> StrokeStyle
+    {
+        return StrokeStyle {
+            width: width,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dash_pattern: Vec::new(),
+            dash_offset: 0.0
+        };
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+impl Path
+{
+    fn push(&mut self, command: PathCommand)
+    {
+        match command
+        {
+            PathCommand::MoveTo(point) =>
+            {
+                self.start = point;
+                self.current = Some(point);
+            },
+            PathCommand::LineTo(point) | PathCommand::QuadTo(_, point) | PathCommand::CubicTo(_, _, point) => self.current = Some(point),
+            PathCommand::Close => self.current = Some(self.start)
+        }
+
+        self.commands.push(command);
+    }
+
+    // adds a clockwise rect with an elliptical radius for each corner, from the top left, scaled down to fit the sides
+    pub(crate) fn add_elliptical_corners(&mut self, rect: Rect, corners: [Size; 4])
+    {
+        const KAPPA: f32 = 0.552_284_8;
+
+        if rect.size.width <= 0.0 || rect.size.height <= 0.0
+        {
+            return;
+        }
+
+        let [top_left, top_right, bottom_right, bottom_left] = corners;
+
+        let fit = |length: f32, first: f32, second: f32| -> f32 { if first + second > length { length / (first + second) } else { 1.0 } };
+
+        let scale = fit(rect.size.width, top_left.width, top_right.width)
+            .min(fit(rect.size.width, bottom_left.width, bottom_right.width))
+            .min(fit(rect.size.height, top_left.height, bottom_left.height))
+            .min(fit(rect.size.height, top_right.height, bottom_right.height));
+
+        let [top_left, top_right, bottom_right, bottom_left] = [top_left, top_right, bottom_right, bottom_left]
+            .map(|radius| Size { width: radius.width * scale, height: radius.height * scale });
+
+        let left = rect.origin.x;
+        let top = rect.origin.y;
+        let right = rect.origin.x + rect.size.width;
+        let bottom = rect.origin.y + rect.size.height;
+
+        self.move_to(Point { x: left + top_left.width, y: top });
+        self.line_to(Point { x: right - top_right.width, y: top });
+
+        if top_right.width > 0.0 && top_right.height > 0.0
+        {
+            self.cubic_to(
+                Point { x: right - top_right.width * (1.0 - KAPPA), y: top },
+                Point { x: right, y: top + top_right.height * (1.0 - KAPPA) },
+                Point { x: right, y: top + top_right.height }
+            );
+        }
+
+        self.line_to(Point { x: right, y: bottom - bottom_right.height });
+
+        if bottom_right.width > 0.0 && bottom_right.height > 0.0
+        {
+            self.cubic_to(
+                Point { x: right, y: bottom - bottom_right.height * (1.0 - KAPPA) },
+                Point { x: right - bottom_right.width * (1.0 - KAPPA), y: bottom },
+                Point { x: right - bottom_right.width, y: bottom }
+            );
+        }
+
+        self.line_to(Point { x: left + bottom_left.width, y: bottom });
+
+        if bottom_left.width > 0.0 && bottom_left.height > 0.0
+        {
+            self.cubic_to(
+                Point { x: left + bottom_left.width * (1.0 - KAPPA), y: bottom },
+                Point { x: left, y: bottom - bottom_left.height * (1.0 - KAPPA) },
+                Point { x: left, y: bottom - bottom_left.height }
+            );
+        }
+
+        self.line_to(Point { x: left, y: top + top_left.height });
+
+        if top_left.width > 0.0 && top_left.height > 0.0
+        {
+            self.cubic_to(
+                Point { x: left, y: top + top_left.height * (1.0 - KAPPA) },
+                Point { x: left + top_left.width * (1.0 - KAPPA), y: top },
+                Point { x: left + top_left.width, y: top }
+            );
+        }
+
+        self.close();
+    }
+
+    // approximates an arc of an ellipse with one cubic curve per quarter turn or less
+    fn add_arc_curves(&mut self, arc: EllipseArc, end: Option<Point>)
+    {
+        let EllipseArc { center, radius_x, radius_y, rotation, start_angle, sweep_angle } = arc;
+
+        if sweep_angle == 0.0 || radius_x <= 0.0 || radius_y <= 0.0
+        {
+            return;
+        }
+
+        let (sin, cos) = rotation.sin_cos();
+
+        let segments = (sweep_angle.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = sweep_angle / segments as f32;
+        let handle = 4.0 / 3.0 * (step / 4.0).tan();
+
+        let point_at = |theta: f32| -> Point {
+            let (sin_theta, cos_theta) = theta.sin_cos();
+
+            return Point {
+                x: center.x + radius_x * cos_theta * cos - radius_y * sin_theta * sin,
+                y: center.y + radius_x * cos_theta * sin + radius_y * sin_theta * cos
+            };
+        };
+
+        let tangent_at = |theta: f32| -> Point {
+            let (sin_theta, cos_theta) = theta.sin_cos();
+
+            return Point {
+                x: -radius_x * sin_theta * cos - radius_y * cos_theta * sin,
+                y: -radius_x * sin_theta * sin + radius_y * cos_theta * cos
+            };
+        };
+
+        for segment in 0..segments
+        {
+            let theta1 = start_angle + step * segment as f32;
+            let theta2 = theta1 + step;
+
+            let point1 = point_at(theta1);
+            let tangent1 = tangent_at(theta1);
+            let tangent2 = tangent_at(theta2);
+
+            // the last segment ends exactly on the requested point
+            let point2 = match end
+            {
+                Some(end) if segment + 1 == segments => end,
+                _ => point_at(theta2)
+            };
+
+            self.cubic_to(
+                Point { x: point1.x + handle * tangent1.x, y: point1.y + handle * tangent1.y },
+                Point { x: point2.x - handle * tangent2.x, y: point2.y - handle * tangent2.y },
+                point2
+            );
+        }
+    }
+
+    // every contour is closed when filling
+    pub(crate) fn add_to_rasterizer(&self, rasterizer: &mut Rasterizer)
+    {
+        for command in self.commands.iter()
+        {
+            match command
+            {
+                PathCommand::MoveTo(point) => rasterizer.move_to(*point),
+                PathCommand::LineTo(point) => rasterizer.line_to(*point),
+                PathCommand::QuadTo(control, point) => rasterizer.quad_to(*control, *point),
+                PathCommand::CubicTo(control1, control2, point) => rasterizer.cubic_to(*control1, *control2, *point),
+                PathCommand::Close => rasterizer.close()
+            }
+        }
+    }
+
+    // adds the outline of the stroke as overlapping pieces that all wind the same way, fill it non-zero
+    pub(crate) fn add_stroke_to_rasterizer(&self, rasterizer: &mut Rasterizer, style: &StrokeStyle)
+    {
+        if style.width <= 0.0
+        {
+            return;
+        }
+
+        // patterns with negative lengths or nothing but gaps are drawn solid
+        let dashed = !style.dash_pattern.is_empty()
+            && style.dash_pattern.iter().all(|length| *length >= 0.0 && length.is_finite())
+            && style.dash_pattern.iter().sum::<f32>() > 0.0;
+
+        for polyline in self.flatten()
+        {
+            if dashed
+            {
+                for dash in dash_polyline(&polyline, &style.dash_pattern, style.dash_offset)
+                {
+                    stroke_polyline(rasterizer, &dash, style);
+                }
+            }
+            else
+            {
+                stroke_polyline(rasterizer, &polyline, style);
+            }
+        }
+    }
+
+    fn flatten(&self) -> Vec<Polyline>
+    {
+        let mut polylines: Vec<Polyline> = Vec::new();
+        let mut current = Polyline { points: Vec::new(), closed: false };
+        let mut start = Point { x: 0.0, y: 0.0 };
+        let mut last = Point { x: 0.0, y: 0.0 };
+
+        for command in self.commands.iter()
+        {
+            match command
+            {
+                PathCommand::MoveTo(point) =>
+                {
+                    // a move on its own draws nothing
+                    if current.points.len() > 1
+                    {
+                        polylines.push(current);
+                    }
+
+                    current = Polyline { points: vec![*point], closed: false };
+                    start = *point;
+                    last = *point;
+                    continue;
+                },
+                PathCommand::Close =>
+                {
+                    if !current.points.is_empty()
+                    {
+                        current.closed = true;
+                        polylines.push(current);
+                    }
+
+                    // drawing on after a close starts a new contour at the same point
+                    current = Polyline { points: Vec::new(), closed: false };
+                    last = start;
+                    continue;
+                },
+                _ => {}
+            }
+
+            if current.points.is_empty()
+            {
+                current.points.push(last);
+            }
+
+            match command
+            {
+                PathCommand::LineTo(point) => current.points.push(*point),
+                PathCommand::QuadTo(control, point) =>
+                {
+                    let deviation = length(last.x - 2.0 * control.x + point.x, last.y - 2.0 * control.y + point.y);
+                    let segments = crate::drawing::raster::segment_count(deviation);
+
+                    for i in 1..=segments
+                    {
+                        let t = i as f32 / segments as f32;
+                        let mt = 1.0 - t;
+
+                        current.points.push(Point {
+                            x: mt * mt * last.x + 2.0 * mt * t * control.x + t * t * point.x,
+                            y: mt * mt * last.y + 2.0 * mt * t * control.y + t * t * point.y
+                        });
+                    }
+                },
+                PathCommand::CubicTo(control1, control2, point) =>
+                {
+                    let deviation = length(last.x - 2.0 * control1.x + control2.x, last.y - 2.0 * control1.y + control2.y)
+                        .max(length(control1.x - 2.0 * control2.x + point.x, control1.y - 2.0 * control2.y + point.y));
+                    let segments = crate::drawing::raster::segment_count(deviation * 1.5);
+
+                    for i in 1..=segments
+                    {
+                        let t = i as f32 / segments as f32;
+                        let mt = 1.0 - t;
+
+                        let a = mt * mt * mt;
+                        let b = 3.0 * mt * mt * t;
+                        let c = 3.0 * mt * t * t;
+                        let d = t * t * t;
+
+                        current.points.push(Point {
+                            x: a * last.x + b * control1.x + c * control2.x + d * point.x,
+                            y: a * last.y + b * control1.y + c * control2.y + d * point.y
+                        });
+                    }
+                },
+                _ => {}
+            }
+
+            last = *current.points.last().unwrap_or(&last);
+        }
+
+        if current.points.len() > 1
+        {
+            polylines.push(current);
+        }
+
+        return polylines;
+    }
+}
+
+// cuts a polyline into the open pieces that fall on the dashes of the pattern
+fn dash_polyline(polyline: &Polyline, pattern: &[f32], offset: f32) -> Vec<Polyline>
+{
+    let mut dashes = Vec::new();
+
+    if polyline.points.len() < 2
+    {
+        return dashes;
+    }
+
+    // an odd pattern is repeated to make dash and gap alternate
+    let pattern: Vec<f32> = if pattern.len() % 2 == 1 { pattern.iter().chain(pattern.iter()).copied().collect() } else { pattern.to_vec() };
+    let total: f32 = pattern.iter().sum();
+
+    let mut index = 0;
+    let mut skip = offset.rem_euclid(total);
+
+    while skip > 0.0 && skip >= pattern[index]
+    {
+        skip -= pattern[index];
+        index = (index + 1) % pattern.len();
+    }
+
+    let mut remaining = pattern[index] - skip;
+    let mut current: Vec<Point> = if index % 2 == 0 { vec![polyline.points[0]] } else { Vec::new() };
+
+    let count = polyline.points.len();
+    let segment_count = if polyline.closed { count } else { count - 1 };
+
+    for i in 0..segment_count
+    {
+        let from = polyline.points[i];
+        let to = polyline.points[(i + 1) % count];
+        let segment_length = distance(from, to);
+
+        let mut position = 0.0;
+
+        while segment_length - position > remaining
+        {
+            position += remaining;
+
+            let t = position / segment_length;
+            let point = Point { x: from.x + (to.x - from.x) * t, y: from.y + (to.y - from.y) * t };
+
+            if index % 2 == 0
+            {
+                current.push(point);
+                dashes.push(Polyline { points: std::mem::take(&mut current), closed: false });
+            }
+            else
+            {
+                current = vec![point];
+            }
+
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+
+        remaining -= segment_length - position;
+
+        if index % 2 == 0
+        {
+            current.push(to);
+        }
+    }
+
+    if index % 2 == 0 && current.len() > 1
+    {
+        dashes.push(Polyline { points: current, closed: false });
+    }
+
+    return dashes;
+}
+
+fn stroke_polyline(rasterizer: &mut Rasterizer, polyline: &Polyline, style: &StrokeStyle)
+{
+    let half_width = style.width / 2.0;
+
+    let mut points: Vec<Point> = Vec::with_capacity(polyline.points.len());
+
+    for point in polyline.points.iter()
+    {
+        if points.last().is_none_or(|last| distance(*last, *point) > POINT_EPSILON)
+        {
+            points.push(*point);
+        }
+    }
+
+    if polyline.closed && points.len() > 1 && distance(points[0], points[points.len() - 1]) <= POINT_EPSILON
+    {
+        points.pop();
+    }
+
+    // a lone point only shows up as its caps
+    if points.len() == 1
+    {
+        if !polyline.closed
+        {
+            match style.cap
+            {
+                LineCap::Round => add_circle(rasterizer, points[0], half_width),
+                LineCap::Square => add_cap(rasterizer, points[0], Point { x: 1.0, y: 0.0 }, half_width, LineCap::Square),
+                LineCap::Butt => {}
+            }
+        }
+
+        return;
+    }
+
+    if points.len() < 2
+    {
+        return;
+    }
+
+    let count = points.len();
+    let segment_count = if polyline.closed { count } else { count - 1 };
+
+    let directions: Vec<Point> = (0..segment_count).map(|i| unit(points[i], points[(i + 1) % count])).collect();
+
+    for i in 0..segment_count
+    {
+        let from = points[i];
+        let to = points[(i + 1) % count];
+        let normal = scale(perpendicular(directions[i]), half_width);
+
+        add_polygon(rasterizer, &[
+            add(from, normal),
+            add(to, normal),
+            subtract(to, normal),
+            subtract(from, normal)
+        ]);
+    }
+
+    if polyline.closed
+    {
+        for i in 0..count
+        {
+            add_join(rasterizer, points[i], directions[(i + segment_count - 1) % segment_count], directions[i], half_width, style);
+        }
+    }
+    else
+    {
+        for i in 1..count - 1
+        {
+            add_join(rasterizer, points[i], directions[i - 1], directions[i], half_width, style);
+        }
+
+        add_cap(rasterizer, points[0], scale(directions[0], -1.0), half_width, style.cap);
+        add_cap(rasterizer, points[count - 1], directions[segment_count - 1], half_width, style.cap);
+    }
+}
+
+// fills the gap on the outside of the turn from one segment direction to the next
+fn add_join(rasterizer: &mut Rasterizer, point: Point, incoming: Point, outgoing: Point, half_width: f32, style: &StrokeStyle)
+{
+    let cross = incoming.x * outgoing.y - incoming.y * outgoing.x;
+    let dot = incoming.x * outgoing.x + incoming.y * outgoing.y;
+
+    if cross.abs() < 1e-6 && dot > 0.0
+    {
+        return;
+    }
+
+    if style.join == LineJoin::Round
+    {
+        add_circle(rasterizer, point, half_width);
+        return;
+    }
+
+    // the outer side of the turn is opposite the direction it bends towards
+    let side = if cross > 0.0 { -half_width } else { half_width };
+
+    let outer_incoming = add(point, scale(perpendicular(incoming), side));
+    let outer_outgoing = add(point, scale(perpendicular(outgoing), side));
+
+    if style.join == LineJoin::Miter
+    {
+        let cosine_half = ((1.0 + dot) / 2.0).max(0.0).sqrt();
+
+        if cosine_half > 0.0 && 1.0 / cosine_half <= style.miter_limit
+        {
+            let bisector = add(perpendicular(incoming), perpendicular(outgoing));
+            let bisector = scale(bisector, side / length(bisector.x, bisector.y) / cosine_half);
+
+            add_polygon(rasterizer, &[point, outer_incoming, add(point, bisector), outer_outgoing]);
+            return;
+        }
+    }
+
+    add_polygon(rasterizer, &[point, outer_incoming, outer_outgoing]);
+}
+
+// extends an open end of the stroke past the point in the given direction
+fn add_cap(rasterizer: &mut Rasterizer, point: Point, direction: Point, half_width: f32, cap: LineCap)
+{
+    match cap
+    {
+        LineCap::Butt => {},
+        LineCap::Round => add_circle(rasterizer, point, half_width),
+        LineCap::Square =>
+        {
+            let normal = scale(perpendicular(direction), half_width);
+            let extension = scale(direction, half_width);
+
+            add_polygon(rasterizer, &[
+                add(point, normal),
+                add(add(point, normal), extension),
+                add(subtract(point, normal), extension),
+                subtract(point, normal)
+            ]);
+        }
+    }
+}
+
+fn add_circle(rasterizer: &mut Rasterizer, center: Point, radius: f32)
+{
+    if radius <= 0.0
+    {
+        return;
+    }
+
+    let step = 2.0 * (1.0 - (ROUND_TOLERANCE / radius).min(1.0)).acos();
+    let segments = ((TAU / step).ceil() as usize).clamp(8, 256);
+
+    let points: Vec<Point> = (0..segments).map(|i| {
+        let angle = i as f32 / segments as f32 * TAU;
+        Point { x: center.x + radius * angle.cos(), y: center.y + radius * angle.sin() }
+    }).collect();
+
+    add_polygon(rasterizer, &points);
+}
+
+// adds a closed polygon wound in the same direction as every other stroke piece, so overlaps do not cancel out
+fn add_polygon(rasterizer: &mut Rasterizer, points: &[Point])
+{
+    let mut area = 0.0;
+
+    for i in 0..points.len()
+    {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+
+    let mut ordered = points.to_vec();
+
+    if area < 0.0
+    {
+        ordered.reverse();
+    }
+
+    rasterizer.move_to(ordered[0]);
+
+    for point in ordered.iter().skip(1)
+    {
+        rasterizer.line_to(*point);
+    }
+
+    rasterizer.close();
+}
+
+fn unit(from: Point, to: Point) -> Point
+{
+    let length = distance(from, to);
+    return Point { x: (to.x - from.x) / length, y: (to.y - from.y) / length };
+}
+
+fn perpendicular(direction: Point) -> Point
+{
+    return Point { x: -direction.y, y: direction.x };
+}
+
+fn add(a: Point, b: Point) -> Point
+{
+    return Point { x: a.x + b.x, y: a.y + b.y };
+}
+
+fn subtract(a: Point, b: Point) -> Point
+{
+    return Point { x: a.x - b.x, y: a.y - b.y };
+}
+
+fn scale(point: Point, factor: f32) -> Point
+{
+    return Point { x: point.x * factor, y: point.y * factor };
+}
+
+fn distance(a: Point, b: Point) -> f32
+{
+    return length(b.x - a.x, b.y - a.y);
+}
+
+fn length(x: f32, y: f32) -> f32
+{
+    return (x * x + y * y).sqrt();
+}
+
+/***************************************************************
+* MARK: TESTS
+***************************************************************/
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn line(points: &[(f32, f32)], closed: bool) -> Polyline
+    {
+        return Polyline { points: points.iter().map(|(x, y)| Point { x: *x, y: *y }).collect(), closed: closed };
+    }
+
+    fn spans(dashes: &[Polyline]) -> Vec<Vec<(f32, f32)>>
+    {
+        return dashes.iter().map(|dash| dash.points.iter().map(|point| ((point.x * 1000.0).round() / 1000.0, (point.y * 1000.0).round() / 1000.0)).collect()).collect();
+    }
+
+    fn end_point(path: &Path) -> Point
+    {
+        return match path.commands().last()
+        {
+            Some(PathCommand::CubicTo(_, _, point)) => *point,
+            _ => panic!("the arc ends in a curve")
+        };
+    }
+
+    #[test]
+    fn dashes_split_a_line()
+    {
+        let dashes = dash_polyline(&line(&[(0.0, 0.0), (10.0, 0.0)], false), &[3.0, 2.0], 0.0);
+        assert_eq!(spans(&dashes), vec![vec![(0.0, 0.0), (3.0, 0.0)], vec![(5.0, 0.0), (8.0, 0.0)]]);
+
+        // the offset moves the pattern back along the line
+        let dashes = dash_polyline(&line(&[(0.0, 0.0), (10.0, 0.0)], false), &[3.0, 2.0], 4.0);
+        assert_eq!(spans(&dashes), vec![vec![(1.0, 0.0), (4.0, 0.0)], vec![(6.0, 0.0), (9.0, 0.0)]]);
+    }
+
+    #[test]
+    fn dashes_follow_corners_and_odd_patterns()
+    {
+        let dashes = dash_polyline(&line(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0)], false), &[6.0, 1.0], 0.0);
+        assert_eq!(spans(&dashes), vec![vec![(0.0, 0.0), (4.0, 0.0), (4.0, 2.0)], vec![(4.0, 3.0), (4.0, 4.0)]]);
+
+        // an odd pattern alternates, so the second 2 is a gap and the third a dash
+        let dashes = dash_polyline(&line(&[(0.0, 0.0), (10.0, 0.0)], false), &[2.0], 0.0);
+        assert_eq!(spans(&dashes), vec![vec![(0.0, 0.0), (2.0, 0.0)], vec![(4.0, 0.0), (6.0, 0.0)], vec![(8.0, 0.0), (10.0, 0.0)]]);
+
+        // a closed polyline is dashed along its closing side too
+        let dashes = dash_polyline(&line(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)], true), &[14.0, 1.0], 0.0);
+        assert_eq!(spans(&dashes), vec![vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0), (0.0, 2.0)], vec![(0.0, 1.0), (0.0, 0.0)]]);
+    }
+
+    #[test]
+    fn elliptical_arcs_end_at_the_point()
+    {
+        for (large_arc, clockwise) in [(false, false), (false, true), (true, false), (true, true)]
+        {
+            let mut path = Path::default();
+            path.move_to(Point { x: 0.0, y: 0.0 });
+            path.elliptical_arc_to(10.0, 5.0, 0.5, large_arc, clockwise, Point { x: 8.0, y: 3.0 });
+
+            let point = end_point(&path);
+            assert!(distance(point, Point { x: 8.0, y: 3.0 }) < 1e-4, "{} {}", large_arc, clockwise);
+        }
+
+        // radii too small for the chord are scaled up into a half circle
+        let mut path = Path::new();
+        path.move_to(Point { x: 0.0, y: 0.0 });
+        path.elliptical_arc_to(1.0, 1.0, 0.0, false, true, Point { x: 10.0, y: 0.0 });
+
+        assert!(distance(end_point(&path), Point { x: 10.0, y: 0.0 }) < 1e-4);
+        assert!((path.bounds().size.height - 5.0).abs() < 0.01, "{}", path.bounds().size.height);
+    }
+}
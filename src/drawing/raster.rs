@@ -0,0 +1,435 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  raster.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the anti-aliased scanline rasterizer.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::Point;
+
+use crate::drawing::transform::Transform;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillRule
+{
+    NonZero,
+    EvenOdd
+}
+
+// 8-bit coverage mask produced by the rasterizer
+pub struct Mask
+{
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>
+}
+
+// collects flattened edges in mask space and converts them to coverage
+pub struct Rasterizer
+{
+    width: usize,
+    height: usize,
+    origin: Point,
+
+    // applied to every point before it is flattened, start and current are already transformed
+    transform: Transform,
+    edges: Vec<Edge>,
+    start: Point,
+    current: Point
+}
+
+#[derive(Clone, Copy)]
+struct Edge
+{
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    winding: i32
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+// vertical samples per pixel row, horizontal coverage is computed exactly
+const SUBSAMPLES: usize = 8;
+
+// maximum distance in pixels between a curve and its flattened lines
+const FLATTEN_TOLERANCE: f32 = 0.1;
+
+const MAX_CURVE_SEGMENTS: usize = 256;
+
+// three box blurs come within a few percent of a gaussian
+const BLUR_PASSES: usize = 3;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+impl Rasterizer
+{
+    pub fn new(width: usize, height: usize) -> Rasterizer
+    {
+        return Rasterizer::new_at(Point { x: 0.0, y: 0.0 }, width, height);
+    }
+
+    // points are given in a space where the top left of the mask sits at origin
+    pub fn new_at(origin: Point, width: usize, height: usize) -> Rasterizer
+    {
+        return Rasterizer {
+            width: width,
+            height: height,
+            origin: origin,
+            transform: Transform::identity(),
+            edges: Vec::new(),
+            start: Point { x: 0.0, y: 0.0 },
+            current: Point { x: 0.0, y: 0.0 }
+        };
+    }
+
+    // maps the points of everything added afterwards, curves are transformed before they are flattened so they stay smooth when scaled up
+    pub fn set_transform(&mut self, transform: Transform)
+    {
+        self.transform = transform;
+    }
+
+    pub fn move_to(&mut self, point: Point)
+    {
+        let point = self.transform.apply(point);
+
+        self.close();
+        self.start = point;
+        self.current = point;
+    }
+
+    pub fn line_to(&mut self, point: Point)
+    {
+        let point = self.transform.apply(point);
+        self.line_to_transformed(point);
+    }
+
+    pub fn quad_to(&mut self, control: Point, point: Point)
+    {
+        let from = self.current;
+        let control = self.transform.apply(control);
+        let point = self.transform.apply(point);
+
+        let deviation = length(from.x - 2.0 * control.x + point.x, from.y - 2.0 * control.y + point.y);
+        let segments = segment_count(deviation);
+
+        for i in 1..=segments
+        {
+            let t = i as f32 / segments as f32;
+            let mt = 1.0 - t;
+
+            self.line_to_transformed(Point {
+                x: mt * mt * from.x + 2.0 * mt * t * control.x + t * t * point.x,
+                y: mt * mt * from.y + 2.0 * mt * t * control.y + t * t * point.y
+            });
+        }
+    }
+
+    pub fn cubic_to(&mut self, control1: Point, control2: Point, point: Point)
+    {
+        let from = self.current;
+        let control1 = self.transform.apply(control1);
+        let control2 = self.transform.apply(control2);
+        let point = self.transform.apply(point);
+
+        let deviation = length(from.x - 2.0 * control1.x + control2.x, from.y - 2.0 * control1.y + control2.y)
+            .max(length(control1.x - 2.0 * control2.x + point.x, control1.y - 2.0 * control2.y + point.y));
+        let segments = segment_count(deviation * 1.5);
+
+        for i in 1..=segments
+        {
+            let t = i as f32 / segments as f32;
+            let mt = 1.0 - t;
+
+            let a = mt * mt * mt;
+            let b = 3.0 * mt * mt * t;
+            let c = 3.0 * mt * t * t;
+            let d = t * t * t;
+
+            self.line_to_transformed(Point {
+                x: a * from.x + b * control1.x + c * control2.x + d * point.x,
+                y: a * from.y + b * control1.y + c * control2.y + d * point.y
+            });
+        }
+    }
+
+    // joins the current point back to the start of the contour
+    pub fn close(&mut self)
+    {
+        self.add_line(self.current, self.start);
+        self.current = self.start;
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        return self.edges.is_empty();
+    }
+
+    pub fn rasterize(&mut self, fill_rule: FillRule) -> Mask
+    {
+        self.close();
+
+        let mut mask = Mask {
+            width: self.width,
+            height: self.height,
+            data: vec![0; self.width * self.height]
+        };
+
+        if self.edges.is_empty() || self.width == 0 || self.height == 0
+        {
+            return mask;
+        }
+
+        self.edges.sort_by(|a, b| a.y0.total_cmp(&b.y0));
+
+        let mut accumulator: Vec<f32> = vec![0.0; self.width];
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        let mut active: Vec<Edge> = Vec::new();
+        let mut next_edge = 0;
+
+        let sample_weight = 1.0 / SUBSAMPLES as f32;
+
+        for row in 0..self.height
+        {
+            accumulator.iter_mut().for_each(|value| *value = 0.0);
+
+            for sample in 0..SUBSAMPLES
+            {
+                let sample_y = row as f32 + (sample as f32 + 0.5) * sample_weight;
+
+                while next_edge < self.edges.len() && self.edges[next_edge].y0 <= sample_y
+                {
+                    active.push(self.edges[next_edge]);
+                    next_edge += 1;
+                }
+
+                active.retain(|edge| edge.y1 > sample_y);
+
+                crossings.clear();
+
+                for edge in active.iter()
+                {
+                    if sample_y >= edge.y0 && sample_y < edge.y1
+                    {
+                        let x = edge.x0 + (sample_y - edge.y0) * (edge.x1 - edge.x0) / (edge.y1 - edge.y0);
+                        crossings.push((x, edge.winding));
+                    }
+                }
+
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+
+                for i in 0..crossings.len()
+                {
+                    winding += crossings[i].1;
+
+                    let inside = match fill_rule
+                    {
+                        FillRule::NonZero => winding != 0,
+                        FillRule::EvenOdd => winding % 2 != 0
+                    };
+
+                    if inside && i + 1 < crossings.len()
+                    {
+                        add_span(&mut accumulator, crossings[i].0, crossings[i + 1].0, sample_weight);
+                    }
+                }
+            }
+
+            let row_start = row * self.width;
+
+            for (coverage, value) in mask.data[row_start..row_start + self.width].iter_mut().zip(accumulator.iter())
+            {
+                *coverage = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+
+        return mask;
+    }
+}
+
+impl Mask
+{
+    // approximates a gaussian blur with the given standard deviation by three box blurs in each direction
+    pub fn blur(&mut self, sigma: f32)
+    {
+        if sigma <= 0.0 || self.width == 0 || self.height == 0
+        {
+            return;
+        }
+
+        let ideal_width = (12.0 * sigma * sigma / BLUR_PASSES as f32 + 1.0).sqrt();
+        let radius = ((ideal_width - 1.0) / 2.0).round().max(1.0) as usize;
+
+        let mut values: Vec<f32> = self.data.iter().map(|value| *value as f32).collect();
+        let mut line: Vec<f32> = Vec::new();
+
+        for _ in 0..BLUR_PASSES
+        {
+            for row in 0..self.height
+            {
+                line.clear();
+                line.extend_from_slice(&values[row * self.width..(row + 1) * self.width]);
+                box_blur_line(&line, radius, |x, value| values[row * self.width + x] = value);
+            }
+
+            for column in 0..self.width
+            {
+                line.clear();
+                line.extend((0..self.height).map(|row| values[row * self.width + column]));
+                box_blur_line(&line, radius, |y, value| values[y * self.width + column] = value);
+            }
+        }
+
+        for (target, value) in self.data.iter_mut().zip(values.iter())
+        {
+            *target = value.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    // scales every value by the matching value of another mask of the same size
+    pub fn intersect(&mut self, other: &Mask)
+    {
+        for (value, other_value) in self.data.iter_mut().zip(other.data.iter())
+        {
+            *value = ((*value as u32 * *other_value as u32 + 127) / 255) as u8;
+        }
+    }
+
+    // keeps only the coverage outside another mask of the same size
+    pub fn subtract(&mut self, other: &Mask)
+    {
+        for (value, other_value) in self.data.iter_mut().zip(other.data.iter())
+        {
+            *value = ((*value as u32 * (255 - *other_value as u32) + 127) / 255) as u8;
+        }
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+impl Rasterizer
+{
+    fn line_to_transformed(&mut self, point: Point)
+    {
+        self.add_line(self.current, point);
+        self.current = point;
+    }
+
+    fn add_line(&mut self, from: Point, to: Point)
+    {
+        let from = Point { x: from.x - self.origin.x, y: from.y - self.origin.y };
+        let to = Point { x: to.x - self.origin.x, y: to.y - self.origin.y };
+
+        if from.y == to.y || !from.x.is_finite() || !from.y.is_finite() || !to.x.is_finite() || !to.y.is_finite()
+        {
+            return;
+        }
+
+        // edges are stored top to bottom, the winding remembers the original direction
+        if from.y < to.y
+        {
+            self.edges.push(Edge { x0: from.x, y0: from.y, x1: to.x, y1: to.y, winding: 1 });
+        }
+        else
+        {
+            self.edges.push(Edge { x0: to.x, y0: to.y, x1: from.x, y1: from.y, winding: -1 });
+        }
+    }
+}
+
+// averages each value with its neighbours up to radius away, treating values past the ends as zero
+fn box_blur_line<F: FnMut(usize, f32)>(line: &[f32], radius: usize, mut write: F)
+{
+    let window = (radius * 2 + 1) as f32;
+    let mut sum: f32 = line.iter().take(radius).sum();
+
+    for index in 0..line.len()
+    {
+        if index + radius < line.len()
+        {
+            sum += line[index + radius];
+        }
+
+        write(index, sum / window);
+
+        if index >= radius
+        {
+            sum -= line[index - radius];
+        }
+    }
+}
+
+// adds horizontal coverage for [start, end) with fractional end pixels
+fn add_span(accumulator: &mut [f32], start: f32, end: f32, weight: f32)
+{
+    let width = accumulator.len() as f32;
+    let start = start.clamp(0.0, width);
+    let end = end.clamp(0.0, width);
+
+    if end <= start
+    {
+        return;
+    }
+
+    let first = start.floor() as usize;
+    let last = end.floor() as usize;
+
+    if first == last
+    {
+        accumulator[first] += (end - start) * weight;
+        return;
+    }
+
+    accumulator[first] += (first as f32 + 1.0 - start) * weight;
+
+    for value in &mut accumulator[(first + 1)..last]
+    {
+        *value += weight;
+    }
+
+    if last < accumulator.len()
+    {
+        accumulator[last] += (end - last as f32) * weight;
+    }
+}
+
+// lines needed to keep a curve with the given second difference within the flatten tolerance
+pub(crate) fn segment_count(deviation: f32) -> usize
+{
+    let segments = (deviation / (4.0 * FLATTEN_TOLERANCE)).sqrt().ceil();
+
+    if !segments.is_finite()
+    {
+        return 1;
+    }
+
+    return (segments as usize).clamp(1, MAX_CURVE_SEGMENTS);
+}
+
+fn length(x: f32, y: f32) -> f32
+{
+    return (x * x + y * y).sqrt();
+}
@@ -0,0 +1,1199 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  svg.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains parsing and rendering of the SVG subset
+**                 used for icons.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect, debug::*};
+
+pub use crate::Svg;
+
+use crate::drawing::DrawingContext;
+use crate::drawing::path::{Path, StrokeStyle, LineJoin, LineCap};
+use crate::drawing::raster::FillRule;
+use crate::drawing::transform::Transform;
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+#[derive(Clone, Copy)]
+enum SvgPaint
+{
+    Color(Color),
+
+    // resolved to the color passed in when the document is drawn, so icons can follow the text color
+    CurrentColor
+}
+
+// the presentation attributes an element inherits from its parents
+#[derive(Clone)]
+struct SvgStyle
+{
+    pub fill: Option<SvgPaint>,
+    pub fill_rule: FillRule,
+    pub fill_opacity: f32,
+
+    pub stroke: Option<SvgPaint>,
+    pub stroke_style: StrokeStyle,
+    pub stroke_opacity: f32,
+
+    // group opacity is multiplied into the shapes rather than composited as a layer
+    pub opacity: f32,
+    pub visible: bool,
+
+    // from the element's coordinates to the coordinates of the view box
+    pub transform: Transform
+}
+
+// a shape flattened out of the document tree with its transform already applied
+struct SvgShape
+{
+    pub path: Path,
+
+    pub fill: Option<(SvgPaint, f32)>,
+    pub fill_rule: FillRule,
+
+    pub stroke: Option<(SvgPaint, f32)>,
+    pub stroke_style: StrokeStyle
+}
+
+struct SvgDocument
+{
+    pub size: Size,
+    pub view_box: Rect,
+
+    // none stretches the view box to the drawn rect, otherwise the alignment in x and y from 0 to 1 and whether to slice
+    pub aspect: Option<(f32, f32, bool)>,
+
+    pub shapes: Vec<SvgShape>
+}
+
+struct SvgObject
+{
+    pub document: Rc<SvgDocument>,
+    pub path: Option<String>
+}
+
+// tokenizer for path data and number lists
+struct PathDataParser<'a>
+{
+    pub data: &'a [u8],
+    pub position: usize
+}
+
+struct SvgState
+{
+    pub svg_id_allocator: Svg,
+    pub svgs: HashMap<Svg, SvgObject>,
+    pub cache: HashMap<String, Svg>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static SVG_STATE: RefCell<SvgState> = RefCell::new(SvgState {
+        svg_id_allocator: 1,
+        svgs: HashMap::new(),
+        cache: HashMap::new()
+    });
+}
+
+// the size used for documents that give neither a size nor a view box
+const DEFAULT_SIZE: Size = Size { width: 100.0, height: 100.0 };
+
+const NAMED_COLORS: [(&str, (u8, u8, u8)); 16] = [
+    ("black", (0, 0, 0)),
+    ("white", (255, 255, 255)),
+    ("red", (255, 0, 0)),
+    ("green", (0, 128, 0)),
+    ("lime", (0, 255, 0)),
+    ("blue", (0, 0, 255)),
+    ("yellow", (255, 255, 0)),
+    ("orange", (255, 165, 0)),
+    ("purple", (128, 0, 128)),
+    ("cyan", (0, 255, 255)),
+    ("magenta", (255, 0, 255)),
+    ("gray", (128, 128, 128)),
+    ("grey", (128, 128, 128)),
+    ("silver", (192, 192, 192)),
+    ("navy", (0, 0, 128)),
+    ("maroon", (128, 0, 0))
+];
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+// loads an SVG file, loading the same path again returns the cached document
+pub fn load_file(path: &str) -> Option<Svg>
+{
+    if let Some(svg) = SVG_STATE.with(|state| state.borrow().cache.get(path).copied())
+    {
+        return Some(svg);
+    }
+
+    match std::fs::read_to_string(path)
+    {
+        Ok(text) => return load(&text, Some(String::from(path))),
+        Err(error) =>
+        {
+            log(LogLevel::FAIL, &format!("Failed to read SVG file {}: {}", path, error));
+            return None;
+        }
+    }
+}
+
+pub fn load_str(text: &str) -> Option<Svg>
+{
+    return load(text, None);
+}
+
+pub fn unload(svg: Svg)
+{
+    SVG_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        match state.svgs.remove(&svg)
+        {
+            Some(svg_object) =>
+            {
+                if let Some(path) = svg_object.path
+                {
+                    state.cache.remove(&path);
+                }
+            },
+            None => log(LogLevel::FAIL, &format!("SVG not found!"))
+        }
+    });
+}
+
+// the size the document asks for, falling back to the size of its view box
+pub fn get_size(svg: Svg) -> Size
+{
+    return match get_document(svg)
+    {
+        Some(document) => document.size,
+        None => Size { width: 0.0, height: 0.0 }
+    };
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn load(text: &str, path: Option<String>) -> Option<Svg>
+{
+    let document = match roxmltree::Document::parse(text)
+    {
+        Ok(document) => document,
+        Err(error) =>
+        {
+            log(LogLevel::FAIL, &format!("Failed to parse SVG: {}", error));
+            return None;
+        }
+    };
+
+    let root = document.root_element();
+
+    if root.tag_name().name() != "svg"
+    {
+        log(LogLevel::FAIL, &format!("Failed to parse SVG: the root element is not svg"));
+        return None;
+    }
+
+    let svg_document = parse_document(root);
+    let shape_count = svg_document.shapes.len();
+
+    let new_svg = SVG_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let new_svg_id = state.svg_id_allocator;
+        state.svg_id_allocator += 1;
+
+        if let Some(path) = &path
+        {
+            state.cache.insert(path.clone(), new_svg_id);
+        }
+
+        state.svgs.insert(new_svg_id, SvgObject {
+            document: Rc::new(svg_document),
+            path: path
+        });
+
+        return new_svg_id;
+    });
+
+    log(LogLevel::INFO, &format!("Loaded SVG with {} shapes with ID: {}", shape_count, new_svg));
+
+    return Some(new_svg);
+}
+
+fn get_document(svg: Svg) -> Option<Rc<SvgDocument>>
+{
+    return SVG_STATE.with(|state| {
+        if let Some(svg_object) = state.borrow().svgs.get(&svg)
+        {
+            return Some(svg_object.document.clone());
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("SVG not found!"));
+            return None;
+        }
+    });
+}
+
+fn parse_document(root: roxmltree::Node) -> SvgDocument
+{
+    let width = root.attribute("width").and_then(parse_length);
+    let height = root.attribute("height").and_then(parse_length);
+
+    let view_box = root.attribute("viewBox").and_then(|value| {
+        let numbers = parse_numbers(value);
+
+        if numbers.len() == 4 && numbers[2] > 0.0 && numbers[3] > 0.0
+        {
+            return Some(Rect { origin: Point { x: numbers[0], y: numbers[1] }, size: Size { width: numbers[2], height: numbers[3] } });
+        }
+
+        return None;
+    });
+
+    // a missing width or height follows the aspect ratio of the view box
+    let size = match (width, height, view_box)
+    {
+        (Some(width), Some(height), _) => Size { width: width, height: height },
+        (Some(width), None, Some(view_box)) => Size { width: width, height: width * view_box.size.height / view_box.size.width },
+        (None, Some(height), Some(view_box)) => Size { width: height * view_box.size.width / view_box.size.height, height: height },
+        (None, None, Some(view_box)) => view_box.size,
+        (width, height, None) => Size { width: width.unwrap_or(DEFAULT_SIZE.width), height: height.unwrap_or(DEFAULT_SIZE.height) }
+    };
+
+    let view_box = view_box.unwrap_or(Rect { origin: Point { x: 0.0, y: 0.0 }, size: size });
+
+    let style = SvgStyle {
+        fill: Some(SvgPaint::Color(Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 })),
+        fill_rule: FillRule::NonZero,
+        fill_opacity: 1.0,
+        stroke: None,
+        stroke_style: StrokeStyle::new(1.0),
+        stroke_opacity: 1.0,
+        opacity: 1.0,
+        visible: true,
+        transform: Transform::identity()
+    };
+
+    // the root element's own presentation attributes apply to the whole document
+    let mut shapes = Vec::new();
+    parse_children(root, &parse_style(root, &style), &mut shapes);
+
+    return SvgDocument {
+        size: size,
+        view_box: view_box,
+        aspect: parse_aspect(root.attribute("preserveAspectRatio").unwrap_or("")),
+        shapes: shapes
+    };
+}
+
+fn parse_children(node: roxmltree::Node, style: &SvgStyle, shapes: &mut Vec<SvgShape>)
+{
+    for child in node.children().filter(|child| child.is_element())
+    {
+        parse_element(child, style, shapes);
+    }
+}
+
+fn parse_element(node: roxmltree::Node, parent_style: &SvgStyle, shapes: &mut Vec<SvgShape>)
+{
+    if get_property(node, "display").as_deref() == Some("none")
+    {
+        return;
+    }
+
+    let style = parse_style(node, parent_style);
+
+    let path = match node.tag_name().name()
+    {
+        "g" | "svg" | "a" =>
+        {
+            parse_children(node, &style, shapes);
+            return;
+        },
+        "path" => parse_path_data(node.attribute("d").unwrap_or("")),
+        "rect" =>
+        {
+            let rect = Rect {
+                origin: Point { x: length_attribute(node, "x"), y: length_attribute(node, "y") },
+                size: Size { width: length_attribute(node, "width"), height: length_attribute(node, "height") }
+            };
+
+            // a single radius applies to both axes
+            let radius_x = node.attribute("rx").and_then(parse_length);
+            let radius_y = node.attribute("ry").and_then(parse_length);
+
+            let mut path = Path::new();
+
+            if rect.size.width > 0.0 && rect.size.height > 0.0
+            {
+                path.add_rounded_rect(rect, radius_x.or(radius_y).unwrap_or(0.0), radius_y.or(radius_x).unwrap_or(0.0));
+            }
+
+            path
+        },
+        "circle" =>
+        {
+            let radius = length_attribute(node, "r");
+            ellipse_path(Point { x: length_attribute(node, "cx"), y: length_attribute(node, "cy") }, radius, radius)
+        },
+        "ellipse" =>
+        {
+            ellipse_path(Point { x: length_attribute(node, "cx"), y: length_attribute(node, "cy") }, length_attribute(node, "rx"), length_attribute(node, "ry"))
+        },
+        "line" =>
+        {
+            let mut path = Path::new();
+            path.move_to(Point { x: length_attribute(node, "x1"), y: length_attribute(node, "y1") });
+            path.line_to(Point { x: length_attribute(node, "x2"), y: length_attribute(node, "y2") });
+            path
+        },
+        "polyline" | "polygon" =>
+        {
+            let numbers = parse_numbers(node.attribute("points").unwrap_or(""));
+            let mut path = Path::new();
+
+            for (index, pair) in numbers.chunks_exact(2).enumerate()
+            {
+                let point = Point { x: pair[0], y: pair[1] };
+
+                if index == 0 { path.move_to(point); } else { path.line_to(point); }
+            }
+
+            if node.tag_name().name() == "polygon" && !path.is_empty()
+            {
+                path.close();
+            }
+
+            path
+        },
+
+        // definitions, text and anything else outside the subset draw nothing
+        _ => return
+    };
+
+    if path.is_empty() || !style.visible
+    {
+        return;
+    }
+
+    let mut stroke_style = style.stroke_style.clone();
+    stroke_style.width *= style.transform.scale_factor();
+
+    shapes.push(SvgShape {
+        path: path.transformed(&style.transform),
+        fill: style.fill.map(|paint| (paint, style.fill_opacity * style.opacity)),
+        fill_rule: style.fill_rule,
+        stroke: style.stroke.filter(|_| stroke_style.width > 0.0).map(|paint| (paint, style.stroke_opacity * style.opacity)),
+        stroke_style: stroke_style
+    });
+}
+
+// the element's presentation attributes and style declarations on top of its parent's
+fn parse_style(node: roxmltree::Node, parent_style: &SvgStyle) -> SvgStyle
+{
+    let mut style = parent_style.clone();
+
+    if let Some(value) = get_property(node, "fill")
+    {
+        style.fill = parse_paint(&value, parent_style.fill);
+    }
+
+    if let Some(value) = get_property(node, "stroke")
+    {
+        style.stroke = parse_paint(&value, parent_style.stroke);
+    }
+
+    if let Some(value) = get_property(node, "fill-rule")
+    {
+        style.fill_rule = if value == "evenodd" { FillRule::EvenOdd } else { FillRule::NonZero };
+    }
+
+    if let Some(value) = get_property(node, "stroke-width").and_then(|value| parse_length(&value))
+    {
+        style.stroke_style.width = value.max(0.0);
+    }
+
+    if let Some(value) = get_property(node, "stroke-linejoin")
+    {
+        style.stroke_style.join = match value.as_str()
+        {
+            "round" => LineJoin::Round,
+            "bevel" => LineJoin::Bevel,
+            _ => LineJoin::Miter
+        };
+    }
+
+    if let Some(value) = get_property(node, "stroke-linecap")
+    {
+        style.stroke_style.cap = match value.as_str()
+        {
+            "round" => LineCap::Round,
+            "square" => LineCap::Square,
+            _ => LineCap::Butt
+        };
+    }
+
+    if let Some(value) = get_property(node, "stroke-miterlimit").and_then(|value| value.parse::<f32>().ok())
+    {
+        style.stroke_style.miter_limit = value.max(1.0);
+    }
+
+    if let Some(value) = get_property(node, "fill-opacity").and_then(|value| parse_opacity(&value))
+    {
+        style.fill_opacity = value;
+    }
+
+    if let Some(value) = get_property(node, "stroke-opacity").and_then(|value| parse_opacity(&value))
+    {
+        style.stroke_opacity = value;
+    }
+
+    // unlike the other properties, opacity is not inherited but multiplies down the tree
+    if let Some(value) = node.attribute("opacity").map(String::from).or_else(|| get_style_declaration(node, "opacity")).and_then(|value| parse_opacity(&value))
+    {
+        style.opacity *= value;
+    }
+
+    if let Some(value) = get_property(node, "visibility")
+    {
+        style.visible = value == "visible";
+    }
+
+    if let Some(value) = node.attribute("transform")
+    {
+        style.transform = parse_transform(value).then(&parent_style.transform);
+    }
+
+    return style;
+}
+
+// style declarations take precedence over presentation attributes
+fn get_property(node: roxmltree::Node, name: &str) -> Option<String>
+{
+    return get_style_declaration(node, name).or_else(|| node.attribute(name).map(|value| String::from(value.trim())));
+}
+
+fn get_style_declaration(node: roxmltree::Node, name: &str) -> Option<String>
+{
+    let style = node.attribute("style")?;
+
+    return style.split(';').rev().find_map(|declaration| {
+        let (property, value) = declaration.split_once(':')?;
+
+        if property.trim() == name
+        {
+            return Some(String::from(value.trim()));
+        }
+
+        return None;
+    });
+}
+
+// none and unsupported paint servers such as gradients draw nothing
+fn parse_paint(value: &str, inherited: Option<SvgPaint>) -> Option<SvgPaint>
+{
+    return match value
+    {
+        "none" => None,
+        "inherit" => inherited,
+        "currentColor" => Some(SvgPaint::CurrentColor),
+        _ => parse_color(value).map(SvgPaint::Color)
+    };
+}
+
+fn parse_color(value: &str) -> Option<Color>
+{
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix('#')
+    {
+        let digits: Vec<u8> = hex.chars().map(|digit| digit.to_digit(16).map(|digit| digit as u8)).collect::<Option<Vec<u8>>>()?;
+
+        // short forms repeat each digit
+        let channels: Vec<u8> = match digits.len()
+        {
+            3 | 4 => digits.iter().map(|digit| digit * 17).collect(),
+            6 | 8 => digits.chunks_exact(2).map(|pair| pair[0] * 16 + pair[1]).collect(),
+            _ => return None
+        };
+
+        return Some(Color {
+            r: channels[0] as f32 / 255.0,
+            g: channels[1] as f32 / 255.0,
+            b: channels[2] as f32 / 255.0,
+            a: channels.get(3).map_or(1.0, |alpha| *alpha as f32 / 255.0)
+        });
+    }
+
+    if let Some(arguments) = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb(")).and_then(|rest| rest.strip_suffix(')'))
+    {
+        let parts: Vec<&str> = arguments.split(',').map(|part| part.trim()).collect();
+
+        if parts.len() < 3
+        {
+            return None;
+        }
+
+        let channel = |part: &str| -> Option<f32> {
+            return match part.strip_suffix('%')
+            {
+                Some(percent) => percent.parse::<f32>().ok().map(|percent| percent / 100.0),
+                None => part.parse::<f32>().ok().map(|value| value / 255.0)
+            };
+        };
+
+        return Some(Color {
+            r: channel(parts[0])?.clamp(0.0, 1.0),
+            g: channel(parts[1])?.clamp(0.0, 1.0),
+            b: channel(parts[2])?.clamp(0.0, 1.0),
+            a: parts.get(3).and_then(|alpha| parse_opacity(alpha)).unwrap_or(1.0)
+        });
+    }
+
+    if value == "transparent"
+    {
+        return Some(Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 });
+    }
+
+    return NAMED_COLORS.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        .map(|(_, (r, g, b))| Color { r: *r as f32 / 255.0, g: *g as f32 / 255.0, b: *b as f32 / 255.0, a: 1.0 });
+}
+
+fn parse_opacity(value: &str) -> Option<f32>
+{
+    let value = value.trim();
+
+    let opacity = match value.strip_suffix('%')
+    {
+        Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+        None => value.parse::<f32>().ok()?
+    };
+
+    return Some(opacity.clamp(0.0, 1.0));
+}
+
+// user units, with a px suffix allowed, other units are read as user units
+fn parse_length(value: &str) -> Option<f32>
+{
+    let value = value.trim();
+
+    if value.ends_with('%')
+    {
+        return None;
+    }
+
+    let number = value.trim_end_matches(|character: char| character.is_ascii_alphabetic());
+
+    return number.trim().parse::<f32>().ok();
+}
+
+fn length_attribute(node: roxmltree::Node, name: &str) -> f32
+{
+    return node.attribute(name).and_then(parse_length).unwrap_or(0.0);
+}
+
+// numbers separated by whitespace and commas, stopping at the first thing that is not a number
+fn parse_numbers(value: &str) -> Vec<f32>
+{
+    let mut parser = PathDataParser { data: value.as_bytes(), position: 0 };
+    let mut numbers = Vec::new();
+
+    while let Some(number) = parser.number()
+    {
+        numbers.push(number);
+    }
+
+    return numbers;
+}
+
+fn parse_aspect(value: &str) -> Option<(f32, f32, bool)>
+{
+    let mut parts = value.split_whitespace();
+    let align = parts.next().unwrap_or("xMidYMid");
+
+    if align == "none"
+    {
+        return None;
+    }
+
+    let position = |name: &str| -> f32 {
+        if align.contains(&format!("{}Min", name)) { 0.0 } else if align.contains(&format!("{}Max", name)) { 1.0 } else { 0.5 }
+    };
+
+    return Some((position("x"), position("Y"), parts.next() == Some("slice")));
+}
+
+// a list of transform functions, applied right to left
+fn parse_transform(value: &str) -> Transform
+{
+    let mut transform = Transform::identity();
+
+    for function in value.split(')')
+    {
+        let (name, arguments) = match function.split_once('(')
+        {
+            Some(parts) => parts,
+            None => continue
+        };
+
+        let arguments = parse_numbers(arguments);
+        let argument = |index: usize, default: f32| -> f32 { *arguments.get(index).unwrap_or(&default) };
+
+        let item = match name.trim_matches(|character: char| character.is_whitespace() || character == ',')
+        {
+            "matrix" if arguments.len() == 6 =>
+            {
+                Transform { a: arguments[0], b: arguments[1], c: arguments[2], d: arguments[3], e: arguments[4], f: arguments[5] }
+            },
+            "translate" => Transform::translation(argument(0, 0.0), argument(1, 0.0)),
+            "scale" => Transform::scaling(argument(0, 1.0), argument(1, argument(0, 1.0))),
+            "rotate" =>
+            {
+                let (center_x, center_y) = (argument(1, 0.0), argument(2, 0.0));
+
+                Transform::translation(-center_x, -center_y)
+                    .then(&Transform::rotation(argument(0, 0.0).to_radians()))
+                    .then(&Transform::translation(center_x, center_y))
+            },
+            "skewX" => Transform::skew(argument(0, 0.0).to_radians(), 0.0),
+            "skewY" => Transform::skew(0.0, argument(0, 0.0).to_radians()),
+            _ => continue
+        };
+
+        transform = item.then(&transform);
+    }
+
+    return transform;
+}
+
+fn ellipse_path(center: Point, radius_x: f32, radius_y: f32) -> Path
+{
+    let mut path = Path::new();
+
+    if radius_x > 0.0 && radius_y > 0.0
+    {
+        path.add_ellipse(Rect {
+            origin: Point { x: center.x - radius_x, y: center.y - radius_y },
+            size: Size { width: radius_x * 2.0, height: radius_y * 2.0 }
+        });
+    }
+
+    return path;
+}
+
+// reads path data, keeping everything up to the first error as the SVG spec asks
+fn parse_path_data(data: &str) -> Path
+{
+    let mut parser = PathDataParser { data: data.as_bytes(), position: 0 };
+    let mut path = Path::new();
+
+    let mut command: Option<u8> = None;
+    let mut current = Point { x: 0.0, y: 0.0 };
+    let mut start = current;
+
+    // the previous command's last control point, reflected by the smooth curve commands
+    let mut previous_cubic_control: Option<Point> = None;
+    let mut previous_quad_control: Option<Point> = None;
+
+    loop
+    {
+        parser.skip_separators();
+
+        let next = match parser.peek()
+        {
+            Some(next) => next,
+            None => break
+        };
+
+        if next.is_ascii_alphabetic()
+        {
+            parser.position += 1;
+            command = Some(next);
+        }
+
+        // numbers without a command letter repeat the previous command
+        let letter = match command
+        {
+            Some(letter) => letter,
+            None => break
+        };
+
+        let relative = letter.is_ascii_lowercase();
+        let offset = if relative { current } else { Point { x: 0.0, y: 0.0 } };
+
+        let point = |parser: &mut PathDataParser| -> Option<Point> {
+            let x = parser.number()?;
+            let y = parser.number()?;
+            return Some(Point { x: offset.x + x, y: offset.y + y });
+        };
+
+        let mut cubic_control = None;
+        let mut quad_control = None;
+
+        let parsed = (|| -> Option<()> {
+            match letter.to_ascii_uppercase()
+            {
+                b'M' =>
+                {
+                    current = point(&mut parser)?;
+                    start = current;
+                    path.move_to(current);
+
+                    // further pairs after a move are lines
+                    command = Some(if relative { b'l' } else { b'L' });
+                },
+                b'L' =>
+                {
+                    current = point(&mut parser)?;
+                    path.line_to(current);
+                },
+                b'H' =>
+                {
+                    current = Point { x: offset.x + parser.number()?, y: current.y };
+                    path.line_to(current);
+                },
+                b'V' =>
+                {
+                    current = Point { x: current.x, y: offset.y + parser.number()? };
+                    path.line_to(current);
+                },
+                b'C' =>
+                {
+                    let control1 = point(&mut parser)?;
+                    let control2 = point(&mut parser)?;
+                    current = point(&mut parser)?;
+                    path.cubic_to(control1, control2, current);
+                    cubic_control = Some(control2);
+                },
+                b'S' =>
+                {
+                    let control1 = reflect(previous_cubic_control, current);
+                    let control2 = point(&mut parser)?;
+                    current = point(&mut parser)?;
+                    path.cubic_to(control1, control2, current);
+                    cubic_control = Some(control2);
+                },
+                b'Q' =>
+                {
+                    let control = point(&mut parser)?;
+                    current = point(&mut parser)?;
+                    path.quad_to(control, current);
+                    quad_control = Some(control);
+                },
+                b'T' =>
+                {
+                    let control = reflect(previous_quad_control, current);
+                    current = point(&mut parser)?;
+                    path.quad_to(control, current);
+                    quad_control = Some(control);
+                },
+                b'A' =>
+                {
+                    let radius_x = parser.number()?;
+                    let radius_y = parser.number()?;
+                    let rotation = parser.number()?;
+                    let large_arc = parser.flag()?;
+                    let sweep = parser.flag()?;
+                    let to = point(&mut parser)?;
+
+                    path.elliptical_arc_to(radius_x, radius_y, rotation.to_radians(), large_arc, sweep, to);
+                    current = to;
+                },
+                b'Z' =>
+                {
+                    path.close();
+                    current = start;
+
+                    // a close takes no numbers, so it never repeats
+                    command = None;
+                },
+                _ => return None
+            }
+
+            return Some(());
+        })();
+
+        if parsed.is_none()
+        {
+            break;
+        }
+
+        previous_cubic_control = cubic_control;
+        previous_quad_control = quad_control;
+    }
+
+    return path;
+}
+
+fn reflect(control: Option<Point>, around: Point) -> Point
+{
+    return match control
+    {
+        Some(control) => Point { x: 2.0 * around.x - control.x, y: 2.0 * around.y - control.y },
+        None => around
+    };
+}
+
+// maps the view box onto rect as preserveAspectRatio asks
+fn view_box_transform(document: &SvgDocument, rect: Rect) -> Transform
+{
+    let view_box = document.view_box;
+
+    let mut scale_x = rect.size.width / view_box.size.width;
+    let mut scale_y = rect.size.height / view_box.size.height;
+
+    let mut offset = Point { x: 0.0, y: 0.0 };
+
+    if let Some((align_x, align_y, slice)) = document.aspect
+    {
+        let scale = if slice { scale_x.max(scale_y) } else { scale_x.min(scale_y) };
+
+        offset.x = (rect.size.width - view_box.size.width * scale) * align_x;
+        offset.y = (rect.size.height - view_box.size.height * scale) * align_y;
+
+        scale_x = scale;
+        scale_y = scale;
+    }
+
+    return Transform::translation(-view_box.origin.x, -view_box.origin.y)
+        .then(&Transform::scaling(scale_x, scale_y))
+        .then(&Transform::translation(rect.origin.x + offset.x, rect.origin.y + offset.y));
+}
+
+// renders the document scaled into rect, clipped to it, with currentColor resolved to color
+pub(crate) fn draw(context: &mut DrawingContext, rect: Rect, svg: Svg, color: Color)
+{
+    let document = match get_document(svg)
+    {
+        Some(document) => document,
+        None => return
+    };
+
+    if rect.size.width <= 0.0 || rect.size.height <= 0.0
+    {
+        return;
+    }
+
+    let transform = view_box_transform(&document, rect);
+    let scale = transform.scale_factor();
+
+    let resolve = |paint: SvgPaint, opacity: f32| -> Color {
+        let base = match paint
+        {
+            SvgPaint::Color(base) => base,
+            SvgPaint::CurrentColor => color
+        };
+
+        return Color { r: base.r, g: base.g, b: base.b, a: base.a * opacity };
+    };
+
+    context.push_clip(rect);
+
+    for shape in document.shapes.iter()
+    {
+        let path = shape.path.transformed(&transform);
+
+        if let Some((paint, opacity)) = shape.fill
+        {
+            context.fill_path(&path, shape.fill_rule, resolve(paint, opacity));
+        }
+
+        if let Some((paint, opacity)) = shape.stroke
+        {
+            let mut stroke_style = shape.stroke_style.clone();
+            stroke_style.width *= scale;
+
+            context.stroke_path(&path, &stroke_style, resolve(paint, opacity));
+        }
+    }
+
+    context.pop_clip();
+}
+
+impl PathDataParser<'_>
+{
+    fn peek(&self) -> Option<u8>
+    {
+        return self.data.get(self.position).copied();
+    }
+
+    fn skip_separators(&mut self)
+    {
+        while let Some(next) = self.peek()
+        {
+            if next.is_ascii_whitespace() || next == b','
+            {
+                self.position += 1;
+            }
+            else
+            {
+                break;
+            }
+        }
+    }
+
+    // numbers may run together, as in "1.5.5" or "2-3"
+    fn number(&mut self) -> Option<f32>
+    {
+        self.skip_separators();
+
+        let start = self.position;
+        let mut end = start;
+
+        let digits = |data: &[u8], mut index: usize| -> usize {
+            while index < data.len() && data[index].is_ascii_digit()
+            {
+                index += 1;
+            }
+
+            return index;
+        };
+
+        if end < self.data.len() && (self.data[end] == b'+' || self.data[end] == b'-')
+        {
+            end += 1;
+        }
+
+        end = digits(self.data, end);
+
+        if end < self.data.len() && self.data[end] == b'.'
+        {
+            end = digits(self.data, end + 1);
+        }
+
+        // an exponent only counts when digits follow, so the e of a command letter is left alone
+        if end < self.data.len() && (self.data[end] == b'e' || self.data[end] == b'E')
+        {
+            let mut exponent = end + 1;
+
+            if exponent < self.data.len() && (self.data[exponent] == b'+' || self.data[exponent] == b'-')
+            {
+                exponent += 1;
+            }
+
+            if exponent < self.data.len() && self.data[exponent].is_ascii_digit()
+            {
+                end = digits(self.data, exponent);
+            }
+        }
+
+        let number = std::str::from_utf8(&self.data[start..end]).ok()?.parse::<f32>().ok()?;
+
+        self.position = end;
+        return Some(number);
+    }
+
+    // arc flags are single digits that need no separator after them
+    fn flag(&mut self) -> Option<bool>
+    {
+        self.skip_separators();
+
+        let flag = match self.peek()
+        {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return None
+        };
+
+        self.position += 1;
+        return Some(flag);
+    }
+}
+
+/***************************************************************
+* MARK: TESTS
+***************************************************************/
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::drawing::path::PathCommand;
+
+    type Commands = Vec<(char, Vec<(f32, f32)>)>;
+
+    // each command as its letter and the points it ends with, rounded so arcs compare cleanly
+    fn commands(data: &str) -> Commands
+    {
+        let round = |point: &Point| -> (f32, f32) { ((point.x * 1000.0).round() / 1000.0, (point.y * 1000.0).round() / 1000.0) };
+
+        return parse_path_data(data).commands().iter().map(|command| match command
+        {
+            PathCommand::MoveTo(point) => ('M', vec![round(point)]),
+            PathCommand::LineTo(point) => ('L', vec![round(point)]),
+            PathCommand::QuadTo(control, point) => ('Q', vec![round(control), round(point)]),
+            PathCommand::CubicTo(control1, control2, point) => ('C', vec![round(control1), round(control2), round(point)]),
+            PathCommand::Close => ('Z', vec![])
+        }).collect();
+    }
+
+    fn apply(transform: &Transform, x: f32, y: f32) -> (f32, f32)
+    {
+        let point = transform.apply(Point { x: x, y: y });
+        return ((point.x * 1000.0).round() / 1000.0, (point.y * 1000.0).round() / 1000.0);
+    }
+
+    #[test]
+    fn numbers_run_together()
+    {
+        let table: [(&str, Commands); 5] = [
+            ("M1.5.5", vec![('M', vec![(1.5, 0.5)])]),
+            ("M2-3", vec![('M', vec![(2.0, -3.0)])]),
+            ("M-.5-.25", vec![('M', vec![(-0.5, -0.25)])]),
+            ("M1e1,2E-1", vec![('M', vec![(10.0, 0.2)])]),
+            ("M0,0L1e", vec![('M', vec![(0.0, 0.0)])])
+        ];
+
+        for (data, expected) in table.iter()
+        {
+            assert_eq!(&commands(data), expected, "{}", data);
+        }
+    }
+
+    #[test]
+    fn pairs_after_a_move_are_lines()
+    {
+        assert_eq!(commands("M1 2 3 4 5 6"), vec![('M', vec![(1.0, 2.0)]), ('L', vec![(3.0, 4.0)]), ('L', vec![(5.0, 6.0)])]);
+        assert_eq!(commands("m1 1 2 2 1 0"), vec![('M', vec![(1.0, 1.0)]), ('L', vec![(3.0, 3.0)]), ('L', vec![(4.0, 3.0)])]);
+        assert_eq!(commands("M0 0 h5 v5 H0 z l1 1"), vec![
+            ('M', vec![(0.0, 0.0)]),
+            ('L', vec![(5.0, 0.0)]),
+            ('L', vec![(5.0, 5.0)]),
+            ('L', vec![(0.0, 5.0)]),
+            ('Z', vec![]),
+            ('L', vec![(1.0, 1.0)])
+        ]);
+    }
+
+    #[test]
+    fn smooth_curves_reflect_the_previous_control()
+    {
+        assert_eq!(commands("M0 0 C0 10 10 10 10 0 S20 -10 20 0"), vec![
+            ('M', vec![(0.0, 0.0)]),
+            ('C', vec![(0.0, 10.0), (10.0, 10.0), (10.0, 0.0)]),
+            ('C', vec![(10.0, -10.0), (20.0, -10.0), (20.0, 0.0)])
+        ]);
+
+        assert_eq!(commands("M0 0 Q5 10 10 0 T20 0"), vec![
+            ('M', vec![(0.0, 0.0)]),
+            ('Q', vec![(5.0, 10.0), (10.0, 0.0)]),
+            ('Q', vec![(15.0, -10.0), (20.0, 0.0)])
+        ]);
+
+        // without a curve of the same kind before it, the first control is the current point
+        assert_eq!(commands("M0 0 L10 0 S20 10 20 0")[2], ('C', vec![(10.0, 0.0), (20.0, 10.0), (20.0, 0.0)]));
+        assert_eq!(commands("M0 0 C0 10 10 10 10 0 T20 0")[2], ('Q', vec![(10.0, 0.0), (20.0, 0.0)]));
+    }
+
+    #[test]
+    fn arcs_read_flags_without_separators()
+    {
+        for data in ["M0 0 A5 5 0 0 1 10 0", "M0,0a5,5,0,0,1,10,0", "M0 0a5 5 0 0110 0"]
+        {
+            let commands = commands(data);
+            let (letter, points) = commands.last().unwrap();
+
+            assert_eq!(*letter, 'C', "{}", data);
+            assert_eq!(*points.last().unwrap(), (10.0, 0.0), "{}", data);
+        }
+
+        // a sweep clockwise on screen passes above the chord, where y is negative
+        let top = commands("M0 0 A5 5 0 0 1 10 0").iter()
+            .flat_map(|(_, points)| points.clone())
+            .map(|(_, y)| y)
+            .fold(0.0, f32::min);
+
+        assert!(top < -4.0, "{}", top);
+
+        // a bad flag ends the path after what was read before it
+        assert_eq!(commands("M0 0 L1 1 A5 5 0 2 1 10 0"), vec![('M', vec![(0.0, 0.0)]), ('L', vec![(1.0, 1.0)])]);
+    }
+
+    #[test]
+    fn transforms_apply_right_to_left()
+    {
+        assert_eq!(apply(&parse_transform("translate(10 20) scale(2)"), 1.0, 1.0), (12.0, 22.0));
+        assert_eq!(apply(&parse_transform("scale(2) translate(10,20)"), 1.0, 1.0), (22.0, 42.0));
+        assert_eq!(apply(&parse_transform("rotate(90 5 5)"), 10.0, 5.0), (5.0, 10.0));
+        assert_eq!(apply(&parse_transform("matrix(1 0 0 1 3 4)"), 1.0, 1.0), (4.0, 5.0));
+        assert_eq!(apply(&parse_transform("scale(2, 3)"), 1.0, 1.0), (2.0, 3.0));
+        assert_eq!(apply(&parse_transform("bogus(1) translate(1)"), 0.0, 0.0), (1.0, 0.0));
+    }
+
+    #[test]
+    fn view_boxes_follow_the_aspect_ratio()
+    {
+        let document = |aspect: Option<(f32, f32, bool)>| SvgDocument {
+            size: Size { width: 10.0, height: 20.0 },
+            view_box: Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 10.0, height: 20.0 } },
+            aspect: aspect,
+            shapes: Vec::new()
+        };
+
+        let rect = Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 100.0, height: 100.0 } };
+
+        let meet = view_box_transform(&document(Some((0.5, 0.5, false))), rect);
+        assert_eq!(apply(&meet, 0.0, 0.0), (25.0, 0.0));
+        assert_eq!(apply(&meet, 10.0, 20.0), (75.0, 100.0));
+
+        let slice = view_box_transform(&document(Some((0.0, 0.5, true))), rect);
+        assert_eq!(apply(&slice, 0.0, 0.0), (0.0, -50.0));
+        assert_eq!(apply(&slice, 10.0, 20.0), (100.0, 150.0));
+
+        let stretch = view_box_transform(&document(None), rect);
+        assert_eq!(apply(&stretch, 10.0, 20.0), (100.0, 100.0));
+    }
+
+    #[test]
+    fn renders_into_a_bitmap()
+    {
+        let svg = load_str(concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 10 10\">",
+            "<rect width=\"5\" height=\"10\" fill=\"#ff0000\"/>",
+            "<path d=\"M5 0h5v5h-5z\" fill=\"currentColor\"/>",
+            "</svg>"
+        )).expect("the document parses");
+
+        let mut context = DrawingContext::new(Size { width: 20.0, height: 20.0 });
+        context.draw_svg(Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 20.0, height: 20.0 } }, svg, Color { r: 0.0, g: 0.0, b: 1.0, a: 1.0 });
+
+        let bitmap = context.bitmap();
+
+        let red = bitmap.get_pixel(4, 15);
+        assert!(red.r > 0.99 && red.b < 0.01 && red.a > 0.99);
+
+        let blue = bitmap.get_pixel(15, 4);
+        assert!(blue.b > 0.99 && blue.r < 0.01 && blue.a > 0.99);
+
+        assert!(bitmap.get_pixel(15, 15).a < 0.01);
+    }
+}
@@ -0,0 +1,232 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  text.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains attributed strings and their layout.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect};
+
+use crate::drawing::bitmap::{Bitmap, PixelBox};
+use crate::drawing::font::{self, FontDescriptor, FontWeight, FontMetrics};
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// attributes left as None inherit the font and color of the view drawing the text
+#[derive(Clone, Default)]
+pub struct TextAttributes
+{
+    pub color: Option<Color>,
+    pub size: Option<f32>,
+    pub weight: Option<FontWeight>,
+    pub italic: Option<bool>,
+    pub underline: bool,
+    pub strikethrough: bool
+}
+
+#[derive(Clone)]
+pub struct TextRun
+{
+    pub text: String,
+    pub attributes: TextAttributes
+}
+
+// a paragraph made of runs of text with their own attributes
+#[derive(Clone, Default)]
+pub struct AttributedString
+{
+    pub runs: Vec<TextRun>
+}
+
+struct LinePiece
+{
+    pub text: String,
+    pub font: FontDescriptor,
+    pub color: Color,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub metrics: FontMetrics,
+    pub width: f32
+}
+
+struct Line
+{
+    pub pieces: Vec<LinePiece>,
+    pub ascent: f32,
+    pub line_height: f32,
+    pub width: f32
+}
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+impl AttributedString
+{
+    pub fn new() -> AttributedString
+    {
+        return AttributedString { runs: Vec::new() };
+    }
+
+    pub fn from_text(text: &str) -> AttributedString
+    {
+        let mut attributed_string = AttributedString::new();
+        attributed_string.push(text, TextAttributes::default());
+
+        return attributed_string;
+    }
+
+    pub fn push(&mut self, text: &str, attributes: TextAttributes)
+    {
+        self.runs.push(TextRun { text: String::from(text), attributes: attributes });
+    }
+
+    pub fn plain_text(&self) -> String
+    {
+        return self.runs.iter().map(|run| run.text.as_str()).collect();
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        return self.runs.iter().all(|run| run.text.is_empty());
+    }
+}
+
+pub fn measure_attributed_text(text: &AttributedString, base_font: &FontDescriptor) -> Size
+{
+    let lines = layout_lines(text, base_font, Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 });
+
+    return Size {
+        width: lines.iter().map(|line| line.width).fold(0.0, f32::max),
+        height: lines.iter().map(|line| line.line_height).sum()
+    };
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// draws the text with the top of its first line at origin, runs on a line share one baseline
+pub(crate) fn draw_attributed_text(bitmap: &mut Bitmap, clip: PixelBox, origin: Point, text: &AttributedString, base_font: &FontDescriptor, base_color: Color)
+{
+    let mut top = origin.y;
+
+    for line in layout_lines(text, base_font, base_color)
+    {
+        let baseline = top + line.ascent;
+        let mut x = origin.x;
+
+        for piece in line.pieces.iter()
+        {
+            font::draw_run(bitmap, clip, Point { x: x, y: baseline }, &piece.text, &piece.font, piece.color);
+
+            if piece.underline
+            {
+                draw_decoration(bitmap, clip, x, baseline + piece.metrics.underline_offset, piece.width, piece.metrics.line_thickness, piece.color);
+            }
+
+            if piece.strikethrough
+            {
+                draw_decoration(bitmap, clip, x, baseline + piece.metrics.strikeout_offset, piece.width, piece.metrics.line_thickness, piece.color);
+            }
+
+            x += piece.width;
+        }
+
+        top += line.line_height;
+    }
+}
+
+fn draw_decoration(bitmap: &mut Bitmap, clip: PixelBox, x: f32, y: f32, width: f32, thickness: f32, color: Color)
+{
+    let rect = Rect {
+        origin: Point { x: x, y: y - thickness / 2.0 },
+        size: Size { width: width, height: thickness }
+    };
+
+    bitmap.fill_rect(rect, color, clip);
+}
+
+// splits the runs into lines and resolves the attributes of every piece
+fn layout_lines(text: &AttributedString, base_font: &FontDescriptor, base_color: Color) -> Vec<Line>
+{
+    let base_metrics = font::get_metrics(base_font);
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut current = empty_line();
+
+    for run in text.runs.iter()
+    {
+        let font = FontDescriptor {
+            family: base_font.family.clone(),
+            size: run.attributes.size.unwrap_or(base_font.size),
+            weight: run.attributes.weight.unwrap_or(base_font.weight),
+            italic: run.attributes.italic.unwrap_or(base_font.italic)
+        };
+
+        let metrics = font::get_metrics(&font);
+
+        for (index, segment) in run.text.split('\n').enumerate()
+        {
+            if index > 0
+            {
+                lines.push(finish_line(current, &base_metrics));
+                current = empty_line();
+            }
+
+            if segment.is_empty()
+            {
+                continue;
+            }
+
+            let width = font::measure_run(segment, &font);
+
+            current.ascent = current.ascent.max(metrics.ascent);
+            current.line_height = current.line_height.max(metrics.line_height);
+            current.width += width;
+
+            current.pieces.push(LinePiece {
+                text: String::from(segment),
+                font: font.clone(),
+                color: run.attributes.color.unwrap_or(base_color),
+                underline: run.attributes.underline,
+                strikethrough: run.attributes.strikethrough,
+                metrics: metrics,
+                width: width
+            });
+        }
+    }
+
+    lines.push(finish_line(current, &base_metrics));
+
+    return lines;
+}
+
+fn empty_line() -> Line
+{
+    return Line { pieces: Vec::new(), ascent: 0.0, line_height: 0.0, width: 0.0 };
+}
+
+// empty lines take the height of the base font
+fn finish_line(mut line: Line, base_metrics: &FontMetrics) -> Line
+{
+    if line.pieces.is_empty()
+    {
+        line.ascent = base_metrics.ascent;
+        line.line_height = base_metrics.line_height;
+    }
+
+    return line;
+}
@@ -0,0 +1,133 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  transform.rs
+** Module       :  drawing
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the 2D affine transform.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::Point;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// maps x' = a * x + c * y + e and y' = b * x + d * y + f, the same layout as an SVG matrix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform
+{
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+impl Transform
+{
+    pub fn identity() -> Transform
+    {
+        return Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 };
+    }
+
+    pub fn translation(x: f32, y: f32) -> Transform
+    {
+        return Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: x, f: y };
+    }
+
+    pub fn scaling(x: f32, y: f32) -> Transform
+    {
+        return Transform { a: x, b: 0.0, c: 0.0, d: y, e: 0.0, f: 0.0 };
+    }
+
+    // positive angles turn clockwise on screen, where y points down
+    pub fn rotation(radians: f32) -> Transform
+    {
+        let (sin, cos) = radians.sin_cos();
+        return Transform { a: cos, b: sin, c: -sin, d: cos, e: 0.0, f: 0.0 };
+    }
+
+    pub fn skew(x_radians: f32, y_radians: f32) -> Transform
+    {
+        return Transform { a: 1.0, b: y_radians.tan(), c: x_radians.tan(), d: 1.0, e: 0.0, f: 0.0 };
+    }
+
+    // the transform that applies self first and then other
+    pub fn then(&self, other: &Transform) -> Transform
+    {
+        return Transform {
+            a: other.a * self.a + other.c * self.b,
+            b: other.b * self.a + other.d * self.b,
+            c: other.a * self.c + other.c * self.d,
+            d: other.b * self.c + other.d * self.d,
+            e: other.a * self.e + other.c * self.f + other.e,
+            f: other.b * self.e + other.d * self.f + other.f
+        };
+    }
+
+    pub fn apply(&self, point: Point) -> Point
+    {
+        return Point {
+            x: self.a * point.x + self.c * point.y + self.e,
+            y: self.b * point.x + self.d * point.y + self.f
+        };
+    }
+
+    // none when the transform flattens everything onto a line or a point
+    pub fn invert(&self) -> Option<Transform>
+    {
+        let determinant = self.determinant();
+
+        if determinant.abs() <= f32::EPSILON
+        {
+            return None;
+        }
+
+        return Some(Transform {
+            a: self.d / determinant,
+            b: -self.b / determinant,
+            c: -self.c / determinant,
+            d: self.a / determinant,
+            e: (self.c * self.f - self.d * self.e) / determinant,
+            f: (self.b * self.e - self.a * self.f) / determinant
+        });
+    }
+
+    pub fn determinant(&self) -> f32
+    {
+        return self.a * self.d - self.b * self.c;
+    }
+
+    // the average factor lengths are scaled by, used for line widths
+    pub fn scale_factor(&self) -> f32
+    {
+        return self.determinant().abs().sqrt();
+    }
+
+    pub fn is_identity(&self) -> bool
+    {
+        return *self == Transform::identity();
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
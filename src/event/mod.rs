@@ -0,0 +1,229 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  mod.rs
+** Module       :  event
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the NanoUI input event types.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::Point;
+
+use crate::drawing::Transform;
+
+/***************************************************************
+* MARK: CHILD MODULES
+***************************************************************/
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton
+{
+    Left,
+    Right,
+    Middle
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key
+{
+    Character(char),
+    Space,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Function(u8)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers
+{
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub meta: bool
+}
+
+// a key pressed with a set of modifiers, such as Ctrl+Shift+P
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord
+{
+    pub key: Key,
+    pub modifiers: Modifiers
+}
+
+// pointer positions are in the coordinates the receiving view is laid out in, which are window coordinates unless an ancestor is transformed
+#[derive(Clone, Copy)]
+pub enum Event
+{
+    MouseDown { point: Point, button: MouseButton, modifiers: Modifiers },
+    MouseUp { point: Point, button: MouseButton, modifiers: Modifiers },
+    MouseMove { point: Point },
+    MouseEnter,
+    MouseLeave,
+
+    // delta is in pixels, positive values scroll towards the end of the content
+    Scroll { point: Point, delta: Point },
+
+    KeyDown { key: Key, modifiers: Modifiers },
+
+    FocusGained,
+    FocusLost,
+
+    // sent to a light dismiss overlay when a click outside it closed it
+    Dismissed
+}
+
+impl KeyChord
+{
+    // letters are kept lower case so chords compare equal whatever case was typed
+    pub fn new(key: Key, modifiers: Modifiers) -> KeyChord
+    {
+        let key = match key
+        {
+            Key::Character(character) => Key::Character(character.to_lowercase().next().unwrap_or(character)),
+            other => other
+        };
+
+        return KeyChord { key: key, modifiers: modifiers };
+    }
+
+    // reads text like "Ctrl+Shift+P" or "F5", returns None for unknown key names
+    pub fn parse(text: &str) -> Option<KeyChord>
+    {
+        let mut modifiers = Modifiers::default();
+        let mut key = None;
+
+        for part in text.split('+').map(|part| part.trim())
+        {
+            if key.is_some()
+            {
+                return None;
+            }
+
+            match part.to_lowercase().as_str()
+            {
+                "ctrl" | "control" => modifiers.control = true,
+                "shift" => modifiers.shift = true,
+                "alt" | "option" => modifiers.alt = true,
+                "meta" | "cmd" | "command" | "super" => modifiers.meta = true,
+                name => key = Some(parse_key(name)?)
+            }
+        }
+
+        return key.map(|key| KeyChord::new(key, modifiers));
+    }
+
+    pub fn matches(&self, key: Key, modifiers: Modifiers) -> bool
+    {
+        return *self == KeyChord::new(key, modifiers);
+    }
+
+    // the chord as shown next to menu items, the inverse of parse
+    pub fn to_text(&self) -> String
+    {
+        let mut text = String::from("");
+
+        if self.modifiers.control { text.push_str("Ctrl+"); }
+        if self.modifiers.alt { text.push_str("Alt+"); }
+        if self.modifiers.shift { text.push_str("Shift+"); }
+        if self.modifiers.meta { text.push_str("Meta+"); }
+
+        let key_text = match self.key
+        {
+            Key::Character(character) => character.to_uppercase().collect(),
+            Key::Function(number) => format!("F{}", number),
+            Key::PageUp => String::from("PgUp"),
+            Key::PageDown => String::from("PgDn"),
+            Key::Escape => String::from("Esc"),
+            other => format!("{:?}", other)
+        };
+
+        text.push_str(&key_text);
+        return text;
+    }
+}
+
+impl Event
+{
+    // the event with its pointer position mapped through transform, scroll deltas turn and scale with it but do not move
+    pub fn transformed(&self, transform: &Transform) -> Event
+    {
+        return match *self
+        {
+            Event::MouseDown { point, button, modifiers } => Event::MouseDown { point: transform.apply(point), button: button, modifiers: modifiers },
+            Event::MouseUp { point, button, modifiers } => Event::MouseUp { point: transform.apply(point), button: button, modifiers: modifiers },
+            Event::MouseMove { point } => Event::MouseMove { point: transform.apply(point) },
+            Event::Scroll { point, delta } =>
+            {
+                let delta = Point {
+                    x: transform.a * delta.x + transform.c * delta.y,
+                    y: transform.b * delta.x + transform.d * delta.y
+                };
+
+                Event::Scroll { point: transform.apply(point), delta: delta }
+            },
+            other => other
+        };
+    }
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// key names are matched lower case
+fn parse_key(name: &str) -> Option<Key>
+{
+    let key = match name
+    {
+        "space" => Key::Space,
+        "enter" | "return" => Key::Enter,
+        "esc" | "escape" => Key::Escape,
+        "tab" => Key::Tab,
+        "backspace" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" | "pgup" => Key::PageUp,
+        "pagedown" | "pgdn" => Key::PageDown,
+        name if name.len() > 1 && name.starts_with('f') => Key::Function(name[1..].parse().ok()?),
+        name if name.chars().count() == 1 => Key::Character(name.chars().next()?),
+        _ => return None
+    };
+
+    return Some(key);
+}
@@ -23,6 +23,11 @@ pub mod app;
 pub mod window;
 pub mod view;
 pub mod drawing;
+pub mod event;
+pub mod widget;
+pub mod animation;
+
+mod platform;
 
 
 /***************************************************************
@@ -32,6 +37,11 @@ pub mod drawing;
 pub type Application = usize;
 pub type Window = usize;
 pub type View = usize;
+pub type Font = usize;
+pub type Image = usize;
+pub type Svg = usize;
+pub type Animation = usize;
+pub type Timer = usize;
 
 /***************************************************************
 * MARK: STATIC VARIABLES & CONSTANTS
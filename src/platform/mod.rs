@@ -0,0 +1,260 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  mod.rs
+** Module       :  platform
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the calls NanoUI makes into NanoWin
+**                 beyond its basic window and renderer interface.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Point, Rect};
+
+#[cfg(not(feature = "nanowin-ext"))]
+use nanowin::{Size, Color, debug::*};
+
+pub use crate::Window;
+
+use crate::drawing::bitmap::Bitmap;
+
+#[cfg(feature = "nanowin-ext")]
+use crate::event::{Key, Modifiers, MouseButton};
+
+use std::time::Duration;
+
+/***************************************************************
+* MARK: CHILD MODULES
+***************************************************************/
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+// shows a premultiplied bitmap stretched over rect in the window
+//
+// with the nanowin-ext feature the pixels go to NanoWin's draw_bitmap in one call, without it they are
+// drawn as runs of equal pixels with draw_rect, which works on any NanoWin but is much slower
+pub(crate) fn draw_bitmap(window: Window, rect: Rect, bitmap: &Bitmap)
+{
+    if bitmap.width == 0 || bitmap.height == 0
+    {
+        return;
+    }
+
+    #[cfg(feature = "nanowin-ext")]
+    {
+        nanowin::renderer::draw_bitmap(window, rect, bitmap.width, bitmap.height, &bitmap.pixels);
+    }
+
+    #[cfg(not(feature = "nanowin-ext"))]
+    {
+        draw_bitmap_runs(window, rect, bitmap);
+    }
+}
+
+// asks NanoWin to render the window again soon, without the nanowin-ext feature the window only renders when NanoWin decides to
+pub(crate) fn request_redraw(window: Window)
+{
+    #[cfg(feature = "nanowin-ext")]
+    {
+        nanowin::window::request_redraw(window);
+    }
+
+    #[cfg(not(feature = "nanowin-ext"))]
+    {
+        let _ = window;
+    }
+}
+
+// has NanoWin call the handler on the UI thread whenever its loop is woken with wake or wake_after
+pub(crate) fn set_wake_handler(handler: fn())
+{
+    #[cfg(feature = "nanowin-ext")]
+    {
+        nanowin::platform::platform::set_wake_handler(handler);
+    }
+
+    #[cfg(not(feature = "nanowin-ext"))]
+    {
+        let _ = handler;
+        log(LogLevel::WARN, &format!("Timers and posted callbacks only run when a window renders, waking NanoWin needs the nanowin-ext feature!"));
+    }
+}
+
+// wakes NanoWin's loop as soon as possible, this can be called from any thread
+pub(crate) fn wake()
+{
+    #[cfg(feature = "nanowin-ext")]
+    {
+        nanowin::platform::platform::wake();
+    }
+}
+
+// wakes NanoWin's loop once delay has passed, even if nothing else happens by then
+pub(crate) fn wake_after(delay: Duration)
+{
+    #[cfg(feature = "nanowin-ext")]
+    {
+        nanowin::platform::platform::wake_after(delay);
+    }
+
+    #[cfg(not(feature = "nanowin-ext"))]
+    {
+        let _ = delay;
+    }
+}
+
+// connects NanoWin's pointer and keyboard input for a new window to the window module
+pub(crate) fn register_input(window: Window)
+{
+    #[cfg(feature = "nanowin-ext")]
+    {
+        nanowin::window::set_mouse_move_handler(window, crate::window::mouse_move);
+        nanowin::window::set_mouse_leave_handler(window, crate::window::mouse_leave);
+        nanowin::window::set_mouse_down_handler(window, mouse_down);
+        nanowin::window::set_mouse_up_handler(window, mouse_up);
+        nanowin::window::set_mouse_scroll_handler(window, crate::window::mouse_scroll);
+        nanowin::window::set_key_down_handler(window, key_down);
+    }
+
+    #[cfg(not(feature = "nanowin-ext"))]
+    {
+        log(LogLevel::WARN, &format!("Window {} will not receive input, NanoWin input needs the nanowin-ext feature!", window));
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+#[cfg(feature = "nanowin-ext")]
+fn mouse_down(window: Window, point: Point, button: nanowin::input::MouseButton, modifiers: nanowin::input::Modifiers)
+{
+    crate::window::mouse_down(window, point, convert_button(button), convert_modifiers(modifiers));
+}
+
+#[cfg(feature = "nanowin-ext")]
+fn mouse_up(window: Window, point: Point, button: nanowin::input::MouseButton, modifiers: nanowin::input::Modifiers)
+{
+    crate::window::mouse_up(window, point, convert_button(button), convert_modifiers(modifiers));
+}
+
+#[cfg(feature = "nanowin-ext")]
+fn key_down(window: Window, key: nanowin::input::Key, modifiers: nanowin::input::Modifiers)
+{
+    crate::window::key_down(window, convert_key(key), convert_modifiers(modifiers));
+}
+
+#[cfg(feature = "nanowin-ext")]
+fn convert_button(button: nanowin::input::MouseButton) -> MouseButton
+{
+    return match button
+    {
+        nanowin::input::MouseButton::Left => MouseButton::Left,
+        nanowin::input::MouseButton::Right => MouseButton::Right,
+        nanowin::input::MouseButton::Middle => MouseButton::Middle
+    };
+}
+
+#[cfg(feature = "nanowin-ext")]
+fn convert_modifiers(modifiers: nanowin::input::Modifiers) -> Modifiers
+{
+    return Modifiers {
+        shift: modifiers.shift,
+        control: modifiers.control,
+        alt: modifiers.alt,
+        meta: modifiers.meta
+    };
+}
+
+#[cfg(feature = "nanowin-ext")]
+fn convert_key(key: nanowin::input::Key) -> Key
+{
+    return match key
+    {
+        nanowin::input::Key::Character(character) => Key::Character(character),
+        nanowin::input::Key::Space => Key::Space,
+        nanowin::input::Key::Enter => Key::Enter,
+        nanowin::input::Key::Escape => Key::Escape,
+        nanowin::input::Key::Tab => Key::Tab,
+        nanowin::input::Key::Backspace => Key::Backspace,
+        nanowin::input::Key::Delete => Key::Delete,
+        nanowin::input::Key::Left => Key::Left,
+        nanowin::input::Key::Right => Key::Right,
+        nanowin::input::Key::Up => Key::Up,
+        nanowin::input::Key::Down => Key::Down,
+        nanowin::input::Key::Home => Key::Home,
+        nanowin::input::Key::End => Key::End,
+        nanowin::input::Key::PageUp => Key::PageUp,
+        nanowin::input::Key::PageDown => Key::PageDown,
+        nanowin::input::Key::Function(number) => Key::Function(number)
+    };
+}
+
+#[cfg(not(feature = "nanowin-ext"))]
+fn draw_bitmap_runs(window: Window, rect: Rect, bitmap: &Bitmap)
+{
+    let pixel_width = rect.size.width / bitmap.width as f32;
+    let pixel_height = rect.size.height / bitmap.height as f32;
+
+    for y in 0..bitmap.height
+    {
+        let row = &bitmap.pixels[y * bitmap.width * 4..(y + 1) * bitmap.width * 4];
+
+        let mut start = 0;
+
+        while start < bitmap.width
+        {
+            let pixel = &row[start * 4..start * 4 + 4];
+
+            let mut end = start + 1;
+
+            while end < bitmap.width && &row[end * 4..end * 4 + 4] == pixel
+            {
+                end += 1;
+            }
+
+            // fully transparent runs leave the window as it is
+            if pixel[3] > 0
+            {
+                let run = Rect {
+                    origin: Point { x: rect.origin.x + start as f32 * pixel_width, y: rect.origin.y + y as f32 * pixel_height },
+                    size: Size { width: (end - start) as f32 * pixel_width, height: pixel_height }
+                };
+
+                nanowin::renderer::draw_rect(window, run, unpremultiply(pixel));
+            }
+
+            start = end;
+        }
+    }
+}
+
+#[cfg(not(feature = "nanowin-ext"))]
+fn unpremultiply(pixel: &[u8]) -> Color
+{
+    let alpha = pixel[3] as f32 / 255.0;
+
+    return Color {
+        r: pixel[0] as f32 / 255.0 / alpha,
+        g: pixel[1] as f32 / 255.0 / alpha,
+        b: pixel[2] as f32 / 255.0 / alpha,
+        a: alpha
+    };
+}
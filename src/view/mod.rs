@@ -20,6 +20,9 @@ pub use nanowin::{Size, Point, Color, Rect, debug::*};
 
 pub use crate::View;
 
+use crate::drawing::{DrawingContext, Canvas, FontDescriptor, FontWeight, Path, CornerRadii, FillRule, Brush, Transform};
+use crate::event::Event;
+
 use std::collections::HashMap;
 use std::cell::RefCell;
 
@@ -40,6 +43,64 @@ pub enum DockLocation
     Right
 }
 
+// whether a view and its subtree are drawn and take part in layout
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Visibility
+{
+    Visible,
+
+    // keeps its place in the layout but is not drawn and cannot be hit or focused
+    Hidden,
+
+    // like hidden, and the dock layout leaves no space for it
+    Collapsed
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BorderSide
+{
+    Top,
+    Right,
+    Bottom,
+    Left
+}
+
+#[derive(Clone, Copy)]
+pub struct Border
+{
+    pub width: f32,
+    pub color: Color
+}
+
+// drawn behind the view and only outside of it, like a CSS box shadow
+#[derive(Clone, Copy)]
+pub struct BoxShadow
+{
+    pub offset: Point,
+
+    // the CSS blur radius, twice the standard deviation of the blur
+    pub blur: f32,
+
+    // grows the shadow on every side before it is blurred
+    pub spread: f32,
+    pub color: Color
+}
+
+// draws the content of a view on top of its background
+pub(crate) type RenderHandler = fn(View, &mut DrawingContext);
+
+// paints app content over the background, the canvas has its origin at the view's top left and is clipped to the view
+pub type PaintHandler = Box<dyn FnMut(View, &mut Canvas)>;
+
+// returns true when the view consumed the event, otherwise it bubbles to the parent
+pub(crate) type EventHandler = fn(View, &Event) -> bool;
+
+// forgets what a module keeps about a view once the view has been destroyed
+pub(crate) type DestroyHandler = fn(View);
+
+// positions the children of a view in place of the dock layout, the view rect is already set
+pub(crate) type LayoutHandler = fn(View, Rect);
+
 struct ViewObject
 {
     pub id: View,
@@ -51,9 +112,42 @@ struct ViewObject
     pub requested_size: Size,
     pub dock_location: DockLocation,
     pub background_color: Color,
+    // brushes take over from the colors when set, their points are relative to the view's top left
+    pub background_brush: Option<Brush>,
+    pub foreground_color: Color,
+    pub font: FontDescriptor,
+
+    // borders are indexed in the order of BorderSide and drawn inside the view rect
+    pub borders: [Border; 4],
+    pub corner_radii: CornerRadii,
+    pub border_brush: Option<Brush>,
+    pub shadow: Option<BoxShadow>,
 
     pub calculated_rect: Rect,
 
+    // scaled, then rotated around the transform origin, then moved, applies to rendering and hit testing of the whole subtree
+    pub translation: Point,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub rotation: f32,
+
+    // a fraction of the view's size, the centre by default
+    pub transform_origin: Point,
+
+    // applies to the subtree as a whole, which is drawn into a layer first when below 1
+    pub opacity: f32,
+    pub visibility: Visibility,
+
+    pub focusable: bool,
+    pub clips_children: bool,
+
+    pub tooltip: Option<String>,
+
+    pub render_handler: Option<RenderHandler>,
+    pub paint_handler: Option<PaintHandler>,
+    pub event_handler: Option<EventHandler>,
+    pub layout_handler: Option<LayoutHandler>,
+    pub destroy_handlers: Vec<DestroyHandler>
 }   
 
 impl Default for ViewObject
@@ -68,7 +162,29 @@ impl Default for ViewObject
             requested_size: Size { width: 0.0, height: 0.0 },
             dock_location: DockLocation::Left,
             background_color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 },
-            calculated_rect: Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 0.0, height: 0.0 } }
+            background_brush: None,
+            foreground_color: Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 },
+            font: FontDescriptor::default(),
+            borders: [Border { width: 0.0, color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 } }; 4],
+            corner_radii: CornerRadii::default(),
+            border_brush: None,
+            shadow: None,
+            calculated_rect: Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 0.0, height: 0.0 } },
+            translation: Point { x: 0.0, y: 0.0 },
+            scale_x: 1.0,
+            scale_y: 1.0,
+            rotation: 0.0,
+            transform_origin: Point { x: 0.5, y: 0.5 },
+            opacity: 1.0,
+            visibility: Visibility::Visible,
+            focusable: false,
+            clips_children: false,
+            tooltip: None,
+            render_handler: None,
+            paint_handler: None,
+            event_handler: None,
+            layout_handler: None,
+            destroy_handlers: Vec::new()
         };
     }
 }
@@ -116,9 +232,24 @@ pub fn create() -> View
     return new_view;
 }
 
+// takes a view out of its parent or window and frees it along with every view below it, the ids must not be used afterwards
 pub fn destroy(view: View)
 {
-    log(LogLevel::WARN, &format!("View destruction not implemented!"));
+    if let Some(parent) = get_parent(view)
+    {
+        remove_child(parent, view);
+    }
+    else if let Some(window) = get_window(view)
+    {
+        if crate::window::has_overlay(window, view)
+        {
+            crate::window::remove_overlay(window, view);
+        }
+
+        crate::window::detach_view(window, view);
+    }
+
+    destroy_down(view);
 }
 
 pub fn add_child(parent: View, child: View)
@@ -152,6 +283,55 @@ pub fn add_child(parent: View, child: View)
         }
 
     });
+
+    // children added after the tree was attached join the parent's window
+    let window = get_window(parent);
+
+    if window.is_some()
+    {
+        set_window_down(child, window);
+    }
+}
+
+pub fn remove_child(parent: View, child: View)
+{
+    let removed = VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(parent_view) = state.views.get_mut(&parent)
+        {
+            let count = parent_view.children.len();
+            parent_view.children.retain(|id| *id != child);
+
+            if parent_view.children.len() == count
+            {
+                log(LogLevel::FAIL, &format!("Child view not found!"));
+                return false;
+            }
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("Parent view not found!"));
+            return false;
+        }
+
+        if let Some(child_view) = state.views.get_mut(&child)
+        {
+            child_view.parent = None;
+        }
+
+        return true;
+    });
+
+    if removed
+    {
+        if let Some(window) = get_window(child)
+        {
+            crate::window::detach_view(window, child);
+        }
+
+        set_window_down(child, None);
+    }
 }
 
 pub fn set_background_color(view: View, color: Color)
@@ -170,6 +350,93 @@ pub fn set_background_color(view: View, color: Color)
     });
 }
 
+pub fn get_background_color(view: View) -> Color
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.background_color;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+        }
+    });
+}
+
+pub fn set_foreground_color(view: View, color: Color)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.foreground_color = color;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_foreground_color(view: View) -> Color
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.foreground_color;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+        }
+    });
+}
+
+pub fn set_font_family(view: View, family: &str)
+{
+    update_font(view, |font| font.family = String::from(family));
+}
+
+pub fn set_font_size(view: View, size: f32)
+{
+    update_font(view, |font| font.size = size);
+}
+
+pub fn set_font_weight(view: View, weight: FontWeight)
+{
+    update_font(view, |font| font.weight = weight);
+}
+
+pub fn set_font_italic(view: View, italic: bool)
+{
+    update_font(view, |font| font.italic = italic);
+}
+
+pub fn get_font(view: View) -> FontDescriptor
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.font.clone();
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return FontDescriptor::default();
+        }
+    });
+}
+
 pub fn set_dock_location(view: View, location: DockLocation)
 {
     VIEW_STATE.with(|state| {
@@ -236,121 +503,64 @@ pub fn get_size(view: View) -> Size
     });
 }
 
-pub fn render_down(view: View)
+pub fn get_rect(view: View) -> Rect
 {
-
-    // render this view
-    render_view(view);
-
-    // render children
-
-    let children_to_render = VIEW_STATE.with(|state| {
+    return VIEW_STATE.with(|state| {
         let state = state.borrow();
 
         if let Some(view_object) = state.views.get(&view)
         {
-            return view_object.children.clone();
+            return view_object.calculated_rect;
         }
         else 
         {
             log(LogLevel::FAIL, &format!("View not found!"));
-            return Vec::new();
+            return Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 0.0, height: 0.0 } };
         }
     });
-
-    for child in children_to_render
-    {
-        render_down(child);
-    }
 }
 
-pub fn layout_down(view: View, rect: Rect)
+pub fn get_window(view: View) -> Option<Window>
 {
-    //log(LogLevel::INFO, &format!("Resizing view: {}", view));
-
-    set_view_rect(view, rect);
-
-    let children_to_resize = VIEW_STATE.with(|state| {
+    return VIEW_STATE.with(|state| {
         let state = state.borrow();
 
         if let Some(view_object) = state.views.get(&view)
         {
-            return view_object.children.clone();
+            return view_object.window;
         }
         else 
         {
             log(LogLevel::FAIL, &format!("View not found!"));
-            return Vec::new();
+            return None;
         }
     });
+}
 
-    let mut left: f32 = rect.origin.x;
-    let mut top: f32 = rect.origin.y;
-    let mut right: f32 = rect.origin.x + rect.size.width;
-    let mut bottom: f32 = rect.origin.y + rect.size.height;
-
-    for i in 0..children_to_resize.len()
-    {
+pub fn get_parent(view: View) -> Option<View>
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
 
-        if i >= children_to_resize.len() - 1
+        if let Some(view_object) = state.views.get(&view)
         {
-            let child = children_to_resize[i];
-            let child_rect = Rect { origin: Point { x: left, y: top }, size: Size { width: right - left, height: bottom - top } };
-
-            layout_down(child, child_rect);
+            return view_object.parent;
         }
         else 
         {
-            let child = children_to_resize[i];
-
-            let child_location = get_dock_location(child);
-            let child_size = get_size(child);
-            
-            let mut child_rect = Rect { origin: Point { x: left, y: top }, size: Size { width: right - left, height: bottom - top } };
-
-            match child_location
-            {
-                DockLocation::Top => 
-                { 
-                    child_rect.origin.y = top;
-                    child_rect.size.height = child_size.height;
-                    top += child_size.height;
-                },
-                DockLocation::Bottom => 
-                { 
-                    child_rect.origin.y = top + bottom - child_size.height;
-                    child_rect.size.height = child_size.height;
-                    bottom -= child_size.height;
-                },
-                DockLocation::Left => 
-                { 
-                    child_rect.origin.x = left;
-                    child_rect.size.width = child_size.width;
-                    left += child_size.width;
-                },
-                DockLocation::Right => 
-                { 
-                    child_rect.origin.x = left + right - child_size.width;
-                    child_rect.size.width = child_size.width;
-                    right -= child_size.width;
-                }
-            }
-            
-            layout_down(child, child_rect);
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return None;
         }
-
-    }
-
+    });
 }
 
-pub fn set_window_down(view: View, window: Option<Window>)
+pub fn get_children(view: View) -> Vec<View>
 {
-    let children = VIEW_STATE.with(|state| {
-        let mut state = state.borrow_mut();
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
 
-        if let Some(view_object) = state.views.get_mut(&view)
+        if let Some(view_object) = state.views.get(&view)
         {
-            view_object.window = window;
             return view_object.children.clone();
         }
         else 
@@ -359,51 +569,1133 @@ pub fn set_window_down(view: View, window: Option<Window>)
             return Vec::new();
         }
     });
-
-    for child in children
-    {
-        set_window_down(child, window);
-    }
 }
 
+pub fn set_focusable(view: View, focusable: bool)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
 
-/***************************************************************
-* MARK: LOCAL FUNCTIONS
-***************************************************************/
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.focusable = focusable;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
 
-fn render_view(view: View)
+pub fn is_focusable(view: View) -> bool
 {
-    //log(LogLevel::INFO, &format!("Rendering view: {}", view));
-
-    // render this view
-    let window = VIEW_STATE.with(|state| {
+    return VIEW_STATE.with(|state| {
         let state = state.borrow();
 
         if let Some(view_object) = state.views.get(&view)
         {
-            return Some((view_object.window, view_object.background_color, view_object.calculated_rect));
+            return view_object.focusable;
         }
         else 
         {
             log(LogLevel::FAIL, &format!("View not found!"));
-            return None;
+            return false;
         }
     });
+}
 
-    if window.is_none()
+pub fn is_focused(view: View) -> bool
+{
+    if let Some(window) = get_window(view)
     {
-        return;
+        return crate::window::get_focus(window) == Some(view);
     }
 
-    let window = window.unwrap();
+    return false;
+}
 
-    if let Some(parent_window) = window.0
-    {
-        //println!("Drawing rect for view: {}", view);
-        nanowin::renderer::draw_rect(parent_window, window.2, window.1);
+// children are clipped to the rect of a clipping view, for scrolling content
+pub fn set_clips_children(view: View, clips_children: bool)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.clips_children = clips_children;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_clips_children(view: View) -> bool
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.clips_children;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return false;
+        }
+    });
+}
+
+// text shown in a small popup near the pointer after it rests on the view, children without one show it too
+pub fn set_tooltip(view: View, tooltip: Option<&str>)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.tooltip = tooltip.map(String::from);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_tooltip(view: View) -> Option<String>
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.tooltip.clone();
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return None;
+        }
+    });
+}
+
+// sets the same border on every side
+pub fn set_border(view: View, border: Border)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.borders = [border; 4];
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn set_border_side(view: View, side: BorderSide, border: Border)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.borders[side as usize] = border;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_border(view: View, side: BorderSide) -> Border
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.borders[side as usize];
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return Border { width: 0.0, color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 } };
+        }
+    });
+}
+
+pub fn set_corner_radius(view: View, radius: f32)
+{
+    set_corner_radii(view, CornerRadii::uniform(radius));
+}
+
+// rounds the background, border and shadow, radii that do not fit are scaled down together
+pub fn set_corner_radii(view: View, radii: CornerRadii)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.corner_radii = radii;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_corner_radii(view: View) -> CornerRadii
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.corner_radii;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return CornerRadii::default();
+        }
+    });
+}
+
+pub fn set_opacity(view: View, opacity: f32)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.opacity = opacity.clamp(0.0, 1.0);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_opacity(view: View) -> f32
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.opacity;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return 1.0;
+        }
+    });
+}
+
+// views with their own layout, like split and tab views, place collapsed children themselves
+pub fn set_visibility(view: View, visibility: Visibility)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.visibility = visibility;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_visibility(view: View) -> Visibility
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.visibility;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return Visibility::Visible;
+        }
+    });
+}
+
+// moves the view and its subtree without changing the layout
+pub fn set_translation(view: View, translation: Point)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.translation = translation;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_translation(view: View) -> Point
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.translation;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return Point { x: 0.0, y: 0.0 };
+        }
+    });
+}
+
+// scales the view and its subtree around the transform origin
+pub fn set_scale(view: View, x: f32, y: f32)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.scale_x = x;
+            view_object.scale_y = y;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_scale(view: View) -> (f32, f32)
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return (view_object.scale_x, view_object.scale_y);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return (1.0, 1.0);
+        }
+    });
+}
+
+// turns the view and its subtree around the transform origin, positive angles turn clockwise
+pub fn set_rotation(view: View, radians: f32)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.rotation = radians;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_rotation(view: View) -> f32
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.rotation;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return 0.0;
+        }
+    });
+}
+
+// the point scaling and rotation happen around, 0, 0 is the top left of the view and 1, 1 the bottom right
+pub fn set_transform_origin(view: View, origin: Point)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.transform_origin = origin;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_transform_origin(view: View) -> Point
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.transform_origin;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return Point { x: 0.5, y: 0.5 };
+        }
+    });
+}
+
+// maps the view's layout coordinates to where it is drawn inside its parent
+pub fn get_transform(view: View) -> Transform
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_transform(view_object);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return Transform::identity();
+        }
+    });
+}
+
+// maps the view's layout coordinates to window coordinates, through the transforms of all its ancestors
+pub fn get_window_transform(view: View) -> Transform
+{
+    let mut transform = Transform::identity();
+    let mut current = Some(view);
+
+    while let Some(ancestor) = current
+    {
+        transform = transform.then(&get_transform(ancestor));
+        current = get_parent(ancestor);
+    }
+
+    return transform;
+}
+
+// lets the app draw the view itself, for charts, canvases and other content no widget covers
+pub fn set_paint_handler<F: FnMut(View, &mut Canvas) + 'static>(view: View, handler: F)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.paint_handler = Some(Box::new(handler));
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn clear_paint_handler(view: View)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.paint_handler = None;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+// paints the background with a gradient or pattern instead of the background color, none goes back to the color
+pub fn set_background_brush(view: View, brush: Option<Brush>)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.background_brush = brush;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_background_brush(view: View) -> Option<Brush>
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.background_brush.clone();
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return None;
+        }
+    });
+}
+
+// paints every side of the border with one brush instead of the border colors, the widths still come from the borders
+pub fn set_border_brush(view: View, brush: Option<Brush>)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.border_brush = brush;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_border_brush(view: View) -> Option<Brush>
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.border_brush.clone();
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return None;
+        }
+    });
+}
+
+pub fn set_shadow(view: View, shadow: Option<BoxShadow>)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.shadow = shadow;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub fn get_shadow(view: View) -> Option<BoxShadow>
+{
+    return VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.shadow;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return None;
+        }
+    });
+}
+
+// finds the deepest view under the point, later children are on top of earlier ones, point is in the parent's coordinates
+pub fn hit_test(view: View, point: Point) -> Option<View>
+{
+    let hit_properties = VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return Some((view_object.calculated_rect, view_object.children.clone(), view_transform(view_object), view_object.visibility));
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return None;
+        }
+    });
+
+    let (rect, children, transform, visibility) = hit_properties?;
+
+    if visibility != Visibility::Visible
+    {
+        return None;
+    }
+
+    // the point is taken back into the view's layout coordinates, a view flattened to nothing cannot be hit
+    let point = if transform.is_identity() { point } else { transform.invert()?.apply(point) };
+
+    if !rect_contains(rect, point)
+    {
+        return None;
+    }
+
+    for child in children.iter().rev()
+    {
+        if let Some(hit) = hit_test(*child, point)
+        {
+            return Some(hit);
+        }
+    }
+
+    return Some(view);
+}
+
+// delivers the event to the view and then its ancestors until one consumes it
+pub fn dispatch_event(view: View, event: &Event) -> bool
+{
+    let mut current = Some(view);
+
+    while let Some(target) = current
+    {
+        if send_event(target, event)
+        {
+            return true;
+        }
+
+        current = get_parent(target);
+    }
+
+    return false;
+}
+
+// delivers the event to the view only
+pub fn send_event(view: View, event: &Event) -> bool
+{
+    let event_handler = VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return view_object.event_handler;
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return None;
+        }
+    });
+
+    if let Some(event_handler) = event_handler
+    {
+        // handlers get pointer positions in the coordinates they were laid out in
+        let transform = get_window_transform(view);
+
+        if transform.is_identity()
+        {
+            return event_handler(view, event);
+        }
+
+        return match transform.invert()
+        {
+            Some(inverse) => event_handler(view, &event.transformed(&inverse)),
+            None => false
+        };
+    }
+
+    return false;
+}
+
+pub fn rect_contains(rect: Rect, point: Point) -> bool
+{
+    return point.x >= rect.origin.x 
+        && point.y >= rect.origin.y 
+        && point.x < rect.origin.x + rect.size.width 
+        && point.y < rect.origin.y + rect.size.height;
+}
+
+pub fn render_down(view: View, context: &mut DrawingContext)
+{
+    let (visibility, opacity) = VIEW_STATE.with(|state| {
+        return state.borrow().views.get(&view).map(|view_object| (view_object.visibility, view_object.opacity)).unwrap_or((Visibility::Visible, 1.0));
+    });
+
+    if visibility != Visibility::Visible || opacity <= 0.0
+    {
+        return;
+    }
+
+    let transform = get_transform(view);
+    let transformed = !transform.is_identity();
+
+    if transformed
+    {
+        context.push_transform(transform);
+    }
+
+    let layered = opacity < 1.0;
+
+    if layered
+    {
+        context.push_layer(opacity);
+    }
+
+    // render this view
+    render_view(view, context);
+
+    // render children
+
+    let children_to_render = VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            let clip_rect = if view_object.clips_children { Some(view_object.calculated_rect) } else { None };
+            return (view_object.children.clone(), clip_rect);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return (Vec::new(), None);
+        }
+    });
+
+    let (children_to_render, clip_rect) = children_to_render;
+
+    if let Some(clip_rect) = clip_rect
+    {
+        context.push_clip(clip_rect);
+    }
+
+    for child in children_to_render
+    {
+        render_down(child, context);
+    }
+
+    if clip_rect.is_some()
+    {
+        context.pop_clip();
+    }
+
+    if layered
+    {
+        context.pop_layer();
+    }
+
+    if transformed
+    {
+        context.pop_transform();
+    }
+}
+
+pub fn layout_down(view: View, rect: Rect)
+{
+    //log(LogLevel::INFO, &format!("Resizing view: {}", view));
+
+    set_view_rect(view, rect);
+
+    let children_to_resize = VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return (view_object.children.clone(), view_object.layout_handler);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return (Vec::new(), None);
+        }
+    });
+
+    let (children_to_resize, layout_handler) = children_to_resize;
+
+    // views with their own layout position their children themselves
+    if let Some(layout_handler) = layout_handler
+    {
+        layout_handler(view, rect);
+        return;
+    }
+
+    // collapsed children are left out, so the last visible child fills the space
+    let children_to_resize: Vec<View> = children_to_resize.into_iter().filter(|child| get_visibility(*child) != Visibility::Collapsed).collect();
+
+    let mut left: f32 = rect.origin.x;
+    let mut top: f32 = rect.origin.y;
+    let mut right: f32 = rect.origin.x + rect.size.width;
+    let mut bottom: f32 = rect.origin.y + rect.size.height;
+
+    for i in 0..children_to_resize.len()
+    {
+
+        if i >= children_to_resize.len() - 1
+        {
+            let child = children_to_resize[i];
+            let child_rect = Rect { origin: Point { x: left, y: top }, size: Size { width: right - left, height: bottom - top } };
+
+            layout_down(child, child_rect);
+        }
+        else 
+        {
+            let child = children_to_resize[i];
+
+            let child_location = get_dock_location(child);
+            let child_size = get_size(child);
+            
+            let mut child_rect = Rect { origin: Point { x: left, y: top }, size: Size { width: right - left, height: bottom - top } };
+
+            match child_location
+            {
+                DockLocation::Top => 
+                { 
+                    child_rect.origin.y = top;
+                    child_rect.size.height = child_size.height;
+                    top += child_size.height;
+                },
+                DockLocation::Bottom => 
+                { 
+                    child_rect.origin.y = top + bottom - child_size.height;
+                    child_rect.size.height = child_size.height;
+                    bottom -= child_size.height;
+                },
+                DockLocation::Left => 
+                { 
+                    child_rect.origin.x = left;
+                    child_rect.size.width = child_size.width;
+                    left += child_size.width;
+                },
+                DockLocation::Right => 
+                { 
+                    child_rect.origin.x = left + right - child_size.width;
+                    child_rect.size.width = child_size.width;
+                    right -= child_size.width;
+                }
+            }
+            
+            layout_down(child, child_rect);
+        }
+
+    }
+
+}
+
+pub fn set_window_down(view: View, window: Option<Window>)
+{
+    let children = VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.window = window;
+            return view_object.children.clone();
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return Vec::new();
+        }
+    });
+
+    for child in children
+    {
+        set_window_down(child, window);
+    }
+}
+
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn destroy_down(view: View)
+{
+    if !VIEW_STATE.with(|state| state.borrow().views.contains_key(&view))
+    {
+        log(LogLevel::FAIL, &format!("View not found!"));
+        return;
+    }
+
+    crate::animation::cancel_view(view);
+
+    let view_object = VIEW_STATE.with(|state| state.borrow_mut().views.remove(&view));
+
+    if let Some(view_object) = view_object
+    {
+        for destroy_handler in view_object.destroy_handlers
+        {
+            destroy_handler(view);
+        }
+
+        for child in view_object.children
+        {
+            destroy_down(child);
+        }
+    }
+}
+
+fn render_view(view: View, context: &mut DrawingContext)
+{
+    //log(LogLevel::INFO, &format!("Rendering view: {}", view));
+
+    // render this view
+    let view_properties = VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(view_object) = state.views.get(&view)
+        {
+            return Some((view_object.background_color, view_object.calculated_rect, view_object.render_handler,
+                view_object.borders, view_object.corner_radii, view_object.shadow,
+                view_object.background_brush.clone(), view_object.border_brush.clone()));
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+            return None;
+        }
+    });
+
+    if let Some((background_color, rect, render_handler, borders, corner_radii, shadow, background_brush, border_brush)) = view_properties
+    {
+        if let Some(shadow) = shadow
+        {
+            render_shadow(context, rect, corner_radii, shadow);
+        }
+
+        // views render into the context whether or not they are attached to a window
+        if let Some(background_brush) = background_brush
+        {
+            let mut background = Path::new();
+            background.add_rounded_rect_corners(rect, corner_radii);
+            context.fill_path(&background, FillRule::NonZero, background_brush.translated(rect.origin));
+        }
+        else if corner_radii.is_zero()
+        {
+            context.fill_rect(rect, background_color);
+        }
+        else if background_color.a > 0.0
+        {
+            let mut background = Path::new();
+            background.add_rounded_rect_corners(rect, corner_radii);
+            context.fill_path(&background, FillRule::NonZero, background_color);
+        }
+
+        if let Some(render_handler) = render_handler
+        {
+            render_handler(view, context);
+        }
+
+        render_paint_handler(view, context, rect);
+
+        // the border frames whatever the view drew
+        render_border(context, rect, borders, corner_radii, border_brush);
     }
 }   
 
+fn view_transform(view_object: &ViewObject) -> Transform
+{
+    if view_object.translation.x == 0.0 && view_object.translation.y == 0.0
+        && view_object.scale_x == 1.0 && view_object.scale_y == 1.0 && view_object.rotation == 0.0
+    {
+        return Transform::identity();
+    }
+
+    let rect = view_object.calculated_rect;
+    let origin = Point {
+        x: rect.origin.x + rect.size.width * view_object.transform_origin.x,
+        y: rect.origin.y + rect.size.height * view_object.transform_origin.y
+    };
+
+    return Transform::translation(-origin.x, -origin.y)
+        .then(&Transform::scaling(view_object.scale_x, view_object.scale_y))
+        .then(&Transform::rotation(view_object.rotation))
+        .then(&Transform::translation(origin.x + view_object.translation.x, origin.y + view_object.translation.y));
+}
+
+fn render_paint_handler(view: View, context: &mut DrawingContext, rect: Rect)
+{
+    // the handler is taken out while it runs so it can call back into this module
+    let handler = VIEW_STATE.with(|state| {
+        return state.borrow_mut().views.get_mut(&view).and_then(|view_object| view_object.paint_handler.take());
+    });
+
+    if let Some(mut handler) = handler
+    {
+        {
+            let mut canvas = Canvas::new(context, rect);
+            handler(view, &mut canvas);
+        }
+
+        VIEW_STATE.with(|state| {
+            if let Some(view_object) = state.borrow_mut().views.get_mut(&view)
+            {
+                if view_object.paint_handler.is_none()
+                {
+                    view_object.paint_handler = Some(handler);
+                }
+            }
+        });
+    }
+}
+
+fn render_shadow(context: &mut DrawingContext, rect: Rect, corner_radii: CornerRadii, shadow: BoxShadow)
+{
+    if shadow.color.a <= 0.0
+    {
+        return;
+    }
+
+    let shadow_rect = Rect {
+        origin: Point { x: rect.origin.x + shadow.offset.x - shadow.spread, y: rect.origin.y + shadow.offset.y - shadow.spread },
+        size: Size { width: rect.size.width + shadow.spread * 2.0, height: rect.size.height + shadow.spread * 2.0 }
+    };
+
+    // only rounded corners grow with the spread
+    let grow = |radius: f32| -> f32 { if radius > 0.0 { (radius + shadow.spread).max(0.0) } else { 0.0 } };
+
+    let shadow_radii = CornerRadii {
+        top_left: grow(corner_radii.top_left),
+        top_right: grow(corner_radii.top_right),
+        bottom_right: grow(corner_radii.bottom_right),
+        bottom_left: grow(corner_radii.bottom_left)
+    };
+
+    context.draw_shadow(shadow_rect, shadow_radii, shadow.blur, shadow.color, Some((rect, corner_radii)));
+}
+
+// fills the ring between the view's outline and the outline inset by the border widths,
+// sides of different colors meet on the diagonals through the corners
+fn render_border(context: &mut DrawingContext, rect: Rect, borders: [Border; 4], corner_radii: CornerRadii, brush: Option<Brush>)
+{
+    let [top, right, bottom, left] = borders;
+
+    // a brush ignores the border colors, so only the widths decide whether there is anything to draw
+    let hidden = match &brush
+    {
+        Some(brush) => brush.is_transparent() || borders.iter().all(|border| border.width <= 0.0),
+        None => borders.iter().all(|border| border.width <= 0.0 || border.color.a <= 0.0)
+    };
+
+    if hidden
+    {
+        return;
+    }
+
+    let inner = Rect {
+        origin: Point { x: rect.origin.x + left.width, y: rect.origin.y + top.width },
+        size: Size {
+            width: (rect.size.width - left.width - right.width).max(0.0),
+            height: (rect.size.height - top.width - bottom.width).max(0.0)
+        }
+    };
+
+    let inner_corner = |radius: f32, horizontal: f32, vertical: f32| -> Size {
+        return Size { width: (radius - horizontal).max(0.0), height: (radius - vertical).max(0.0) };
+    };
+
+    let mut ring = Path::new();
+    ring.add_rounded_rect_corners(rect, corner_radii);
+    ring.add_elliptical_corners(inner, [
+        inner_corner(corner_radii.top_left, left.width, top.width),
+        inner_corner(corner_radii.top_right, right.width, top.width),
+        inner_corner(corner_radii.bottom_right, right.width, bottom.width),
+        inner_corner(corner_radii.bottom_left, left.width, bottom.width)
+    ]);
+
+    if let Some(brush) = brush
+    {
+        context.fill_path(&ring, FillRule::EvenOdd, brush.translated(rect.origin));
+        return;
+    }
+
+    let visible: Vec<&Border> = borders.iter().filter(|border| border.width > 0.0).collect();
+    let single_color = visible.windows(2).all(|pair| {
+        pair[0].color.r == pair[1].color.r && pair[0].color.g == pair[1].color.g && pair[0].color.b == pair[1].color.b && pair[0].color.a == pair[1].color.a
+    });
+
+    if single_color
+    {
+        context.fill_path(&ring, FillRule::EvenOdd, visible[0].color);
+        return;
+    }
+
+    // the diagonals run from each outer corner through the inner corner, as far as they can without crossing
+    let reach = (rect.size.height / 2.0 / top.width.max(bottom.width).max(f32::EPSILON))
+        .min(rect.size.width / 2.0 / left.width.max(right.width).max(f32::EPSILON))
+        .max(1.0);
+
+    let left_x = rect.origin.x;
+    let top_y = rect.origin.y;
+    let right_x = rect.origin.x + rect.size.width;
+    let bottom_y = rect.origin.y + rect.size.height;
+
+    let outer = [
+        Point { x: left_x, y: top_y },
+        Point { x: right_x, y: top_y },
+        Point { x: right_x, y: bottom_y },
+        Point { x: left_x, y: bottom_y }
+    ];
+
+    let diagonal = [
+        Point { x: left_x + left.width * reach, y: top_y + top.width * reach },
+        Point { x: right_x - right.width * reach, y: top_y + top.width * reach },
+        Point { x: right_x - right.width * reach, y: bottom_y - bottom.width * reach },
+        Point { x: left_x + left.width * reach, y: bottom_y - bottom.width * reach }
+    ];
+
+    for (index, border) in borders.iter().enumerate()
+    {
+        if border.width <= 0.0 || border.color.a <= 0.0
+        {
+            continue;
+        }
+
+        let next = (index + 1) % 4;
+
+        let mut wedge = Path::new();
+        wedge.move_to(outer[index]);
+        wedge.line_to(outer[next]);
+        wedge.line_to(diagonal[next]);
+        wedge.line_to(diagonal[index]);
+        wedge.close();
+
+        context.fill_path_clipped(&ring, FillRule::EvenOdd, &wedge, border.color);
+    }
+}
+
 fn set_view_rect(view: View, rect: Rect)
 {
     //log(LogLevel::INFO, &format!("Sizing view: {}", view));
@@ -417,4 +1709,88 @@ fn set_view_rect(view: View, rect: Rect)
         }
     });
 
+}
+
+fn update_font<F: FnOnce(&mut FontDescriptor)>(view: View, update: F)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            update(&mut view_object.font);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub(crate) fn set_render_handler(view: View, handler: RenderHandler)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.render_handler = Some(handler);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub(crate) fn set_event_handler(view: View, handler: EventHandler)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.event_handler = Some(handler);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+pub(crate) fn set_layout_handler(view: View, handler: LayoutHandler)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            view_object.layout_handler = Some(handler);
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
+}
+
+// a view can have several, such as a widget's own and one for a context menu set on it
+pub(crate) fn add_destroy_handler(view: View, handler: DestroyHandler)
+{
+    VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(view_object) = state.views.get_mut(&view)
+        {
+            if !view_object.destroy_handlers.iter().any(|existing| *existing as usize == handler as usize)
+            {
+                view_object.destroy_handlers.push(handler);
+            }
+        }
+        else 
+        {
+            log(LogLevel::FAIL, &format!("View not found!"));
+        }
+    });
 }
\ No newline at end of file
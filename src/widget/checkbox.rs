@@ -0,0 +1,251 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  checkbox.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the Checkbox widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect, debug::*};
+
+pub use crate::View;
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, MouseButton};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckState
+{
+    Unchecked,
+    Checked,
+    Indeterminate
+}
+
+pub type CheckboxChangedHandler = Box<dyn FnMut(View, CheckState)>;
+
+struct CheckboxObject
+{
+    pub text: String,
+    pub state: CheckState,
+    pub pressed: bool,
+
+    pub changed_handler: Option<CheckboxChangedHandler>
+}
+
+struct CheckboxState
+{
+    pub checkboxes: HashMap<View, CheckboxObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static CHECKBOX_STATE: RefCell<CheckboxState> = RefCell::new(CheckboxState {
+        checkboxes: HashMap::new()
+    });
+}
+
+const DEFAULT_CHECKBOX_SIZE: Size = Size { width: 160.0, height: 24.0 };
+const BOX_SIZE: Size = Size { width: 16.0, height: 16.0 };
+const BOX_RADIUS: f32 = 3.0;
+const BOX_BORDER_WIDTH: f32 = 1.5;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+
+    CHECKBOX_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.checkboxes.insert(new_view, CheckboxObject {
+            text: String::from(""),
+            state: CheckState::Unchecked,
+            pressed: false,
+            changed_handler: None
+        });
+    });
+
+    crate::view::set_size(new_view, DEFAULT_CHECKBOX_SIZE);
+    crate::view::set_focusable(new_view, true);
+    crate::view::set_render_handler(new_view, render);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+pub fn set_text(view: View, text: &str)
+{
+    with_checkbox(view, |checkbox_object| checkbox_object.text = String::from(text));
+}
+
+pub fn get_text(view: View) -> String
+{
+    return with_checkbox(view, |checkbox_object| checkbox_object.text.clone()).unwrap_or(String::from(""));
+}
+
+// changing the state from code does not call the changed handler
+pub fn set_state(view: View, check_state: CheckState)
+{
+    with_checkbox(view, |checkbox_object| checkbox_object.state = check_state);
+}
+
+pub fn get_state(view: View) -> CheckState
+{
+    return with_checkbox(view, |checkbox_object| checkbox_object.state).unwrap_or(CheckState::Unchecked);
+}
+
+pub fn set_checked(view: View, checked: bool)
+{
+    set_state(view, if checked { CheckState::Checked } else { CheckState::Unchecked });
+}
+
+pub fn is_checked(view: View) -> bool
+{
+    return get_state(view) == CheckState::Checked;
+}
+
+// called whenever the user changes the state by pointer or keyboard
+pub fn set_changed_handler<F: FnMut(View, CheckState) + 'static>(view: View, handler: F)
+{
+    with_checkbox(view, |checkbox_object| checkbox_object.changed_handler = Some(Box::new(handler)));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    CHECKBOX_STATE.with(|state| state.borrow_mut().checkboxes.remove(&view));
+}
+
+fn with_checkbox<T, F: FnOnce(&mut CheckboxObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return CHECKBOX_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(checkbox_object) = state.checkboxes.get_mut(&view)
+        {
+            return Some(action(checkbox_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Checkbox not found!"));
+            return None;
+        }
+    });
+}
+
+// indeterminate and unchecked boxes become checked, checked boxes become unchecked
+fn toggle(view: View)
+{
+    let new_state = match get_state(view)
+    {
+        CheckState::Checked => CheckState::Unchecked,
+        CheckState::Unchecked | CheckState::Indeterminate => CheckState::Checked
+    };
+
+    set_state(view, new_state);
+
+    crate::widget::call_taken(|slot| with_checkbox(view, |checkbox_object| slot(&mut checkbox_object.changed_handler)), |handler| handler(view, new_state));
+}
+
+fn handle_event(view: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { button: MouseButton::Left, .. } =>
+        {
+            with_checkbox(view, |checkbox_object| checkbox_object.pressed = true);
+            return true;
+        },
+        Event::MouseUp { point, button: MouseButton::Left, .. } =>
+        {
+            let was_pressed = with_checkbox(view, |checkbox_object| std::mem::replace(&mut checkbox_object.pressed, false)).unwrap_or(false);
+
+            if was_pressed && crate::view::rect_contains(crate::view::get_rect(view), *point)
+            {
+                toggle(view);
+            }
+
+            return true;
+        },
+        Event::KeyDown { key: Key::Space, .. } =>
+        {
+            toggle(view);
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn render(view: View, context: &mut DrawingContext)
+{
+    let (text, check_state) = match with_checkbox(view, |checkbox_object| (checkbox_object.text.clone(), checkbox_object.state))
+    {
+        Some(checkbox_properties) => checkbox_properties,
+        None => return
+    };
+
+    let color = crate::view::get_foreground_color(view);
+    let box_rect = crate::widget::indicator_rect(view, BOX_SIZE);
+
+    crate::widget::draw_focus_ring(view, context, box_rect, BOX_RADIUS);
+
+    match check_state
+    {
+        CheckState::Unchecked =>
+        {
+            context.stroke_rounded_rect(box_rect, BOX_RADIUS, BOX_BORDER_WIDTH, color);
+        },
+        CheckState::Checked =>
+        {
+            context.stroke_rounded_rect(box_rect, BOX_RADIUS, BOX_BORDER_WIDTH, color);
+
+            let x = box_rect.origin.x;
+            let y = box_rect.origin.y;
+            let size = box_rect.size.width;
+
+            crate::widget::stroke_polyline(context, &[
+                Point { x: x + size * 0.22, y: y + size * 0.52 },
+                Point { x: x + size * 0.42, y: y + size * 0.72 },
+                Point { x: x + size * 0.78, y: y + size * 0.30 }
+            ], 2.0, color);
+        },
+        CheckState::Indeterminate =>
+        {
+            context.stroke_rounded_rect(box_rect, BOX_RADIUS, BOX_BORDER_WIDTH, color);
+
+            context.fill_rect(Rect {
+                origin: Point { x: box_rect.origin.x + box_rect.size.width * 0.25, y: box_rect.origin.y + box_rect.size.height / 2.0 - 1.0 },
+                size: Size { width: box_rect.size.width * 0.5, height: 2.0 }
+            }, color);
+        }
+    }
+
+    let text_x = box_rect.origin.x + box_rect.size.width + crate::widget::INDICATOR_SPACING;
+    crate::widget::draw_view_text(view, context, text_x, &text);
+}
@@ -0,0 +1,604 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  combo_box.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the ComboBox widget and its popup list.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect, debug::*};
+
+pub use crate::{View, Window};
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, MouseButton};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+pub type ComboBoxSelectedHandler = Box<dyn FnMut(View, usize)>;
+
+struct ComboBoxObject
+{
+    pub items: Vec<String>,
+    pub selected_index: Option<usize>,
+
+    // the popup list is a separate view shown in the window's overlay layer
+    pub popup: View,
+    pub open: bool,
+    pub highlighted_index: usize,
+    pub first_visible_index: usize,
+
+    pub type_ahead: String,
+    pub last_type_ahead: Instant,
+
+    pub selected_handler: Option<ComboBoxSelectedHandler>
+}
+
+struct ComboBoxState
+{
+    pub combo_boxes: HashMap<View, ComboBoxObject>,
+    pub popup_owners: HashMap<View, View>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static COMBO_BOX_STATE: RefCell<ComboBoxState> = RefCell::new(ComboBoxState {
+        combo_boxes: HashMap::new(),
+        popup_owners: HashMap::new()
+    });
+}
+
+const DEFAULT_COMBO_BOX_SIZE: Size = Size { width: 160.0, height: 26.0 };
+const CORNER_RADIUS: f32 = 3.0;
+const BORDER_WIDTH: f32 = 1.0;
+const TEXT_PADDING: f32 = 6.0;
+const ARROW_SIZE: f32 = 8.0;
+
+const ROW_HEIGHT: f32 = 24.0;
+const MAX_VISIBLE_ROWS: usize = 8;
+
+// border and highlight colors are the foreground color at these alphas
+const BORDER_ALPHA: f32 = 0.5;
+const HIGHLIGHT_ALPHA: f32 = 0.15;
+
+// popups over a transparent combo box use this background
+const DEFAULT_POPUP_BACKGROUND: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+
+// typed characters within this time of each other build up one search string
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+    let popup = crate::view::create();
+
+    COMBO_BOX_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.combo_boxes.insert(new_view, ComboBoxObject {
+            items: Vec::new(),
+            selected_index: None,
+            popup: popup,
+            open: false,
+            highlighted_index: 0,
+            first_visible_index: 0,
+            type_ahead: String::from(""),
+            last_type_ahead: crate::app::clock::now(),
+            selected_handler: None
+        });
+
+        state.popup_owners.insert(popup, new_view);
+    });
+
+    crate::view::set_size(new_view, DEFAULT_COMBO_BOX_SIZE);
+    crate::view::set_focusable(new_view, true);
+    crate::view::set_render_handler(new_view, render);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::set_layout_handler(new_view, layout);
+
+    crate::view::add_destroy_handler(new_view, forget);
+
+    crate::view::set_render_handler(popup, render_popup);
+    crate::view::set_event_handler(popup, handle_popup_event);
+    crate::view::add_destroy_handler(popup, forget_popup);
+
+    return new_view;
+}
+
+pub fn set_items(view: View, items: Vec<String>)
+{
+    with_combo_box(view, |combo_box_object| {
+        combo_box_object.selected_index = combo_box_object.selected_index.filter(|index| *index < items.len());
+        combo_box_object.highlighted_index = 0;
+        combo_box_object.first_visible_index = 0;
+        combo_box_object.items = items;
+    });
+}
+
+pub fn add_item(view: View, item: &str)
+{
+    with_combo_box(view, |combo_box_object| combo_box_object.items.push(String::from(item)));
+}
+
+pub fn get_items(view: View) -> Vec<String>
+{
+    return with_combo_box(view, |combo_box_object| combo_box_object.items.clone()).unwrap_or(Vec::new());
+}
+
+// selecting from code does not call the selected handler
+pub fn set_selected_index(view: View, index: Option<usize>)
+{
+    with_combo_box(view, |combo_box_object| {
+        combo_box_object.selected_index = index.filter(|index| *index < combo_box_object.items.len());
+    });
+}
+
+pub fn get_selected_index(view: View) -> Option<usize>
+{
+    return with_combo_box(view, |combo_box_object| combo_box_object.selected_index).flatten();
+}
+
+pub fn get_selected_item(view: View) -> Option<String>
+{
+    return with_combo_box(view, |combo_box_object| {
+        combo_box_object.selected_index.and_then(|index| combo_box_object.items.get(index).cloned())
+    }).flatten();
+}
+
+// called with the item index whenever the user chooses an item
+pub fn set_selected_handler<F: FnMut(View, usize) + 'static>(view: View, handler: F)
+{
+    with_combo_box(view, |combo_box_object| combo_box_object.selected_handler = Some(Box::new(handler)));
+}
+
+pub fn is_open(view: View) -> bool
+{
+    return with_combo_box(view, |combo_box_object| combo_box_object.open).unwrap_or(false);
+}
+
+// opens the popup list below the combo box, or above it when there is no room below
+pub fn open(view: View)
+{
+    let window = match crate::view::get_window(view)
+    {
+        Some(window) => window,
+        None => return
+    };
+
+    let (popup, item_count, selected_index) = match with_combo_box(view, |combo_box_object| {
+        (combo_box_object.popup, combo_box_object.items.len(), combo_box_object.selected_index)
+    })
+    {
+        Some(combo_box_properties) => combo_box_properties,
+        None => return
+    };
+
+    if item_count == 0
+    {
+        return;
+    }
+
+    let popup_rect = get_popup_rect(view, window, item_count);
+
+    let background = crate::view::get_background_color(view);
+    crate::view::set_background_color(popup, if background.a > 0.0 { background } else { DEFAULT_POPUP_BACKGROUND });
+    crate::view::set_foreground_color(popup, crate::view::get_foreground_color(view));
+    crate::view::set_font_size(popup, crate::view::get_font(view).size);
+
+    with_combo_box(view, |combo_box_object| {
+        combo_box_object.open = true;
+        combo_box_object.highlighted_index = selected_index.unwrap_or(0);
+        scroll_to_highlight(combo_box_object);
+    });
+
+    crate::window::add_overlay(window, popup, popup_rect, true);
+}
+
+pub fn close(view: View)
+{
+    let popup = match with_combo_box(view, |combo_box_object| {
+        combo_box_object.open = false;
+        combo_box_object.popup
+    })
+    {
+        Some(popup) => popup,
+        None => return
+    };
+
+    if let Some(window) = crate::view::get_window(view)
+    {
+        crate::window::remove_overlay(window, popup);
+    }
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// below the combo box as it is drawn in the window, or above it when there is no room below
+fn get_popup_rect(view: View, window: Window, item_count: usize) -> Rect
+{
+    let rect = crate::view::get_rect(view);
+    let transform = crate::view::get_window_transform(view);
+
+    let corners = [
+        transform.apply(rect.origin),
+        transform.apply(Point { x: rect.origin.x + rect.size.width, y: rect.origin.y }),
+        transform.apply(Point { x: rect.origin.x, y: rect.origin.y + rect.size.height }),
+        transform.apply(Point { x: rect.origin.x + rect.size.width, y: rect.origin.y + rect.size.height })
+    ];
+
+    let left = corners.iter().map(|corner| corner.x).fold(f32::MAX, f32::min);
+    let right = corners.iter().map(|corner| corner.x).fold(f32::MIN, f32::max);
+    let top = corners.iter().map(|corner| corner.y).fold(f32::MAX, f32::min);
+    let bottom = corners.iter().map(|corner| corner.y).fold(f32::MIN, f32::max);
+
+    let window_size = crate::window::get_size(window);
+    let popup_height = item_count.min(MAX_VISIBLE_ROWS) as f32 * ROW_HEIGHT;
+
+    let popup_y = if bottom + popup_height > window_size.height && top - popup_height >= 0.0 { top - popup_height } else { bottom };
+
+    return Rect {
+        origin: Point { x: left, y: popup_y },
+        size: Size { width: right - left, height: popup_height }
+    };
+}
+
+// an open popup follows the combo box when a window resize or its parent's layout moves it
+fn layout(view: View, _rect: Rect)
+{
+    let (open, popup, item_count) = match with_combo_box(view, |combo_box_object| {
+        (combo_box_object.open, combo_box_object.popup, combo_box_object.items.len())
+    })
+    {
+        Some(combo_box_properties) => combo_box_properties,
+        None => return
+    };
+
+    if !open
+    {
+        return;
+    }
+
+    if let Some(window) = crate::view::get_window(view)
+    {
+        crate::window::set_overlay_rect(window, popup, get_popup_rect(view, window, item_count));
+    }
+}
+
+// the popup is only in the window while it is open, so it goes with the combo box here rather than as a child
+fn forget(view: View)
+{
+    let combo_box_object = COMBO_BOX_STATE.with(|state| state.borrow_mut().combo_boxes.remove(&view));
+
+    if let Some(combo_box_object) = combo_box_object
+    {
+        crate::view::destroy(combo_box_object.popup);
+    }
+}
+
+fn forget_popup(popup: View)
+{
+    COMBO_BOX_STATE.with(|state| state.borrow_mut().popup_owners.remove(&popup));
+}
+
+fn with_combo_box<T, F: FnOnce(&mut ComboBoxObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return COMBO_BOX_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(combo_box_object) = state.combo_boxes.get_mut(&view)
+        {
+            return Some(action(combo_box_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Combo box not found!"));
+            return None;
+        }
+    });
+}
+
+fn get_popup_owner(popup: View) -> Option<View>
+{
+    return COMBO_BOX_STATE.with(|state| {
+        return state.borrow().popup_owners.get(&popup).copied();
+    });
+}
+
+fn scroll_to_highlight(combo_box_object: &mut ComboBoxObject)
+{
+    let highlighted_index = combo_box_object.highlighted_index;
+
+    if highlighted_index < combo_box_object.first_visible_index
+    {
+        combo_box_object.first_visible_index = highlighted_index;
+    }
+    else if highlighted_index >= combo_box_object.first_visible_index + MAX_VISIBLE_ROWS
+    {
+        combo_box_object.first_visible_index = highlighted_index + 1 - MAX_VISIBLE_ROWS;
+    }
+}
+
+fn choose(view: View, index: usize)
+{
+    let changed = with_combo_box(view, |combo_box_object| {
+        if index >= combo_box_object.items.len()
+        {
+            return false;
+        }
+
+        let changed = combo_box_object.selected_index != Some(index);
+        combo_box_object.selected_index = Some(index);
+        return changed;
+    }).unwrap_or(false);
+
+    close(view);
+
+    if !changed
+    {
+        return;
+    }
+
+    crate::widget::call_taken(|slot| with_combo_box(view, |combo_box_object| slot(&mut combo_box_object.selected_handler)), |handler| handler(view, index));
+}
+
+// finds the next item starting with the typed text, repeating one letter cycles through matches
+fn type_ahead(view: View, character: char) -> Option<usize>
+{
+    return with_combo_box(view, |combo_box_object| {
+        let now = crate::app::clock::now();
+
+        if now.saturating_duration_since(combo_box_object.last_type_ahead) > TYPE_AHEAD_TIMEOUT
+        {
+            combo_box_object.type_ahead.clear();
+        }
+
+        combo_box_object.last_type_ahead = now;
+        combo_box_object.type_ahead.extend(character.to_lowercase());
+
+        let search = combo_box_object.type_ahead.clone();
+        let first_character = search.chars().next().unwrap_or(character);
+
+        // typing the same letter again moves on to the next match instead of extending the search
+        let cycling = search.chars().all(|search_character| search_character == first_character);
+        let prefix = if cycling { first_character.to_string() } else { search };
+
+        let count = combo_box_object.items.len();
+        let current = if combo_box_object.open { Some(combo_box_object.highlighted_index) } else { combo_box_object.selected_index };
+
+        let start = match current
+        {
+            Some(current) if cycling => current + 1,
+            Some(current) => current,
+            None => 0
+        };
+
+        return (0..count)
+            .map(|offset| (start + offset) % count)
+            .find(|index| combo_box_object.items[*index].to_lowercase().starts_with(&prefix));
+    }).flatten();
+}
+
+fn handle_event(view: View, event: &Event) -> bool
+{
+    let (is_open, item_count) = match with_combo_box(view, |combo_box_object| (combo_box_object.open, combo_box_object.items.len()))
+    {
+        Some(combo_box_properties) => combo_box_properties,
+        None => return false
+    };
+
+    match event
+    {
+        Event::MouseDown { button: MouseButton::Left, .. } =>
+        {
+            if is_open { close(view); } else { open(view); }
+            return true;
+        },
+        Event::FocusLost =>
+        {
+            close(view);
+            return false;
+        },
+        Event::KeyDown { key, modifiers } =>
+        {
+            if item_count == 0
+            {
+                return false;
+            }
+
+            if !is_open
+            {
+                // closed combo boxes step through the items in place
+                let selected_index = get_selected_index(view);
+
+                let new_index = match key
+                {
+                    Key::Down if modifiers.alt => { open(view); return true; },
+                    Key::Space | Key::Enter => { open(view); return true; },
+                    Key::Down => selected_index.map(|index| (index + 1).min(item_count - 1)).unwrap_or(0),
+                    Key::Up => selected_index.map(|index| index.saturating_sub(1)).unwrap_or(0),
+                    Key::Home => 0,
+                    Key::End => item_count - 1,
+                    Key::Character(character) if !modifiers.control && !modifiers.meta => match type_ahead(view, *character) { Some(index) => index, None => return false },
+                    _ => return false
+                };
+
+                choose(view, new_index);
+                return true;
+            }
+
+            let highlighted_index = with_combo_box(view, |combo_box_object| combo_box_object.highlighted_index).unwrap_or(0);
+
+            let new_highlight = match key
+            {
+                Key::Escape => { close(view); return true; },
+                Key::Enter | Key::Space => { choose(view, highlighted_index); return true; },
+                Key::Tab => { close(view); return false; },
+                Key::Down => (highlighted_index + 1).min(item_count - 1),
+                Key::Up => highlighted_index.saturating_sub(1),
+                Key::PageDown => (highlighted_index + MAX_VISIBLE_ROWS).min(item_count - 1),
+                Key::PageUp => highlighted_index.saturating_sub(MAX_VISIBLE_ROWS),
+                Key::Home => 0,
+                Key::End => item_count - 1,
+                // shortcuts and letters nothing starts with go on to commands and menu accelerators
+                Key::Character(character) if !modifiers.control && !modifiers.meta => match type_ahead(view, *character) { Some(index) => index, None => return false },
+                _ => return false
+            };
+
+            with_combo_box(view, |combo_box_object| {
+                combo_box_object.highlighted_index = new_highlight;
+                scroll_to_highlight(combo_box_object);
+            });
+
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn row_at_point(owner: View, popup: View, point: Point) -> Option<usize>
+{
+    let rect = crate::view::get_rect(popup);
+    let row = ((point.y - rect.origin.y) / ROW_HEIGHT).floor();
+
+    if row < 0.0 || !crate::view::rect_contains(rect, point)
+    {
+        return None;
+    }
+
+    return with_combo_box(owner, |combo_box_object| {
+        Some(combo_box_object.first_visible_index + row as usize).filter(|index| *index < combo_box_object.items.len())
+    }).flatten();
+}
+
+fn handle_popup_event(popup: View, event: &Event) -> bool
+{
+    let owner = match get_popup_owner(popup)
+    {
+        Some(owner) => owner,
+        None => return false
+    };
+
+    match event
+    {
+        Event::MouseMove { point } =>
+        {
+            if let Some(row) = row_at_point(owner, popup, *point)
+            {
+                with_combo_box(owner, |combo_box_object| combo_box_object.highlighted_index = row);
+            }
+
+            return true;
+        },
+        Event::MouseDown { .. } => return true,
+        Event::MouseUp { point, button: MouseButton::Left, .. } =>
+        {
+            if let Some(row) = row_at_point(owner, popup, *point)
+            {
+                choose(owner, row);
+            }
+
+            return true;
+        },
+        Event::Dismissed =>
+        {
+            with_combo_box(owner, |combo_box_object| combo_box_object.open = false);
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn render(view: View, context: &mut DrawingContext)
+{
+    let text = get_selected_item(view).unwrap_or(String::from(""));
+
+    let rect = crate::view::get_rect(view);
+    let color = crate::view::get_foreground_color(view);
+
+    crate::widget::draw_focus_ring(view, context, rect, CORNER_RADIUS);
+    context.stroke_rounded_rect(rect, CORNER_RADIUS, BORDER_WIDTH, crate::widget::with_alpha(color, BORDER_ALPHA));
+
+    // downward arrow at the trailing edge
+    let arrow_center = Point { x: rect.origin.x + rect.size.width - TEXT_PADDING - ARROW_SIZE / 2.0, y: rect.origin.y + rect.size.height / 2.0 };
+
+    context.fill_polygon(&[
+        Point { x: arrow_center.x - ARROW_SIZE / 2.0, y: arrow_center.y - ARROW_SIZE / 4.0 },
+        Point { x: arrow_center.x + ARROW_SIZE / 2.0, y: arrow_center.y - ARROW_SIZE / 4.0 },
+        Point { x: arrow_center.x, y: arrow_center.y + ARROW_SIZE / 4.0 }
+    ], color);
+
+    context.push_clip(Rect {
+        origin: rect.origin,
+        size: Size { width: (rect.size.width - ARROW_SIZE - 2.0 * TEXT_PADDING).max(0.0), height: rect.size.height }
+    });
+    crate::widget::draw_view_text(view, context, rect.origin.x + TEXT_PADDING, &text);
+    context.pop_clip();
+}
+
+fn render_popup(popup: View, context: &mut DrawingContext)
+{
+    let owner = match get_popup_owner(popup)
+    {
+        Some(owner) => owner,
+        None => return
+    };
+
+    let (items, highlighted_index, first_visible_index) = match with_combo_box(owner, |combo_box_object| {
+        (combo_box_object.items.clone(), combo_box_object.highlighted_index, combo_box_object.first_visible_index)
+    })
+    {
+        Some(popup_properties) => popup_properties,
+        None => return
+    };
+
+    let rect = crate::view::get_rect(popup);
+    let color = crate::view::get_foreground_color(popup);
+    let font = crate::view::get_font(popup);
+    let metrics = crate::drawing::font::get_metrics(&font);
+
+    context.push_clip(rect);
+
+    for (row, index) in (first_visible_index..items.len()).take(MAX_VISIBLE_ROWS).enumerate()
+    {
+        let row_rect = Rect {
+            origin: Point { x: rect.origin.x, y: rect.origin.y + row as f32 * ROW_HEIGHT },
+            size: Size { width: rect.size.width, height: ROW_HEIGHT }
+        };
+
+        if index == highlighted_index
+        {
+            context.fill_rect(row_rect, crate::widget::with_alpha(color, HIGHLIGHT_ALPHA));
+        }
+
+        let origin = Point { x: row_rect.origin.x + TEXT_PADDING, y: row_rect.origin.y + (ROW_HEIGHT - metrics.line_height) / 2.0 };
+        context.draw_text(origin, &items[index], &font, color);
+    }
+
+    context.pop_clip();
+
+    context.stroke_rounded_rect(rect, 0.0, BORDER_WIDTH, crate::widget::with_alpha(color, BORDER_ALPHA));
+}
@@ -0,0 +1,844 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  data_grid.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the DataGrid widget, a table of cells
+**                 read from a data source with a ListView body.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect, debug::*};
+
+pub use crate::View;
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, MouseButton};
+use crate::widget::list_view::{self, SelectionMode};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortDirection
+{
+    Ascending,
+    Descending
+}
+
+// the model behind a data grid, cells are read only when their row is on screen
+pub trait DataGridSource
+{
+    fn row_count(&self) -> usize;
+    fn cell_text(&self, row: usize, column: usize) -> String;
+
+    // reorders the rows when the user clicks a sortable column header, and returns the index each row had before
+    // so the selection can follow it, or None to leave the selection where it is
+    fn sort(&mut self, column: usize, direction: SortDirection) -> Option<Vec<usize>>
+    {
+        let _ = (column, direction);
+        return None;
+    }
+}
+
+// receives the selected cell as a row and column
+pub type DataGridSelectionChangedHandler = Box<dyn FnMut(View, Option<(usize, usize)>)>;
+
+struct DataGridColumn
+{
+    pub title: String,
+    pub width: f32,
+    pub sortable: bool
+}
+
+// a column border being dragged in the header
+#[derive(Clone, Copy)]
+struct ColumnResize
+{
+    pub column: usize,
+    pub start_x: f32,
+    pub start_width: f32
+}
+
+struct DataGridObject
+{
+    pub header: View,
+    pub list: View,
+
+    pub source: Option<Box<dyn DataGridSource>>,
+    pub columns: Vec<DataGridColumn>,
+
+    pub sort: Option<(usize, SortDirection)>,
+    pub first_column_frozen: bool,
+    pub horizontal_offset: f32,
+
+    pub selected_column: Option<usize>,
+
+    pub pressed_column: Option<usize>,
+    pub resize: Option<ColumnResize>,
+
+    pub selection_changed_handler: Option<DataGridSelectionChangedHandler>
+}
+
+struct DataGridState
+{
+    pub data_grids: HashMap<View, DataGridObject>,
+
+    // headers and rows lead back to their grid, rows also to the index they show
+    pub headers: HashMap<View, View>,
+    pub rows: HashMap<View, (View, usize)>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static DATA_GRID_STATE: RefCell<DataGridState> = RefCell::new(DataGridState {
+        data_grids: HashMap::new(),
+        headers: HashMap::new(),
+        rows: HashMap::new()
+    });
+}
+
+const DEFAULT_DATA_GRID_SIZE: Size = Size { width: 400.0, height: 240.0 };
+const DEFAULT_COLUMN_WIDTH: f32 = 100.0;
+const MIN_COLUMN_WIDTH: f32 = 24.0;
+
+const HEADER_HEIGHT: f32 = 26.0;
+const CELL_PADDING: f32 = 6.0;
+const SORT_ARROW_SIZE: f32 = 8.0;
+
+// presses this close to the right edge of a header column drag its width
+const RESIZE_GRIP_WIDTH: f32 = 4.0;
+
+// header, grid line and selection colors are the foreground color at these alphas
+const HEADER_ALPHA: f32 = 0.08;
+const GRID_LINE_ALPHA: f32 = 0.2;
+const SELECTED_CELL_ALPHA: f32 = 0.6;
+
+// the frozen column covers scrolled cells, over a transparent grid it uses this background
+const DEFAULT_FROZEN_BACKGROUND: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+    let header = crate::view::create();
+    let list = list_view::create();
+
+    DATA_GRID_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.data_grids.insert(new_view, DataGridObject {
+            header: header,
+            list: list,
+            source: None,
+            columns: Vec::new(),
+            sort: None,
+            first_column_frozen: false,
+            horizontal_offset: 0.0,
+            selected_column: None,
+            pressed_column: None,
+            resize: None,
+            selection_changed_handler: None
+        });
+
+        state.headers.insert(header, new_view);
+    });
+
+    crate::view::set_size(new_view, DEFAULT_DATA_GRID_SIZE);
+    crate::view::set_clips_children(new_view, true);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::set_layout_handler(new_view, layout);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    crate::view::set_render_handler(header, render_header);
+    crate::view::set_event_handler(header, handle_header_event);
+    crate::view::add_destroy_handler(header, forget_header);
+
+    crate::view::add_child(new_view, header);
+    crate::view::add_child(new_view, list);
+
+    list_view::set_selection_mode(list, SelectionMode::Single);
+    list_view::set_row_builder(list, move |_, index| create_row(new_view, index));
+    list_view::set_row_recycler(list, move |_, index, row| bind_row(new_view, row, index));
+    list_view::set_selection_changed_handler(list, move |_, _| notify_selection_changed(new_view));
+
+    return new_view;
+}
+
+pub fn set_data_source<S: DataGridSource + 'static>(view: View, source: S)
+{
+    with_data_grid(view, |data_grid_object| {
+        data_grid_object.source = Some(Box::new(source));
+        data_grid_object.sort = None;
+    });
+
+    reload(view);
+}
+
+// reads the row count again and redraws every cell, for when the data source changed
+pub fn reload(view: View)
+{
+    let row_count = with_source(view, |source| source.row_count()).unwrap_or(0);
+
+    if let Some(list) = get_list(view)
+    {
+        list_view::set_item_count(list, row_count);
+    }
+}
+
+// appends a column and returns its index, the data source is asked for cells by this index
+pub fn add_column(view: View, title: &str, width: f32) -> usize
+{
+    let column = with_data_grid(view, |data_grid_object| {
+        data_grid_object.columns.push(DataGridColumn {
+            title: String::from(title),
+            width: width.max(MIN_COLUMN_WIDTH),
+            sortable: true
+        });
+
+        return data_grid_object.columns.len() - 1;
+    }).unwrap_or(0);
+
+    relayout(view);
+
+    return column;
+}
+
+pub fn get_column_count(view: View) -> usize
+{
+    return with_data_grid(view, |data_grid_object| data_grid_object.columns.len()).unwrap_or(0);
+}
+
+pub fn set_column_title(view: View, column: usize, title: &str)
+{
+    with_column(view, column, |column_object| column_object.title = String::from(title));
+}
+
+pub fn get_column_title(view: View, column: usize) -> String
+{
+    return with_column(view, column, |column_object| column_object.title.clone()).unwrap_or(String::from(""));
+}
+
+pub fn set_column_width(view: View, column: usize, width: f32)
+{
+    with_column(view, column, |column_object| column_object.width = width.max(MIN_COLUMN_WIDTH));
+
+    relayout(view);
+}
+
+pub fn get_column_width(view: View, column: usize) -> f32
+{
+    return with_column(view, column, |column_object| column_object.width).unwrap_or(DEFAULT_COLUMN_WIDTH);
+}
+
+// clicking the header of a sortable column sorts the data source by it
+pub fn set_column_sortable(view: View, column: usize, sortable: bool)
+{
+    with_column(view, column, |column_object| column_object.sortable = sortable);
+}
+
+// sorts the data source by a column and redraws the rows
+pub fn sort_by(view: View, column: usize, direction: SortDirection)
+{
+    if column >= get_column_count(view)
+    {
+        log(LogLevel::FAIL, &format!("Column not found!"));
+        return;
+    }
+
+    let previous_rows = with_source(view, |source| source.sort(column, direction)).flatten();
+    with_data_grid(view, |data_grid_object| data_grid_object.sort = Some((column, direction)));
+
+    reload(view);
+
+    if let (Some(previous_rows), Some(list)) = (previous_rows, get_list(view))
+    {
+        let selected_indices = list_view::get_selected_indices(list);
+
+        let moved_indices: Vec<usize> = previous_rows.iter()
+            .enumerate()
+            .filter(|(_, previous_row)| selected_indices.contains(previous_row))
+            .map(|(row, _)| row)
+            .collect();
+
+        list_view::set_selected_indices(list, &moved_indices);
+
+        if let Some(row) = moved_indices.first()
+        {
+            list_view::scroll_to_index(list, *row);
+        }
+    }
+}
+
+pub fn get_sort(view: View) -> Option<(usize, SortDirection)>
+{
+    return with_data_grid(view, |data_grid_object| data_grid_object.sort).flatten();
+}
+
+// a frozen first column stays in place while the other columns scroll sideways
+pub fn set_first_column_frozen(view: View, frozen: bool)
+{
+    with_data_grid(view, |data_grid_object| data_grid_object.first_column_frozen = frozen);
+
+    relayout(view);
+}
+
+pub fn is_first_column_frozen(view: View) -> bool
+{
+    return with_data_grid(view, |data_grid_object| data_grid_object.first_column_frozen).unwrap_or(false);
+}
+
+pub fn set_horizontal_offset(view: View, offset: f32)
+{
+    with_data_grid(view, |data_grid_object| data_grid_object.horizontal_offset = offset);
+
+    relayout(view);
+}
+
+pub fn get_horizontal_offset(view: View) -> f32
+{
+    return with_data_grid(view, |data_grid_object| data_grid_object.horizontal_offset).unwrap_or(0.0);
+}
+
+// selecting from code does not call the selection changed handler
+pub fn set_selected_cell(view: View, cell: Option<(usize, usize)>)
+{
+    let list = match get_list(view)
+    {
+        Some(list) => list,
+        None => return
+    };
+
+    with_data_grid(view, |data_grid_object| data_grid_object.selected_column = cell.map(|(_, column)| column));
+
+    match cell
+    {
+        Some((row, column)) =>
+        {
+            list_view::set_selected_indices(list, &[row]);
+            list_view::scroll_to_index(list, row);
+            scroll_to_column(view, column);
+        },
+        None => list_view::set_selected_indices(list, &[])
+    }
+}
+
+pub fn get_selected_cell(view: View) -> Option<(usize, usize)>
+{
+    let row = get_list(view).and_then(list_view::get_selected_index);
+    let column = with_data_grid(view, |data_grid_object| data_grid_object.selected_column).flatten();
+
+    return row.zip(column);
+}
+
+// called whenever the user selects a different cell
+pub fn set_selection_changed_handler<F: FnMut(View, Option<(usize, usize)>) + 'static>(view: View, handler: F)
+{
+    with_data_grid(view, |data_grid_object| data_grid_object.selection_changed_handler = Some(Box::new(handler)));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    DATA_GRID_STATE.with(|state| state.borrow_mut().data_grids.remove(&view));
+}
+
+fn forget_header(header: View)
+{
+    DATA_GRID_STATE.with(|state| state.borrow_mut().headers.remove(&header));
+}
+
+fn forget_row(row: View)
+{
+    DATA_GRID_STATE.with(|state| state.borrow_mut().rows.remove(&row));
+}
+
+fn with_data_grid<T, F: FnOnce(&mut DataGridObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return DATA_GRID_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(data_grid_object) = state.data_grids.get_mut(&view)
+        {
+            return Some(action(data_grid_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Data grid not found!"));
+            return None;
+        }
+    });
+}
+
+fn with_column<T, F: FnOnce(&mut DataGridColumn) -> T>(view: View, column: usize, action: F) -> Option<T>
+{
+    return with_data_grid(view, |data_grid_object| {
+        if let Some(column_object) = data_grid_object.columns.get_mut(column)
+        {
+            return Some(action(column_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Column not found!"));
+            return None;
+        }
+    }).flatten();
+}
+
+fn with_source<T, F: FnOnce(&mut Box<dyn DataGridSource>) -> T>(view: View, action: F) -> Option<T>
+{
+    return crate::widget::call_taken(|slot| with_data_grid(view, |data_grid_object| slot(&mut data_grid_object.source)), action);
+}
+
+fn get_list(view: View) -> Option<View>
+{
+    return with_data_grid(view, |data_grid_object| data_grid_object.list);
+}
+
+fn relayout(view: View)
+{
+    crate::view::layout_down(view, crate::view::get_rect(view));
+}
+
+// the header sits on top and the list of rows fills the rest
+fn layout(view: View, rect: Rect)
+{
+    let layout_properties = with_data_grid(view, |data_grid_object| {
+        let content_width: f32 = data_grid_object.columns.iter().map(|column_object| column_object.width).sum();
+        let max_offset = (content_width - rect.size.width).max(0.0);
+        data_grid_object.horizontal_offset = data_grid_object.horizontal_offset.clamp(0.0, max_offset);
+
+        return (data_grid_object.header, data_grid_object.list);
+    });
+
+    if let Some((header, list)) = layout_properties
+    {
+        let header_height = HEADER_HEIGHT.min(rect.size.height);
+
+        crate::view::layout_down(header, Rect {
+            origin: rect.origin,
+            size: Size { width: rect.size.width, height: header_height }
+        });
+
+        crate::view::layout_down(list, Rect {
+            origin: Point { x: rect.origin.x, y: rect.origin.y + header_height },
+            size: Size { width: rect.size.width, height: rect.size.height - header_height }
+        });
+    }
+}
+
+// returns the left edge and width of every column on screen, starting from the left edge x
+fn column_spans(view: View, x: f32) -> Vec<(f32, f32)>
+{
+    return with_data_grid(view, |data_grid_object| {
+        let mut left = x;
+
+        return data_grid_object.columns.iter().enumerate().map(|(column, column_object)| {
+            let offset = if column == 0 && data_grid_object.first_column_frozen { 0.0 } else { data_grid_object.horizontal_offset };
+            let span = (left - offset, column_object.width);
+            left += column_object.width;
+            return span;
+        }).collect();
+    }).unwrap_or(Vec::new());
+}
+
+// the frozen column is on top of the columns scrolled underneath it
+fn column_at(view: View, x: f32, left: f32) -> Option<usize>
+{
+    let spans = column_spans(view, left);
+
+    if is_first_column_frozen(view)
+    {
+        if let Some((frozen_left, frozen_width)) = spans.first()
+        {
+            if x >= *frozen_left && x < frozen_left + frozen_width
+            {
+                return Some(0);
+            }
+        }
+    }
+
+    return spans.iter().position(|(column_left, width)| x >= *column_left && x < column_left + width);
+}
+
+// scrolls sideways the least distance that shows the whole column
+fn scroll_to_column(view: View, column: usize)
+{
+    let rect = crate::view::get_rect(view);
+    let spans = column_spans(view, 0.0);
+    let frozen = is_first_column_frozen(view);
+
+    if column >= spans.len() || (frozen && column == 0)
+    {
+        return;
+    }
+
+    let visible_left = if frozen { spans[0].1 } else { 0.0 };
+    let offset = get_horizontal_offset(view);
+    let (left, width) = (spans[column].0 + offset, spans[column].1);
+
+    if left - offset < visible_left
+    {
+        set_horizontal_offset(view, left - visible_left);
+    }
+    else if left + width - offset > rect.size.width
+    {
+        set_horizontal_offset(view, left + width - rect.size.width);
+    }
+}
+
+fn notify_selection_changed(view: View)
+{
+    crate::widget::call_taken(|slot| with_data_grid(view, |data_grid_object| slot(&mut data_grid_object.selection_changed_handler)), |handler| handler(view, get_selected_cell(view)));
+}
+
+fn create_row(view: View, index: usize) -> View
+{
+    let row = crate::view::create();
+
+    crate::view::set_foreground_color(row, crate::view::get_foreground_color(view));
+
+    let font = crate::view::get_font(view);
+    crate::view::set_font_family(row, &font.family);
+    crate::view::set_font_size(row, font.size);
+    crate::view::set_font_weight(row, font.weight);
+    crate::view::set_font_italic(row, font.italic);
+
+    crate::view::set_render_handler(row, render_row);
+    crate::view::set_event_handler(row, handle_row_event);
+    crate::view::add_destroy_handler(row, forget_row);
+
+    return bind_row(view, row, index);
+}
+
+fn bind_row(view: View, row: View, index: usize) -> View
+{
+    DATA_GRID_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.rows.insert(row, (view, index));
+    });
+
+    return row;
+}
+
+fn get_row(row: View) -> Option<(View, usize)>
+{
+    return DATA_GRID_STATE.with(|state| state.borrow().rows.get(&row).cloned());
+}
+
+fn get_header_owner(header: View) -> Option<View>
+{
+    return DATA_GRID_STATE.with(|state| state.borrow().headers.get(&header).cloned());
+}
+
+// a press picks the cell's column, the list then selects the row
+fn handle_row_event(row: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { point, button: MouseButton::Left, .. } =>
+        {
+            if let Some((view, _)) = get_row(row)
+            {
+                let column = column_at(view, point.x, crate::view::get_rect(row).origin.x);
+                let previous = with_data_grid(view, |data_grid_object| std::mem::replace(&mut data_grid_object.selected_column, column)).flatten();
+
+                if let Some(column) = column
+                {
+                    scroll_to_column(view, column);
+                }
+
+                // a new column in the same row is not a list selection change
+                let row_selected = get_list(view).is_some_and(|list| list_view::get_selected_index(list) == get_row(row).map(|(_, index)| index));
+
+                if row_selected && previous != column
+                {
+                    notify_selection_changed(view);
+                }
+            }
+
+            return false;
+        },
+        _ => return false
+    }
+}
+
+// sideways scrolling and left and right arrive here after the list passed them up
+fn handle_event(view: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::Scroll { delta, .. } =>
+        {
+            let offset = get_horizontal_offset(view);
+            set_horizontal_offset(view, offset + delta.x);
+            return get_horizontal_offset(view) != offset;
+        },
+        Event::KeyDown { key: Key::Left, .. } | Event::KeyDown { key: Key::Right, .. } =>
+        {
+            let column_count = get_column_count(view);
+
+            if column_count == 0
+            {
+                return false;
+            }
+
+            let forward = matches!(event, Event::KeyDown { key: Key::Right, .. });
+
+            let column = with_data_grid(view, |data_grid_object| {
+                let column = match (data_grid_object.selected_column, forward)
+                {
+                    (Some(column), true) => (column + 1).min(column_count - 1),
+                    (Some(column), false) => column.saturating_sub(1),
+                    (None, _) => 0
+                };
+
+                let changed = data_grid_object.selected_column != Some(column);
+                data_grid_object.selected_column = Some(column);
+                return (column, changed);
+            });
+
+            if let Some((column, changed)) = column
+            {
+                scroll_to_column(view, column);
+
+                if changed && get_selected_cell(view).is_some()
+                {
+                    notify_selection_changed(view);
+                }
+            }
+
+            return true;
+        },
+        _ => return false
+    }
+}
+
+// dragging near a column's right edge resizes it, a click elsewhere sorts by the column
+fn handle_header_event(header: View, event: &Event) -> bool
+{
+    let view = match get_header_owner(header)
+    {
+        Some(view) => view,
+        None => return false
+    };
+
+    let rect = crate::view::get_rect(header);
+
+    match event
+    {
+        Event::MouseDown { point, button: MouseButton::Left, .. } =>
+        {
+            let spans = column_spans(view, rect.origin.x);
+
+            let grip = spans.iter().enumerate()
+                .rev()
+                .find(|(_, (left, width))| (point.x - (left + width)).abs() <= RESIZE_GRIP_WIDTH)
+                .map(|(column, (_, width))| ColumnResize { column: column, start_x: point.x, start_width: *width });
+
+            let column = column_at(view, point.x, rect.origin.x);
+
+            with_data_grid(view, |data_grid_object| {
+                data_grid_object.resize = grip;
+                data_grid_object.pressed_column = if grip.is_some() { None } else { column };
+            });
+
+            return true;
+        },
+        Event::MouseMove { point } =>
+        {
+            let resize = with_data_grid(view, |data_grid_object| data_grid_object.resize).flatten();
+
+            if let Some(resize) = resize
+            {
+                set_column_width(view, resize.column, resize.start_width + point.x - resize.start_x);
+                return true;
+            }
+
+            return false;
+        },
+        Event::MouseUp { point, button: MouseButton::Left, .. } =>
+        {
+            let pressed_column = with_data_grid(view, |data_grid_object| {
+                data_grid_object.resize = None;
+                return data_grid_object.pressed_column.take();
+            }).flatten();
+
+            if let Some(column) = pressed_column.filter(|column| column_at(view, point.x, rect.origin.x) == Some(*column))
+            {
+                if with_column(view, column, |column_object| column_object.sortable).unwrap_or(false)
+                {
+                    // the first click sorts ascending and later clicks flip the direction
+                    let direction = match get_sort(view)
+                    {
+                        Some((sorted_column, SortDirection::Ascending)) if sorted_column == column => SortDirection::Descending,
+                        _ => SortDirection::Ascending
+                    };
+
+                    sort_by(view, column, direction);
+                }
+            }
+
+            return true;
+        },
+        _ => return false
+    }
+}
+
+// draws the columns that scroll, then the frozen column over them
+fn draw_columns<F: FnMut(&mut DrawingContext, usize, Rect)>(view: View, context: &mut DrawingContext, rect: Rect, background: Color, mut draw_cell: F)
+{
+    let spans = column_spans(view, rect.origin.x);
+    let frozen = is_first_column_frozen(view) && !spans.is_empty();
+
+    context.push_clip(rect);
+
+    for (column, (left, width)) in spans.iter().enumerate()
+    {
+        if frozen && column == 0
+        {
+            continue;
+        }
+
+        if left + width < rect.origin.x || *left > rect.origin.x + rect.size.width
+        {
+            continue;
+        }
+
+        draw_cell(context, column, Rect { origin: Point { x: *left, y: rect.origin.y }, size: Size { width: *width, height: rect.size.height } });
+    }
+
+    if frozen
+    {
+        let (left, width) = spans[0];
+        let cell = Rect { origin: Point { x: left, y: rect.origin.y }, size: Size { width: width, height: rect.size.height } };
+
+        context.fill_rect(cell, background);
+        draw_cell(context, 0, cell);
+    }
+
+    context.pop_clip();
+}
+
+fn frozen_background(view: View) -> Color
+{
+    let background = crate::view::get_background_color(view);
+
+    return if background.a > 0.0 { background } else { DEFAULT_FROZEN_BACKGROUND };
+}
+
+fn draw_cell_text(view: View, context: &mut DrawingContext, cell: Rect, text: &str)
+{
+    let font = crate::view::get_font(view);
+    let color = crate::view::get_foreground_color(view);
+
+    let text_size = crate::drawing::font::measure_text(text, &font);
+    let origin = Point { x: cell.origin.x + CELL_PADDING, y: cell.origin.y + (cell.size.height - text_size.height) / 2.0 };
+
+    context.push_clip(Rect {
+        origin: cell.origin,
+        size: Size { width: (cell.size.width - CELL_PADDING).max(0.0), height: cell.size.height }
+    });
+    context.draw_text(origin, text, &font, color);
+    context.pop_clip();
+}
+
+fn render_row(row: View, context: &mut DrawingContext)
+{
+    let (view, index) = match get_row(row)
+    {
+        Some(row_properties) => row_properties,
+        None => return
+    };
+
+    let rect = crate::view::get_rect(row);
+    let color = crate::view::get_foreground_color(row);
+    let line_color = crate::widget::with_alpha(color, GRID_LINE_ALPHA);
+    let background = frozen_background(view);
+
+    let selected_column = get_selected_cell(view).filter(|(selected_row, _)| *selected_row == index).map(|(_, column)| column);
+
+    draw_columns(view, context, rect, background, |context, column, cell| {
+        let text = with_source(view, |source| source.cell_text(index, column)).unwrap_or(String::from(""));
+        draw_cell_text(row, context, cell, &text);
+
+        // the grid lines run along the right and bottom of every cell
+        context.fill_rect(Rect { origin: Point { x: cell.origin.x + cell.size.width - 1.0, y: cell.origin.y }, size: Size { width: 1.0, height: cell.size.height } }, line_color);
+        context.fill_rect(Rect { origin: Point { x: cell.origin.x, y: cell.origin.y + cell.size.height - 1.0 }, size: Size { width: cell.size.width, height: 1.0 } }, line_color);
+
+        if selected_column == Some(column)
+        {
+            context.stroke_rounded_rect(cell, 0.0, 1.5, crate::widget::with_alpha(color, SELECTED_CELL_ALPHA));
+        }
+    });
+}
+
+fn render_header(header: View, context: &mut DrawingContext)
+{
+    let view = match get_header_owner(header)
+    {
+        Some(view) => view,
+        None => return
+    };
+
+    let rect = crate::view::get_rect(header);
+    let color = crate::view::get_foreground_color(view);
+    let line_color = crate::widget::with_alpha(color, GRID_LINE_ALPHA);
+    let background = frozen_background(view);
+
+    let titles: Vec<String> = with_data_grid(view, |data_grid_object| {
+        data_grid_object.columns.iter().map(|column_object| column_object.title.clone()).collect()
+    }).unwrap_or(Vec::new());
+
+    let sort = get_sort(view);
+
+    draw_columns(view, context, rect, background, |context, column, cell| {
+        context.fill_rect(cell, crate::widget::with_alpha(color, HEADER_ALPHA));
+
+        let font = crate::view::get_font(view);
+        let text_width = crate::drawing::font::measure_run(&titles[column], &font);
+        draw_cell_text(view, context, cell, &titles[column]);
+
+        // the sort arrow follows the title, pointing up for ascending
+        if let Some((_, direction)) = sort.filter(|(sorted_column, _)| *sorted_column == column)
+        {
+            let center = Point {
+                x: (cell.origin.x + CELL_PADDING * 2.0 + text_width + SORT_ARROW_SIZE / 2.0).min(cell.origin.x + cell.size.width - CELL_PADDING - SORT_ARROW_SIZE / 2.0),
+                y: cell.origin.y + cell.size.height / 2.0
+            };
+            let half = SORT_ARROW_SIZE / 2.0;
+
+            let tip = if direction == SortDirection::Ascending { -half / 2.0 } else { half / 2.0 };
+
+            context.fill_polygon(&[
+                Point { x: center.x - half, y: center.y - tip },
+                Point { x: center.x + half, y: center.y - tip },
+                Point { x: center.x, y: center.y + tip }
+            ], color);
+        }
+
+        context.fill_rect(Rect { origin: Point { x: cell.origin.x + cell.size.width - 1.0, y: cell.origin.y }, size: Size { width: 1.0, height: cell.size.height } }, line_color);
+        context.fill_rect(Rect { origin: Point { x: cell.origin.x, y: cell.origin.y + cell.size.height - 1.0 }, size: Size { width: cell.size.width, height: 1.0 } }, line_color);
+    });
+}
@@ -0,0 +1,737 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  dialog.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains in-window modal dialogs and the alert,
+**                 confirm and prompt message boxes.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect, debug::*};
+
+pub use crate::{View, Window};
+
+use crate::drawing::{DrawingContext, FontDescriptor, FontWeight};
+use crate::event::{Event, Key, MouseButton};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// called with whether the message box was accepted and the text typed into a prompt
+type MessageBoxResultHandler = Box<dyn FnOnce(bool, String)>;
+
+// an open dialog, its content is centred on a backdrop that covers the window
+struct DialogObject
+{
+    pub window: Window,
+    pub backdrop: View,
+
+    // focus to hand back when the dialog closes
+    pub previous_focus: Option<View>
+}
+
+struct MessageBoxObject
+{
+    pub title: String,
+    pub message: String,
+
+    pub input: Option<View>,
+    pub buttons: Vec<View>,
+
+    pub result_handler: Option<MessageBoxResultHandler>
+}
+
+struct ButtonObject
+{
+    pub message_box: View,
+    pub title: String,
+
+    // the accepting button is drawn as the default and also runs on Enter
+    pub accepts: bool,
+    pub pressed: bool
+}
+
+struct DialogState
+{
+    pub dialogs: HashMap<View, DialogObject>,
+    pub backdrops: HashMap<View, View>,
+
+    pub message_boxes: HashMap<View, MessageBoxObject>,
+    pub buttons: HashMap<View, ButtonObject>,
+    pub inputs: HashMap<View, String>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static DIALOG_STATE: RefCell<DialogState> = RefCell::new(DialogState {
+        dialogs: HashMap::new(),
+        backdrops: HashMap::new(),
+        message_boxes: HashMap::new(),
+        buttons: HashMap::new(),
+        inputs: HashMap::new()
+    });
+}
+
+const BACKDROP_COLOR: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 0.35 };
+
+// message boxes keep this far from the window edges
+const WINDOW_MARGIN: f32 = 20.0;
+
+const MESSAGE_BOX_BACKGROUND: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+const MESSAGE_BOX_MIN_WIDTH: f32 = 300.0;
+const MESSAGE_BOX_MAX_WIDTH: f32 = 480.0;
+const MESSAGE_BOX_PADDING: f32 = 16.0;
+const MESSAGE_BOX_SPACING: f32 = 10.0;
+const TITLE_FONT_SIZE: f32 = 15.0;
+
+const BUTTON_HEIGHT: f32 = 28.0;
+const BUTTON_MIN_WIDTH: f32 = 80.0;
+const BUTTON_PADDING: f32 = 12.0;
+const BUTTON_SPACING: f32 = 8.0;
+const INPUT_HEIGHT: f32 = 26.0;
+const INPUT_PADDING: f32 = 6.0;
+const CORNER_RADIUS: f32 = 4.0;
+const BORDER_WIDTH: f32 = 1.0;
+
+// border and button fill colors are the foreground color at these alphas
+const BORDER_ALPHA: f32 = 0.3;
+const BUTTON_ALPHA: f32 = 0.08;
+const PRESSED_ALPHA: f32 = 0.2;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+// shows a view as a modal dialog at its requested size, centred over a backdrop that blocks the rest of the window
+pub fn show(window: Window, content: View)
+{
+    if is_open(content)
+    {
+        return;
+    }
+
+    // the content is moved into the backdrop, so it cannot be shown while it is part of another view tree
+    if crate::view::get_parent(content).is_some() || crate::view::get_window(content).is_some()
+    {
+        log(LogLevel::FAIL, &format!("Dialog content already has a parent!"));
+        return;
+    }
+
+    // menus and tooltips under a new dialog would be left stranded
+    crate::widget::menu::close(window);
+    crate::widget::tooltip::hide(window);
+
+    let backdrop = crate::view::create();
+
+    crate::view::set_render_handler(backdrop, render_backdrop);
+    crate::view::set_event_handler(backdrop, handle_backdrop_event);
+    crate::view::set_layout_handler(backdrop, layout_backdrop);
+    crate::view::add_destroy_handler(backdrop, forget_backdrop);
+    crate::view::add_child(backdrop, content);
+
+    let previous_focus = crate::window::get_focus(window);
+
+    DIALOG_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.dialogs.insert(content, DialogObject { window: window, backdrop: backdrop, previous_focus: previous_focus });
+        state.backdrops.insert(backdrop, content);
+    });
+
+    crate::window::add_modal_overlay(window, backdrop);
+
+    // the first focusable view in the dialog takes the keyboard
+    crate::window::set_focus(window, None);
+    crate::window::focus_next(window, false);
+}
+
+// closes a dialog, a message box closed this way reports that it was cancelled
+pub fn close(content: View)
+{
+    let result_handler = DIALOG_STATE.with(|state| {
+        return state.borrow_mut().message_boxes.get_mut(&content).and_then(|message_box_object| message_box_object.result_handler.take());
+    });
+
+    dismiss(content);
+
+    if let Some(result_handler) = result_handler
+    {
+        result_handler(false, String::from(""));
+    }
+}
+
+pub fn is_open(content: View) -> bool
+{
+    return DIALOG_STATE.with(|state| state.borrow().dialogs.contains_key(&content));
+}
+
+// a message with an OK button, the handler runs once it is dismissed
+pub fn alert<F: FnOnce() + 'static>(window: Window, title: &str, message: &str, on_closed: F) -> View
+{
+    return show_message_box(window, title, message, None, &["OK"], Box::new(move |_, _| on_closed()));
+}
+
+// a message with OK and Cancel buttons, the handler gets true for OK
+pub fn confirm<F: FnOnce(bool) + 'static>(window: Window, title: &str, message: &str, on_result: F) -> View
+{
+    return show_message_box(window, title, message, None, &["Cancel", "OK"], Box::new(move |accepted, _| on_result(accepted)));
+}
+
+// asks for a line of text, the handler gets None when cancelled
+pub fn prompt<F: FnOnce(Option<String>) + 'static>(window: Window, title: &str, message: &str, default_text: &str, on_result: F) -> View
+{
+    return show_message_box(window, title, message, Some(default_text), &["Cancel", "OK"], Box::new(move |accepted, text| {
+        on_result(if accepted { Some(text) } else { None });
+    }));
+}
+
+// the text typed so far into an open prompt
+pub fn get_prompt_text(message_box: View) -> Option<String>
+{
+    return DIALOG_STATE.with(|state| {
+        let state = state.borrow();
+
+        let input = state.message_boxes.get(&message_box)?.input?;
+        return state.inputs.get(&input).cloned();
+    });
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// takes a dialog off the window without reporting a result
+fn dismiss(content: View)
+{
+    let dialog_object = DIALOG_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let dialog_object = state.dialogs.remove(&content)?;
+        state.backdrops.remove(&dialog_object.backdrop);
+
+        return Some(dialog_object);
+    });
+
+    let dialog_object = match dialog_object
+    {
+        Some(dialog_object) => dialog_object,
+        None =>
+        {
+            log(LogLevel::FAIL, &format!("Dialog not found!"));
+            return;
+        }
+    };
+
+    let window = dialog_object.window;
+    let is_message_box = DIALOG_STATE.with(|state| state.borrow().message_boxes.contains_key(&content));
+
+    // focus goes back to where it was unless something outside the dialog, like another dialog, has it now
+    let focus_in_dialog = crate::window::get_focus(window).is_none_or(|focused_view| is_inside(focused_view, content));
+
+    crate::window::remove_overlay(window, dialog_object.backdrop);
+    crate::view::remove_child(dialog_object.backdrop, content);
+    crate::view::destroy(dialog_object.backdrop);
+
+    // message boxes belong to this module and are not shown again, their buttons and input go with them
+    if is_message_box
+    {
+        crate::view::destroy(content);
+    }
+
+    if focus_in_dialog
+    {
+        let previous_focus = dialog_object.previous_focus.filter(|previous_focus| crate::view::get_window(*previous_focus) == Some(window));
+        crate::window::set_focus(window, previous_focus);
+    }
+}
+
+fn forget_backdrop(backdrop: View)
+{
+    DIALOG_STATE.with(|state| state.borrow_mut().backdrops.remove(&backdrop));
+}
+
+fn forget_message_box(message_box: View)
+{
+    DIALOG_STATE.with(|state| state.borrow_mut().message_boxes.remove(&message_box));
+}
+
+fn forget_button(button: View)
+{
+    DIALOG_STATE.with(|state| state.borrow_mut().buttons.remove(&button));
+}
+
+fn forget_input(input: View)
+{
+    DIALOG_STATE.with(|state| state.borrow_mut().inputs.remove(&input));
+}
+
+fn is_inside(view: View, ancestor: View) -> bool
+{
+    let mut current = Some(view);
+
+    while let Some(candidate) = current
+    {
+        if candidate == ancestor
+        {
+            return true;
+        }
+
+        current = crate::view::get_parent(candidate);
+    }
+
+    return false;
+}
+
+fn show_message_box(window: Window, title: &str, message: &str, default_text: Option<&str>, button_titles: &[&str], result_handler: MessageBoxResultHandler) -> View
+{
+    let message_box = crate::view::create();
+
+    let input = default_text.map(|default_text| {
+        let input = crate::view::create();
+
+        crate::view::set_focusable(input, true);
+        crate::view::set_render_handler(input, render_input);
+        crate::view::set_event_handler(input, handle_input_event);
+        crate::view::add_destroy_handler(input, forget_input);
+        crate::view::add_child(message_box, input);
+
+        DIALOG_STATE.with(|state| state.borrow_mut().inputs.insert(input, String::from(default_text)));
+
+        input
+    });
+
+    // the last button accepts, the others cancel
+    let buttons: Vec<View> = button_titles.iter().enumerate().map(|(index, button_title)| {
+        let button = crate::view::create();
+
+        crate::view::set_focusable(button, true);
+        crate::view::set_render_handler(button, render_button);
+        crate::view::set_event_handler(button, handle_button_event);
+        crate::view::add_destroy_handler(button, forget_button);
+        crate::view::add_child(message_box, button);
+
+        DIALOG_STATE.with(|state| {
+            state.borrow_mut().buttons.insert(button, ButtonObject {
+                message_box: message_box,
+                title: String::from(*button_title),
+                accepts: index + 1 == button_titles.len(),
+                pressed: false
+            });
+        });
+
+        button
+    }).collect();
+
+    DIALOG_STATE.with(|state| {
+        state.borrow_mut().message_boxes.insert(message_box, MessageBoxObject {
+            title: String::from(title),
+            message: String::from(message),
+            input: input,
+            buttons: buttons.clone(),
+            result_handler: Some(result_handler)
+        });
+    });
+
+    crate::view::set_render_handler(message_box, render_message_box);
+    crate::view::set_event_handler(message_box, handle_message_box_event);
+    crate::view::set_layout_handler(message_box, layout_message_box);
+    crate::view::add_destroy_handler(message_box, forget_message_box);
+
+    let font = crate::view::get_font(message_box);
+    let max_width = MESSAGE_BOX_MAX_WIDTH.min(crate::window::get_size(window).width - WINDOW_MARGIN * 2.0);
+
+    let natural_width = crate::drawing::font::measure_text(title, &title_font(&font)).width
+        .max(message.lines().map(|line| crate::drawing::font::measure_text(line, &font).width).fold(0.0, f32::max));
+
+    let width = (natural_width + MESSAGE_BOX_PADDING * 2.0).clamp(MESSAGE_BOX_MIN_WIDTH, max_width.max(MESSAGE_BOX_MIN_WIDTH));
+    let height = message_box_height(message_box, width);
+
+    crate::view::set_size(message_box, Size { width: width.ceil(), height: height.ceil() });
+
+    show(window, message_box);
+
+    // a prompt starts in its text field, everything else on the accepting button
+    if let Some(focus_target) = input.or(buttons.last().copied())
+    {
+        crate::window::set_focus(window, Some(focus_target));
+    }
+
+    return message_box;
+}
+
+// reports the result to the handler and closes the message box
+fn finish(message_box: View, accepted: bool)
+{
+    let (result_handler, input) = match DIALOG_STATE.with(|state| {
+        state.borrow_mut().message_boxes.get_mut(&message_box).map(|message_box_object| (message_box_object.result_handler.take(), message_box_object.input))
+    })
+    {
+        Some(message_box_properties) => message_box_properties,
+        None => return
+    };
+
+    let text = input.and_then(|input| DIALOG_STATE.with(|state| state.borrow().inputs.get(&input).cloned())).unwrap_or(String::from(""));
+
+    dismiss(message_box);
+
+    if let Some(result_handler) = result_handler
+    {
+        result_handler(accepted, text);
+    }
+}
+
+fn title_font(font: &FontDescriptor) -> FontDescriptor
+{
+    return FontDescriptor { family: font.family.clone(), size: TITLE_FONT_SIZE, weight: FontWeight::Bold, italic: font.italic };
+}
+
+// greedy word wrap of each paragraph of the message
+fn wrap_text(text: &str, font: &FontDescriptor, width: f32) -> Vec<String>
+{
+    let mut lines: Vec<String> = Vec::new();
+
+    for paragraph in text.lines()
+    {
+        let mut line = String::from("");
+
+        for word in paragraph.split_whitespace()
+        {
+            let candidate = if line.is_empty() { String::from(word) } else { format!("{} {}", line, word) };
+
+            if !line.is_empty() && crate::drawing::font::measure_text(&candidate, font).width > width
+            {
+                lines.push(line);
+                line = String::from(word);
+            }
+            else
+            {
+                line = candidate;
+            }
+        }
+
+        lines.push(line);
+    }
+
+    return lines;
+}
+
+fn message_box_height(message_box: View, width: f32) -> f32
+{
+    let (title, message, has_input) = match DIALOG_STATE.with(|state| {
+        state.borrow().message_boxes.get(&message_box).map(|message_box_object| {
+            (message_box_object.title.clone(), message_box_object.message.clone(), message_box_object.input.is_some())
+        })
+    })
+    {
+        Some(message_box_properties) => message_box_properties,
+        None => return 0.0
+    };
+
+    let font = crate::view::get_font(message_box);
+    let line_height = crate::drawing::font::get_metrics(&font).line_height;
+
+    let mut height = MESSAGE_BOX_PADDING * 2.0 + BUTTON_HEIGHT + MESSAGE_BOX_SPACING * 2.0;
+
+    if !title.is_empty()
+    {
+        height += crate::drawing::font::get_metrics(&title_font(&font)).line_height + MESSAGE_BOX_SPACING;
+    }
+
+    height += wrap_text(&message, &font, width - MESSAGE_BOX_PADDING * 2.0).len() as f32 * line_height;
+
+    if has_input
+    {
+        height += MESSAGE_BOX_SPACING + INPUT_HEIGHT;
+    }
+
+    return height;
+}
+
+fn layout_backdrop(backdrop: View, rect: Rect)
+{
+    let content = match DIALOG_STATE.with(|state| state.borrow().backdrops.get(&backdrop).copied())
+    {
+        Some(content) => content,
+        None => return
+    };
+
+    let size = crate::view::get_size(content);
+    let width = size.width.min(rect.size.width);
+    let height = size.height.min(rect.size.height);
+
+    crate::view::layout_down(content, Rect {
+        origin: Point {
+            x: (rect.origin.x + (rect.size.width - width) / 2.0).round(),
+            y: (rect.origin.y + (rect.size.height - height) / 2.0).round()
+        },
+        size: Size { width: width, height: height }
+    });
+}
+
+// the input sits above the button row, which is right aligned along the bottom
+fn layout_message_box(message_box: View, rect: Rect)
+{
+    let (input, buttons) = match DIALOG_STATE.with(|state| {
+        state.borrow().message_boxes.get(&message_box).map(|message_box_object| (message_box_object.input, message_box_object.buttons.clone()))
+    })
+    {
+        Some(message_box_properties) => message_box_properties,
+        None => return
+    };
+
+    let font = crate::view::get_font(message_box);
+    let bottom = rect.origin.y + rect.size.height - MESSAGE_BOX_PADDING;
+
+    let mut x = rect.origin.x + rect.size.width - MESSAGE_BOX_PADDING;
+
+    for button in buttons.iter().rev()
+    {
+        let title = DIALOG_STATE.with(|state| state.borrow().buttons.get(button).map(|button_object| button_object.title.clone())).unwrap_or(String::from(""));
+        let width = (crate::drawing::font::measure_text(&title, &font).width + BUTTON_PADDING * 2.0).max(BUTTON_MIN_WIDTH).ceil();
+
+        x -= width;
+
+        crate::view::layout_down(*button, Rect {
+            origin: Point { x: x, y: bottom - BUTTON_HEIGHT },
+            size: Size { width: width, height: BUTTON_HEIGHT }
+        });
+
+        x -= BUTTON_SPACING;
+    }
+
+    if let Some(input) = input
+    {
+        crate::view::layout_down(input, Rect {
+            origin: Point { x: rect.origin.x + MESSAGE_BOX_PADDING, y: bottom - BUTTON_HEIGHT - MESSAGE_BOX_SPACING * 2.0 - INPUT_HEIGHT },
+            size: Size { width: rect.size.width - MESSAGE_BOX_PADDING * 2.0, height: INPUT_HEIGHT }
+        });
+    }
+}
+
+// the backdrop swallows every pointer event so nothing underneath it can be used
+fn handle_backdrop_event(_backdrop: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { .. } | Event::MouseUp { .. } | Event::MouseMove { .. } | Event::Scroll { .. } => return true,
+        _ => return false
+    }
+}
+
+fn handle_message_box_event(message_box: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::KeyDown { key: Key::Escape, .. } =>
+        {
+            finish(message_box, false);
+            return true;
+        },
+        Event::KeyDown { key: Key::Enter, .. } =>
+        {
+            finish(message_box, true);
+            return true;
+        },
+        Event::MouseDown { .. } | Event::MouseUp { .. } | Event::MouseMove { .. } | Event::Scroll { .. } => return true,
+        _ => return false
+    }
+}
+
+fn handle_button_event(button: View, event: &Event) -> bool
+{
+    let (message_box, accepts) = match DIALOG_STATE.with(|state| {
+        state.borrow().buttons.get(&button).map(|button_object| (button_object.message_box, button_object.accepts))
+    })
+    {
+        Some(button_properties) => button_properties,
+        None => return false
+    };
+
+    let set_pressed = |pressed: bool| {
+        DIALOG_STATE.with(|state| {
+            if let Some(button_object) = state.borrow_mut().buttons.get_mut(&button)
+            {
+                button_object.pressed = pressed;
+            }
+        });
+    };
+
+    match event
+    {
+        Event::MouseDown { button: MouseButton::Left, .. } =>
+        {
+            set_pressed(true);
+            return true;
+        },
+        Event::MouseUp { point, button: MouseButton::Left, .. } =>
+        {
+            set_pressed(false);
+
+            if crate::view::rect_contains(crate::view::get_rect(button), *point)
+            {
+                finish(message_box, accepts);
+            }
+
+            return true;
+        },
+        Event::KeyDown { key: Key::Enter | Key::Space, .. } =>
+        {
+            finish(message_box, accepts);
+            return true;
+        },
+        _ => return false
+    }
+}
+
+// a single line of text with the caret kept at the end
+fn handle_input_event(input: View, event: &Event) -> bool
+{
+    let edit = |edit: &dyn Fn(&mut String)| {
+        DIALOG_STATE.with(|state| {
+            if let Some(text) = state.borrow_mut().inputs.get_mut(&input)
+            {
+                edit(text);
+            }
+        });
+    };
+
+    match event
+    {
+        Event::KeyDown { key: Key::Character(character), modifiers } if !modifiers.control && !modifiers.meta =>
+        {
+            edit(&|text| text.push(*character));
+            return true;
+        },
+        Event::KeyDown { key: Key::Space, .. } =>
+        {
+            edit(&|text| text.push(' '));
+            return true;
+        },
+        Event::KeyDown { key: Key::Backspace, .. } =>
+        {
+            edit(&|text| { text.pop(); });
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn render_backdrop(backdrop: View, context: &mut DrawingContext)
+{
+    context.fill_rect(crate::view::get_rect(backdrop), BACKDROP_COLOR);
+}
+
+fn render_message_box(message_box: View, context: &mut DrawingContext)
+{
+    let (title, message) = match DIALOG_STATE.with(|state| {
+        state.borrow().message_boxes.get(&message_box).map(|message_box_object| (message_box_object.title.clone(), message_box_object.message.clone()))
+    })
+    {
+        Some(message_box_properties) => message_box_properties,
+        None => return
+    };
+
+    let rect = crate::view::get_rect(message_box);
+    let color = crate::view::get_foreground_color(message_box);
+    let font = crate::view::get_font(message_box);
+    let line_height = crate::drawing::font::get_metrics(&font).line_height;
+
+    context.fill_rounded_rect(rect, CORNER_RADIUS, MESSAGE_BOX_BACKGROUND);
+    context.stroke_rounded_rect(rect, CORNER_RADIUS, BORDER_WIDTH, crate::widget::with_alpha(color, BORDER_ALPHA));
+
+    let x = rect.origin.x + MESSAGE_BOX_PADDING;
+    let mut y = rect.origin.y + MESSAGE_BOX_PADDING;
+
+    context.push_clip(rect);
+
+    if !title.is_empty()
+    {
+        let title_font = title_font(&font);
+
+        context.draw_text(Point { x: x, y: y }, &title, &title_font, color);
+        y += crate::drawing::font::get_metrics(&title_font).line_height + MESSAGE_BOX_SPACING;
+    }
+
+    for line in wrap_text(&message, &font, rect.size.width - MESSAGE_BOX_PADDING * 2.0)
+    {
+        context.draw_text(Point { x: x, y: y }, &line, &font, color);
+        y += line_height;
+    }
+
+    context.pop_clip();
+}
+
+fn render_button(button: View, context: &mut DrawingContext)
+{
+    let (title, accepts, pressed) = match DIALOG_STATE.with(|state| {
+        state.borrow().buttons.get(&button).map(|button_object| (button_object.title.clone(), button_object.accepts, button_object.pressed))
+    })
+    {
+        Some(button_properties) => button_properties,
+        None => return
+    };
+
+    let rect = crate::view::get_rect(button);
+    let color = crate::view::get_foreground_color(button);
+    let font = crate::view::get_font(button);
+
+    crate::widget::draw_focus_ring(button, context, rect, CORNER_RADIUS);
+
+    context.fill_rounded_rect(rect, CORNER_RADIUS, crate::widget::with_alpha(color, if pressed { PRESSED_ALPHA } else { BUTTON_ALPHA }));
+    context.stroke_rounded_rect(rect, CORNER_RADIUS, if accepts { BORDER_WIDTH * 2.0 } else { BORDER_WIDTH }, crate::widget::with_alpha(color, BORDER_ALPHA * 2.0));
+
+    let text_width = crate::drawing::font::measure_text(&title, &font).width;
+    crate::widget::draw_view_text(button, context, rect.origin.x + (rect.size.width - text_width) / 2.0, &title);
+}
+
+fn render_input(input: View, context: &mut DrawingContext)
+{
+    let text = DIALOG_STATE.with(|state| state.borrow().inputs.get(&input).cloned()).unwrap_or(String::from(""));
+
+    let rect = crate::view::get_rect(input);
+    let color = crate::view::get_foreground_color(input);
+    let font = crate::view::get_font(input);
+    let metrics = crate::drawing::font::get_metrics(&font);
+
+    crate::widget::draw_focus_ring(input, context, rect, CORNER_RADIUS);
+    context.stroke_rounded_rect(rect, CORNER_RADIUS, BORDER_WIDTH, crate::widget::with_alpha(color, BORDER_ALPHA * 2.0));
+
+    let inner = Rect {
+        origin: Point { x: rect.origin.x + INPUT_PADDING, y: rect.origin.y },
+        size: Size { width: (rect.size.width - INPUT_PADDING * 2.0).max(0.0), height: rect.size.height }
+    };
+
+    // long text scrolls so the end with the caret stays in view
+    let text_width = crate::drawing::font::measure_text(&text, &font).width;
+    let text_x = inner.origin.x - (text_width - inner.size.width + 1.0).max(0.0);
+    let text_y = rect.origin.y + (rect.size.height - metrics.line_height) / 2.0;
+
+    context.push_clip(inner);
+    context.draw_text(Point { x: text_x, y: text_y }, &text, &font, color);
+
+    if crate::view::is_focused(input)
+    {
+        context.fill_rect(Rect {
+            origin: Point { x: (text_x + text_width).floor(), y: text_y },
+            size: Size { width: 1.0, height: metrics.line_height }
+        }, color);
+    }
+
+    context.pop_clip();
+}
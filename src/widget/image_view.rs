@@ -0,0 +1,214 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  image_view.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the ImageView widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect, debug::*};
+
+pub use crate::{Image, View};
+
+use crate::drawing::{DrawingContext, ImageSampling};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// how the image is sized inside the view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageScaling
+{
+    // fills the view, ignoring the image's aspect ratio
+    Stretch,
+
+    // as large as fits inside the view with the aspect ratio kept, centred
+    Fit,
+
+    // covers the whole view with the aspect ratio kept, the overflow is clipped
+    Fill,
+
+    // drawn at its own size in the middle of the view
+    Center
+}
+
+struct ImageViewObject
+{
+    pub image: Option<Image>,
+    pub scaling: ImageScaling,
+    pub sampling: ImageSampling
+}
+
+struct ImageViewState
+{
+    pub image_views: HashMap<View, ImageViewObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static IMAGE_VIEW_STATE: RefCell<ImageViewState> = RefCell::new(ImageViewState {
+        image_views: HashMap::new()
+    });
+}
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+
+    IMAGE_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.image_views.insert(new_view, ImageViewObject {
+            image: None,
+            scaling: ImageScaling::Fit,
+            sampling: ImageSampling::Bilinear
+        });
+    });
+
+    crate::view::set_render_handler(new_view, render);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+// a view that has no size yet takes the size of the image
+pub fn set_image(view: View, image: Option<Image>)
+{
+    if with_image_view(view, |image_view_object| image_view_object.image = image).is_none()
+    {
+        return;
+    }
+
+    if let Some(image) = image
+    {
+        let size = crate::view::get_size(view);
+
+        if size.width <= 0.0 && size.height <= 0.0
+        {
+            crate::view::set_size(view, crate::drawing::image::get_size(image));
+        }
+    }
+}
+
+pub fn get_image(view: View) -> Option<Image>
+{
+    return with_image_view(view, |image_view_object| image_view_object.image).flatten();
+}
+
+pub fn set_scaling(view: View, scaling: ImageScaling)
+{
+    with_image_view(view, |image_view_object| image_view_object.scaling = scaling);
+}
+
+pub fn get_scaling(view: View) -> ImageScaling
+{
+    return with_image_view(view, |image_view_object| image_view_object.scaling).unwrap_or(ImageScaling::Fit);
+}
+
+// nearest keeps pixel art sharp, bilinear smooths photos when they are scaled
+pub fn set_sampling(view: View, sampling: ImageSampling)
+{
+    with_image_view(view, |image_view_object| image_view_object.sampling = sampling);
+}
+
+pub fn get_sampling(view: View) -> ImageSampling
+{
+    return with_image_view(view, |image_view_object| image_view_object.sampling).unwrap_or(ImageSampling::Bilinear);
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    IMAGE_VIEW_STATE.with(|state| state.borrow_mut().image_views.remove(&view));
+}
+
+fn with_image_view<T, F: FnOnce(&mut ImageViewObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return IMAGE_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(image_view_object) = state.image_views.get_mut(&view)
+        {
+            return Some(action(image_view_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Image view not found!"));
+            return None;
+        }
+    });
+}
+
+// where the image lands for a scaling mode, may reach outside the view for fill and center
+fn image_rect(bounds: Rect, image_size: Size, scaling: ImageScaling) -> Rect
+{
+    let size = match scaling
+    {
+        ImageScaling::Stretch => return bounds,
+        ImageScaling::Center => image_size,
+        ImageScaling::Fit | ImageScaling::Fill =>
+        {
+            let scale_x = bounds.size.width / image_size.width;
+            let scale_y = bounds.size.height / image_size.height;
+
+            let scale = if scaling == ImageScaling::Fit { scale_x.min(scale_y) } else { scale_x.max(scale_y) };
+
+            Size { width: image_size.width * scale, height: image_size.height * scale }
+        }
+    };
+
+    return Rect {
+        origin: Point {
+            x: bounds.origin.x + (bounds.size.width - size.width) / 2.0,
+            y: bounds.origin.y + (bounds.size.height - size.height) / 2.0
+        },
+        size: size
+    };
+}
+
+fn render(view: View, context: &mut DrawingContext)
+{
+    let (image, scaling, sampling) = match with_image_view(view, |image_view_object| {
+        (image_view_object.image, image_view_object.scaling, image_view_object.sampling)
+    })
+    {
+        Some((Some(image), scaling, sampling)) => (image, scaling, sampling),
+        _ => return
+    };
+
+    let image_size = crate::drawing::image::get_size(image);
+
+    if image_size.width <= 0.0 || image_size.height <= 0.0
+    {
+        return;
+    }
+
+    let rect = crate::view::get_rect(view);
+
+    context.push_clip(rect);
+    context.draw_image(image_rect(rect, image_size, scaling), image, sampling);
+    context.pop_clip();
+}
@@ -0,0 +1,146 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  label.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the Label widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Point, debug::*};
+
+pub use crate::View;
+
+use crate::drawing::{DrawingContext, AttributedString};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+struct LabelObject
+{
+    pub text: AttributedString
+}
+
+struct LabelState
+{
+    pub labels: HashMap<View, LabelObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static LABEL_STATE: RefCell<LabelState> = RefCell::new(LabelState {
+        labels: HashMap::new()
+    });
+}
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+
+    LABEL_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.labels.insert(new_view, LabelObject {
+            text: AttributedString::new()
+        });
+    });
+
+    crate::view::set_render_handler(new_view, render);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+pub fn set_text(view: View, text: &str)
+{
+    set_attributed_text(view, AttributedString::from_text(text));
+}
+
+pub fn get_text(view: View) -> String
+{
+    return get_attributed_text(view).plain_text();
+}
+
+// runs without their own color, size, weight or style use the label's view properties
+pub fn set_attributed_text(view: View, text: AttributedString)
+{
+    LABEL_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(label_object) = state.labels.get_mut(&view)
+        {
+            label_object.text = text;
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Label not found!"));
+        }
+    });
+}
+
+pub fn get_attributed_text(view: View) -> AttributedString
+{
+    return LABEL_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(label_object) = state.labels.get(&view)
+        {
+            return label_object.text.clone();
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Label not found!"));
+            return AttributedString::new();
+        }
+    });
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    LABEL_STATE.with(|state| state.borrow_mut().labels.remove(&view));
+}
+
+fn render(view: View, context: &mut DrawingContext)
+{
+    let text = get_attributed_text(view);
+
+    if text.is_empty()
+    {
+        return;
+    }
+
+    let rect = crate::view::get_rect(view);
+    let font = crate::view::get_font(view);
+    let color = crate::view::get_foreground_color(view);
+
+    // text is vertically centred and clipped to the label
+    let text_size = crate::drawing::text::measure_attributed_text(&text, &font);
+    let origin = Point { x: rect.origin.x, y: rect.origin.y + (rect.size.height - text_size.height) / 2.0 };
+
+    context.push_clip(rect);
+    context.draw_attributed_text(origin, &text, &font, color);
+    context.pop_clip();
+}
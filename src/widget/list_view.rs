@@ -0,0 +1,850 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  list_view.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the virtualized ListView widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect, debug::*};
+
+pub use crate::View;
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, Modifiers, MouseButton};
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// creates the view for a row, given the list and the row index
+pub type ListRowBuilder = Box<dyn FnMut(View, usize) -> View>;
+
+// fills a row view that scrolled out of sight with the content of another index and returns the view to show
+pub type ListRowRecycler = Box<dyn FnMut(View, usize, View) -> View>;
+
+pub type ListRowHeightProvider = Box<dyn FnMut(usize) -> f32>;
+pub type ListSelectionChangedHandler = Box<dyn FnMut(View, &[usize])>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionMode
+{
+    None,
+    Single,
+    Multiple
+}
+
+struct ListViewObject
+{
+    pub item_count: usize,
+
+    pub row_builder: Option<ListRowBuilder>,
+    pub row_recycler: Option<ListRowRecycler>,
+
+    // rows share a fixed height unless a height provider is set, then the offsets hold the top of every row
+    pub row_height: f32,
+    pub row_height_provider: Option<ListRowHeightProvider>,
+    pub row_offsets: Vec<f32>,
+
+    pub scroll_offset: f32,
+
+    // only the visible rows have views, the rest wait in the pool to be reused
+    pub visible_rows: BTreeMap<usize, View>,
+    pub recycled_rows: Vec<View>,
+
+    pub selection_mode: SelectionMode,
+    pub selected_indices: BTreeSet<usize>,
+    pub anchor_index: Option<usize>,
+    pub cursor_index: Option<usize>,
+
+    pub selection_changed_handler: Option<ListSelectionChangedHandler>
+}
+
+struct ListViewState
+{
+    pub list_views: HashMap<View, ListViewObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static LIST_VIEW_STATE: RefCell<ListViewState> = RefCell::new(ListViewState {
+        list_views: HashMap::new()
+    });
+}
+
+const DEFAULT_LIST_VIEW_SIZE: Size = Size { width: 200.0, height: 240.0 };
+const DEFAULT_ROW_HEIGHT: f32 = 24.0;
+
+const SCROLLBAR_WIDTH: f32 = 8.0;
+const SCROLLBAR_INSET: f32 = 2.0;
+const MIN_SCROLLBAR_THUMB: f32 = 16.0;
+
+// selection and scrollbar colors are the foreground color at these alphas
+const SELECTION_ALPHA: f32 = 0.2;
+const CURSOR_ALPHA: f32 = 0.6;
+const SCROLLBAR_ALPHA: f32 = 0.35;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+
+    LIST_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.list_views.insert(new_view, ListViewObject {
+            item_count: 0,
+            row_builder: None,
+            row_recycler: None,
+            row_height: DEFAULT_ROW_HEIGHT,
+            row_height_provider: None,
+            row_offsets: Vec::new(),
+            scroll_offset: 0.0,
+            visible_rows: BTreeMap::new(),
+            recycled_rows: Vec::new(),
+            selection_mode: SelectionMode::Single,
+            selected_indices: BTreeSet::new(),
+            anchor_index: None,
+            cursor_index: None,
+            selection_changed_handler: None
+        });
+    });
+
+    crate::view::set_size(new_view, DEFAULT_LIST_VIEW_SIZE);
+    crate::view::set_focusable(new_view, true);
+    crate::view::set_clips_children(new_view, true);
+    crate::view::set_render_handler(new_view, render);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::set_layout_handler(new_view, layout);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+// changing the count rebuilds every visible row and drops selected rows past the end
+pub fn set_item_count(view: View, count: usize)
+{
+    with_list_view(view, |list_view_object| {
+        list_view_object.item_count = count;
+        list_view_object.selected_indices.retain(|index| *index < count);
+        list_view_object.anchor_index = list_view_object.anchor_index.filter(|index| *index < count);
+        list_view_object.cursor_index = list_view_object.cursor_index.filter(|index| *index < count);
+    });
+
+    reload(view);
+}
+
+pub fn get_item_count(view: View) -> usize
+{
+    return with_list_view(view, |list_view_object| list_view_object.item_count).unwrap_or(0);
+}
+
+// called to create a row view whenever no recycled row is available
+pub fn set_row_builder<F: FnMut(View, usize) -> View + 'static>(view: View, builder: F)
+{
+    with_list_view(view, |list_view_object| list_view_object.row_builder = Some(Box::new(builder)));
+
+    reload(view);
+}
+
+// without a recycler every row that comes into view is built from scratch and rows that leave it are destroyed
+pub fn set_row_recycler<F: FnMut(View, usize, View) -> View + 'static>(view: View, recycler: F)
+{
+    with_list_view(view, |list_view_object| list_view_object.row_recycler = Some(Box::new(recycler)));
+}
+
+// gives every row the same height and removes any height provider
+pub fn set_row_height(view: View, height: f32)
+{
+    with_list_view(view, |list_view_object| {
+        list_view_object.row_height = height.max(1.0);
+        list_view_object.row_height_provider = None;
+        list_view_object.row_offsets.clear();
+    });
+
+    relayout(view);
+}
+
+pub fn get_row_height(view: View) -> f32
+{
+    return with_list_view(view, |list_view_object| list_view_object.row_height).unwrap_or(DEFAULT_ROW_HEIGHT);
+}
+
+// asks for the height of each row instead of using a fixed height, heights are read again on reload
+pub fn set_row_height_provider<F: FnMut(usize) -> f32 + 'static>(view: View, provider: F)
+{
+    with_list_view(view, |list_view_object| list_view_object.row_height_provider = Some(Box::new(provider)));
+
+    reload(view);
+}
+
+// rebuilds the visible rows and reads the row heights again, for when the underlying data changed
+pub fn reload(view: View)
+{
+    update_row_offsets(view);
+
+    let released = with_list_view(view, |list_view_object| {
+        let rows: Vec<View> = list_view_object.visible_rows.values().cloned().collect();
+        list_view_object.visible_rows.clear();
+        let pooled = recycle_rows(list_view_object, &rows);
+        return (rows, pooled);
+    });
+
+    if let Some((rows, pooled)) = released
+    {
+        release_rows(view, rows, pooled);
+    }
+
+    relayout(view);
+}
+
+pub fn get_content_height(view: View) -> f32
+{
+    return with_list_view(view, |list_view_object| content_height(list_view_object)).unwrap_or(0.0);
+}
+
+pub fn set_scroll_offset(view: View, offset: f32)
+{
+    with_list_view(view, |list_view_object| list_view_object.scroll_offset = offset);
+
+    relayout(view);
+}
+
+pub fn get_scroll_offset(view: View) -> f32
+{
+    return with_list_view(view, |list_view_object| list_view_object.scroll_offset).unwrap_or(0.0);
+}
+
+// scrolls the least distance that brings the whole row into view
+pub fn scroll_to_index(view: View, index: usize)
+{
+    let viewport_height = crate::view::get_rect(view).size.height;
+
+    let changed = with_list_view(view, |list_view_object| {
+        if index >= list_view_object.item_count
+        {
+            return false;
+        }
+
+        let top = row_top(list_view_object, index);
+        let bottom = top + row_height(list_view_object, index);
+
+        let offset = if top < list_view_object.scroll_offset
+        {
+            top
+        }
+        else if bottom > list_view_object.scroll_offset + viewport_height
+        {
+            bottom - viewport_height
+        }
+        else
+        {
+            return false;
+        };
+
+        list_view_object.scroll_offset = offset;
+        return true;
+    }).unwrap_or(false);
+
+    if changed
+    {
+        relayout(view);
+    }
+}
+
+// returns the index of the row under a point in window coordinates
+pub fn index_at_point(view: View, point: Point) -> Option<usize>
+{
+    let rect = crate::view::get_rect(view);
+
+    if !crate::view::rect_contains(rect, point)
+    {
+        return None;
+    }
+
+    return with_list_view(view, |list_view_object| {
+        return index_at_offset(list_view_object, point.y - rect.origin.y + list_view_object.scroll_offset);
+    }).flatten();
+}
+
+// returns the view showing a row, rows that are scrolled out of view have none
+pub fn get_row_view(view: View, index: usize) -> Option<View>
+{
+    return with_list_view(view, |list_view_object| list_view_object.visible_rows.get(&index).cloned()).flatten();
+}
+
+pub fn set_selection_mode(view: View, mode: SelectionMode)
+{
+    with_list_view(view, |list_view_object| {
+        list_view_object.selection_mode = mode;
+
+        match mode
+        {
+            SelectionMode::None => list_view_object.selected_indices.clear(),
+            SelectionMode::Single =>
+            {
+                let first = list_view_object.selected_indices.iter().next().cloned();
+                list_view_object.selected_indices = first.into_iter().collect();
+            },
+            SelectionMode::Multiple => {}
+        }
+    });
+}
+
+pub fn get_selection_mode(view: View) -> SelectionMode
+{
+    return with_list_view(view, |list_view_object| list_view_object.selection_mode).unwrap_or(SelectionMode::None);
+}
+
+// selecting from code respects the selection mode but does not call the changed handler
+pub fn set_selected_indices(view: View, indices: &[usize])
+{
+    with_list_view(view, |list_view_object| {
+        let count = list_view_object.item_count;
+        let mut selected: BTreeSet<usize> = indices.iter().cloned().filter(|index| *index < count).collect();
+
+        match list_view_object.selection_mode
+        {
+            SelectionMode::None => selected.clear(),
+            SelectionMode::Single => selected = selected.into_iter().next().into_iter().collect(),
+            SelectionMode::Multiple => {}
+        }
+
+        list_view_object.anchor_index = selected.iter().next().cloned();
+        list_view_object.cursor_index = list_view_object.anchor_index.or(list_view_object.cursor_index);
+        list_view_object.selected_indices = selected;
+    });
+}
+
+pub fn get_selected_indices(view: View) -> Vec<usize>
+{
+    return with_list_view(view, |list_view_object| list_view_object.selected_indices.iter().cloned().collect()).unwrap_or(Vec::new());
+}
+
+pub fn get_selected_index(view: View) -> Option<usize>
+{
+    return with_list_view(view, |list_view_object| list_view_object.selected_indices.iter().next().cloned()).flatten();
+}
+
+pub fn is_index_selected(view: View, index: usize) -> bool
+{
+    return with_list_view(view, |list_view_object| list_view_object.selected_indices.contains(&index)).unwrap_or(false);
+}
+
+// the row the keyboard acts on, it follows clicks and arrow keys
+pub fn get_cursor_index(view: View) -> Option<usize>
+{
+    return with_list_view(view, |list_view_object| list_view_object.cursor_index).flatten();
+}
+
+// called whenever the user changes the selection by pointer or keyboard
+pub fn set_selection_changed_handler<F: FnMut(View, &[usize]) + 'static>(view: View, handler: F)
+{
+    with_list_view(view, |list_view_object| list_view_object.selection_changed_handler = Some(Box::new(handler)));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// visible rows are children and go with the list, pooled rows are not so they are destroyed here
+fn forget(view: View)
+{
+    let list_view_object = LIST_VIEW_STATE.with(|state| state.borrow_mut().list_views.remove(&view));
+
+    if let Some(list_view_object) = list_view_object
+    {
+        for row in list_view_object.recycled_rows
+        {
+            crate::view::destroy(row);
+        }
+    }
+}
+
+fn with_list_view<T, F: FnOnce(&mut ListViewObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return LIST_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(list_view_object) = state.list_views.get_mut(&view)
+        {
+            return Some(action(list_view_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("List view not found!"));
+            return None;
+        }
+    });
+}
+
+fn relayout(view: View)
+{
+    crate::view::layout_down(view, crate::view::get_rect(view));
+}
+
+// without a height provider there are no offsets and every row has the fixed row height
+fn update_row_offsets(view: View)
+{
+    let count = get_item_count(view);
+
+    let offsets = crate::widget::call_taken(|slot| with_list_view(view, |list_view_object| slot(&mut list_view_object.row_height_provider)), |provider| {
+        let mut offsets: Vec<f32> = Vec::with_capacity(count + 1);
+
+        let mut offset = 0.0;
+        offsets.push(offset);
+
+        for index in 0..count
+        {
+            offset += provider(index).max(0.0);
+            offsets.push(offset);
+        }
+
+        return offsets;
+    }).unwrap_or(Vec::new());
+
+    with_list_view(view, |list_view_object| list_view_object.row_offsets = offsets);
+}
+
+fn uses_row_offsets(list_view_object: &ListViewObject) -> bool
+{
+    return list_view_object.row_offsets.len() == list_view_object.item_count + 1;
+}
+
+fn row_top(list_view_object: &ListViewObject, index: usize) -> f32
+{
+    if uses_row_offsets(list_view_object)
+    {
+        return list_view_object.row_offsets[index];
+    }
+
+    return index as f32 * list_view_object.row_height;
+}
+
+fn row_height(list_view_object: &ListViewObject, index: usize) -> f32
+{
+    if uses_row_offsets(list_view_object)
+    {
+        return list_view_object.row_offsets[index + 1] - list_view_object.row_offsets[index];
+    }
+
+    return list_view_object.row_height;
+}
+
+fn content_height(list_view_object: &ListViewObject) -> f32
+{
+    return row_top(list_view_object, list_view_object.item_count);
+}
+
+// finds the row containing an offset from the top of the content
+fn index_at_offset(list_view_object: &ListViewObject, offset: f32) -> Option<usize>
+{
+    if offset < 0.0 || offset >= content_height(list_view_object)
+    {
+        return None;
+    }
+
+    if uses_row_offsets(list_view_object)
+    {
+        let index = list_view_object.row_offsets.partition_point(|top| *top <= offset);
+        return Some(index.saturating_sub(1).min(list_view_object.item_count - 1));
+    }
+
+    return Some(((offset / list_view_object.row_height) as usize).min(list_view_object.item_count - 1));
+}
+
+fn visible_range(list_view_object: &ListViewObject, viewport_height: f32) -> (usize, usize)
+{
+    let top = list_view_object.scroll_offset;
+
+    let first = match index_at_offset(list_view_object, top)
+    {
+        Some(first) => first,
+        None => return (0, 0)
+    };
+
+    let mut last = first;
+
+    while last < list_view_object.item_count && row_top(list_view_object, last) < top + viewport_height
+    {
+        last += 1;
+    }
+
+    return (first, last);
+}
+
+// materializes the rows in view, reusing the views of rows that scrolled out
+fn layout(view: View, rect: Rect)
+{
+    let rows_to_release = with_list_view(view, |list_view_object| {
+        let max_offset = (content_height(list_view_object) - rect.size.height).max(0.0);
+        list_view_object.scroll_offset = list_view_object.scroll_offset.clamp(0.0, max_offset);
+
+        let (first, last) = visible_range(list_view_object, rect.size.height);
+
+        let released: Vec<(usize, View)> = list_view_object.visible_rows.iter()
+            .filter(|(index, _)| **index < first || **index >= last)
+            .map(|(index, row)| (*index, *row))
+            .collect();
+
+        for (index, _) in released.iter()
+        {
+            list_view_object.visible_rows.remove(index);
+        }
+
+        let released: Vec<View> = released.into_iter().map(|(_, row)| row).collect();
+        let pooled = recycle_rows(list_view_object, &released);
+
+        return (released, pooled, first, last);
+    });
+
+    let (released, pooled, first, last) = match rows_to_release
+    {
+        Some(rows_to_release) => rows_to_release,
+        None => return
+    };
+
+    release_rows(view, released, pooled);
+
+    for index in first..last
+    {
+        if get_row_view(view, index).is_none()
+        {
+            bind_row(view, index);
+        }
+    }
+
+    let row_rects: Vec<(View, Rect)> = with_list_view(view, |list_view_object| {
+        let scrollable = content_height(list_view_object) > rect.size.height;
+        let width = if scrollable { (rect.size.width - SCROLLBAR_WIDTH).max(0.0) } else { rect.size.width };
+
+        return list_view_object.visible_rows.iter().map(|(index, row)| {
+            (*row, Rect {
+                origin: Point { x: rect.origin.x, y: rect.origin.y + row_top(list_view_object, *index) - list_view_object.scroll_offset },
+                size: Size { width: width, height: row_height(list_view_object, *index) }
+            })
+        }).collect();
+    }).unwrap_or(Vec::new());
+
+    for (row, row_rect) in row_rects
+    {
+        crate::view::layout_down(row, row_rect);
+    }
+}
+
+// rows are only pooled when there is a recycler to reuse them, returns whether they were
+fn recycle_rows(list_view_object: &mut ListViewObject, rows: &[View]) -> bool
+{
+    if list_view_object.row_recycler.is_some()
+    {
+        list_view_object.recycled_rows.extend(rows.iter().cloned());
+        return true;
+    }
+
+    return false;
+}
+
+// takes rows that left the view out of the list, destroying the ones nothing will reuse so scrolling does not leak views
+fn release_rows(view: View, rows: Vec<View>, pooled: bool)
+{
+    for row in rows
+    {
+        if pooled
+        {
+            crate::view::remove_child(view, row);
+        }
+        else
+        {
+            crate::view::destroy(row);
+        }
+    }
+}
+
+// the builder and recycler are taken out while they run so they can call back into this module
+fn bind_row(view: View, index: usize)
+{
+    let callbacks = with_list_view(view, |list_view_object| {
+        let recycled = if list_view_object.row_recycler.is_some() { list_view_object.recycled_rows.pop() } else { None };
+        return (recycled, list_view_object.row_builder.take(), list_view_object.row_recycler.take());
+    });
+
+    let (recycled, builder, recycler) = match callbacks
+    {
+        Some(callbacks) => callbacks,
+        None => return
+    };
+
+    let mut builder = builder;
+    let mut recycler = recycler;
+
+    let row = match (recycled, recycler.as_mut(), builder.as_mut())
+    {
+        (Some(recycled), Some(recycler), _) => Some(recycler(view, index, recycled)),
+        (_, _, Some(builder)) => Some(builder(view, index)),
+        _ => None
+    };
+
+    with_list_view(view, |list_view_object| {
+        if list_view_object.row_builder.is_none()
+        {
+            list_view_object.row_builder = builder;
+        }
+
+        if list_view_object.row_recycler.is_none()
+        {
+            list_view_object.row_recycler = recycler;
+        }
+
+        if let Some(row) = row
+        {
+            list_view_object.visible_rows.insert(index, row);
+        }
+
+        // a recycler may hand back a different view, the pool keeps the one it replaced
+        if let Some(recycled) = recycled
+        {
+            if row != Some(recycled)
+            {
+                list_view_object.recycled_rows.push(recycled);
+            }
+        }
+    });
+
+    if let Some(row) = row
+    {
+        if crate::view::get_parent(row) != Some(view)
+        {
+            crate::view::add_child(view, row);
+        }
+    }
+}
+
+fn notify_selection_changed(view: View)
+{
+    crate::widget::call_taken(|slot| with_list_view(view, |list_view_object| slot(&mut list_view_object.selection_changed_handler)), |handler| handler(view, &get_selected_indices(view)));
+}
+
+// shift extends the selection from the anchor and control toggles a row in multiple selection mode,
+// when only_cursor is set the cursor moves without changing the selection
+fn select_index(view: View, index: usize, modifiers: Modifiers, only_cursor: bool)
+{
+    let changed = with_list_view(view, |list_view_object| {
+        list_view_object.cursor_index = Some(index);
+
+        if only_cursor
+        {
+            return false;
+        }
+
+        let previous = list_view_object.selected_indices.clone();
+        let toggle = modifiers.control || modifiers.meta;
+
+        match list_view_object.selection_mode
+        {
+            SelectionMode::None => {},
+            SelectionMode::Single =>
+            {
+                list_view_object.selected_indices = [index].into_iter().collect();
+                list_view_object.anchor_index = Some(index);
+            },
+            SelectionMode::Multiple =>
+            {
+                if modifiers.shift
+                {
+                    let anchor = list_view_object.anchor_index.unwrap_or(index);
+                    let range = anchor.min(index)..=anchor.max(index);
+
+                    if !toggle
+                    {
+                        list_view_object.selected_indices.clear();
+                    }
+
+                    list_view_object.selected_indices.extend(range);
+                }
+                else if toggle
+                {
+                    if !list_view_object.selected_indices.remove(&index)
+                    {
+                        list_view_object.selected_indices.insert(index);
+                    }
+
+                    list_view_object.anchor_index = Some(index);
+                }
+                else
+                {
+                    list_view_object.selected_indices = [index].into_iter().collect();
+                    list_view_object.anchor_index = Some(index);
+                }
+            }
+        }
+
+        return previous != list_view_object.selected_indices;
+    }).unwrap_or(false);
+
+    if changed
+    {
+        notify_selection_changed(view);
+    }
+}
+
+// number of whole rows that fit in the view, used for paging
+fn rows_per_page(view: View) -> usize
+{
+    let viewport_height = crate::view::get_rect(view).size.height;
+
+    return with_list_view(view, |list_view_object| {
+        let cursor = list_view_object.cursor_index.unwrap_or(0);
+
+        if list_view_object.item_count == 0
+        {
+            return 1;
+        }
+
+        return ((viewport_height / row_height(list_view_object, cursor.min(list_view_object.item_count - 1)).max(1.0)) as usize).max(1);
+    }).unwrap_or(1);
+}
+
+fn handle_key(view: View, key: Key, modifiers: Modifiers) -> bool
+{
+    let count = get_item_count(view);
+
+    if count == 0
+    {
+        return false;
+    }
+
+    let cursor = get_cursor_index(view);
+    let multiple = get_selection_mode(view) == SelectionMode::Multiple;
+
+    // control moves the cursor alone in multiple selection mode so space can toggle rows
+    let only_cursor = multiple && (modifiers.control || modifiers.meta) && !modifiers.shift;
+
+    let target = match (key, cursor)
+    {
+        (Key::Space, Some(cursor)) =>
+        {
+            select_index(view, cursor, Modifiers { shift: false, ..modifiers }, false);
+            return true;
+        },
+        (Key::Down, Some(cursor)) => (cursor + 1).min(count - 1),
+        (Key::Up, Some(cursor)) => cursor.saturating_sub(1),
+        (Key::PageDown, Some(cursor)) => (cursor + rows_per_page(view)).min(count - 1),
+        (Key::PageUp, Some(cursor)) => cursor.saturating_sub(rows_per_page(view)),
+        (Key::Down, None) | (Key::PageDown, None) | (Key::Home, _) => 0,
+        (Key::Up, None) | (Key::PageUp, None) | (Key::End, _) => count - 1,
+        _ => return false
+    };
+
+    select_index(view, target, Modifiers { control: false, meta: false, ..modifiers }, only_cursor);
+    scroll_to_index(view, target);
+
+    return true;
+}
+
+fn handle_event(view: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { point, button: MouseButton::Left, modifiers } =>
+        {
+            if let Some(index) = index_at_point(view, *point)
+            {
+                select_index(view, index, *modifiers, false);
+            }
+
+            return true;
+        },
+        Event::Scroll { delta, .. } =>
+        {
+            let offset = get_scroll_offset(view);
+            set_scroll_offset(view, offset + delta.y);
+
+            // scrolling past either end is left to an enclosing scrollable view
+            return get_scroll_offset(view) != offset;
+        },
+        Event::KeyDown { key, modifiers } =>
+        {
+            return handle_key(view, *key, *modifiers);
+        },
+        _ => return false
+    }
+}
+
+// selection highlights are drawn behind the rows, the scrollbar sits in the gutter beside them
+fn render(view: View, context: &mut DrawingContext)
+{
+    let rect = crate::view::get_rect(view);
+    let color = crate::view::get_foreground_color(view);
+    let focused = crate::view::is_focused(view);
+
+    let list_properties = with_list_view(view, |list_view_object| {
+        let (first, last) = visible_range(list_view_object, rect.size.height);
+        let content_height = content_height(list_view_object);
+        let width = if content_height > rect.size.height { (rect.size.width - SCROLLBAR_WIDTH).max(0.0) } else { rect.size.width };
+
+        let row_rect = |index: usize| Rect {
+            origin: Point { x: rect.origin.x, y: rect.origin.y + row_top(list_view_object, index) - list_view_object.scroll_offset },
+            size: Size { width: width, height: row_height(list_view_object, index) }
+        };
+
+        let selected: Vec<Rect> = list_view_object.selected_indices.range(first..last.max(first)).map(|index| row_rect(*index)).collect();
+        let cursor = list_view_object.cursor_index.filter(|index| *index >= first && *index < last).map(row_rect);
+
+        return (selected, cursor, content_height, list_view_object.scroll_offset);
+    });
+
+    let (selected, cursor, content_height, scroll_offset) = match list_properties
+    {
+        Some(list_properties) => list_properties,
+        None => return
+    };
+
+    context.push_clip(rect);
+
+    for selected_rect in selected
+    {
+        context.fill_rect(selected_rect, crate::widget::with_alpha(color, SELECTION_ALPHA));
+    }
+
+    if focused
+    {
+        if let Some(cursor_rect) = cursor
+        {
+            context.stroke_rounded_rect(cursor_rect, 0.0, 1.0, crate::widget::with_alpha(color, CURSOR_ALPHA));
+        }
+    }
+
+    if content_height > rect.size.height && content_height > 0.0
+    {
+        let track_height = rect.size.height - 2.0 * SCROLLBAR_INSET;
+        let thumb_height = (track_height * rect.size.height / content_height).max(MIN_SCROLLBAR_THUMB).min(track_height);
+        let max_offset = content_height - rect.size.height;
+        let thumb_y = rect.origin.y + SCROLLBAR_INSET + (track_height - thumb_height) * (scroll_offset / max_offset);
+        let thumb_width = SCROLLBAR_WIDTH - 2.0 * SCROLLBAR_INSET;
+
+        context.fill_rounded_rect(Rect {
+            origin: Point { x: rect.origin.x + rect.size.width - SCROLLBAR_WIDTH + SCROLLBAR_INSET, y: thumb_y },
+            size: Size { width: thumb_width, height: thumb_height }
+        }, thumb_width / 2.0, crate::widget::with_alpha(color, SCROLLBAR_ALPHA));
+    }
+
+    context.pop_clip();
+}
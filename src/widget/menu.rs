@@ -0,0 +1,1088 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  menu.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the menu model, popup menus shown in
+**                 the window overlay layer and context menus.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect, debug::*};
+
+pub use crate::{View, Window};
+
+use crate::drawing::DrawingContext;
+use crate::drawing::font::FontDescriptor;
+use crate::event::{Event, Key, KeyChord, Modifiers, MouseButton};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+pub type Menu = usize;
+pub type MenuItem = usize;
+
+pub type MenuActionHandler = Box<dyn FnMut(MenuItem)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItemKind
+{
+    Action,
+    Separator,
+    Submenu(Menu)
+}
+
+struct MenuItemObject
+{
+    pub menu: Menu,
+    pub kind: MenuItemKind,
+    pub title: String,
+    pub enabled: bool,
+
+    // checkable items flip their checked state each time they are activated
+    pub checkable: bool,
+    pub checked: bool,
+
+    // a key chord that activates the item, shown next to its title
+    pub accelerator: Option<KeyChord>,
+    pub action: Option<MenuActionHandler>,
+
+    // items can run a registered command instead of, or as well as, their own action
+    pub command: Option<String>
+}
+
+struct MenuObject
+{
+    pub items: Vec<MenuItem>
+}
+
+// one open level of a popup menu, every level is its own overlay
+struct PopupObject
+{
+    pub menu: Menu,
+    pub window: Window,
+
+    pub parent: Option<View>,
+    pub child: Option<View>,
+
+    pub highlighted_index: Option<usize>,
+
+    // focus to hand back when the menu closes, only kept by the root level
+    pub previous_focus: Option<View>
+}
+
+struct MenuState
+{
+    pub menu_id_allocator: Menu,
+    pub item_id_allocator: MenuItem,
+
+    pub menus: HashMap<Menu, MenuObject>,
+    pub items: HashMap<MenuItem, MenuItemObject>,
+
+    pub popups: HashMap<View, PopupObject>,
+    pub free_popups: Vec<View>,
+
+    pub context_menus: HashMap<View, Menu>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static MENU_STATE: RefCell<MenuState> = RefCell::new(MenuState {
+        menu_id_allocator: 1,
+        item_id_allocator: 1,
+        menus: HashMap::new(),
+        items: HashMap::new(),
+        popups: HashMap::new(),
+        free_popups: Vec::new(),
+        context_menus: HashMap::new()
+    });
+}
+
+const ITEM_HEIGHT: f32 = 24.0;
+const SEPARATOR_HEIGHT: f32 = 9.0;
+const MENU_PADDING: f32 = 4.0;
+const MIN_MENU_WIDTH: f32 = 120.0;
+const BORDER_WIDTH: f32 = 1.0;
+
+// the check mark column leads every item and the submenu arrow column trails it
+const CHECK_WIDTH: f32 = 22.0;
+const CHECK_MARK_SIZE: f32 = 8.0;
+const ARROW_WIDTH: f32 = 18.0;
+const ARROW_SIZE: f32 = 8.0;
+const TEXT_PADDING: f32 = 8.0;
+const ACCELERATOR_SPACING: f32 = 24.0;
+
+// submenus overlap their parent slightly so the pointer can cross between them
+const SUBMENU_OVERLAP: f32 = 2.0;
+
+// border, separator and highlight colors are the foreground color at these alphas
+const BORDER_ALPHA: f32 = 0.5;
+const SEPARATOR_ALPHA: f32 = 0.25;
+const HIGHLIGHT_ALPHA: f32 = 0.15;
+const DISABLED_ALPHA: f32 = 0.4;
+
+const POPUP_BACKGROUND: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> Menu
+{
+    return MENU_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let new_menu = state.menu_id_allocator;
+        state.menu_id_allocator += 1;
+
+        state.menus.insert(new_menu, MenuObject { items: Vec::new() });
+
+        return new_menu;
+    });
+}
+
+pub fn add_item(menu: Menu, title: &str) -> Option<MenuItem>
+{
+    return insert_item(menu, MenuItemKind::Action, title);
+}
+
+pub fn add_separator(menu: Menu) -> Option<MenuItem>
+{
+    return insert_item(menu, MenuItemKind::Separator, "");
+}
+
+// the submenu opens beside the item when it is highlighted
+pub fn add_submenu(menu: Menu, title: &str, submenu: Menu) -> Option<MenuItem>
+{
+    if submenu == menu
+    {
+        log(LogLevel::FAIL, &format!("Menu cannot contain itself!"));
+        return None;
+    }
+
+    return insert_item(menu, MenuItemKind::Submenu(submenu), title);
+}
+
+pub fn remove_item(item: MenuItem)
+{
+    MENU_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(item_object) = state.items.remove(&item)
+        {
+            if let Some(menu_object) = state.menus.get_mut(&item_object.menu)
+            {
+                menu_object.items.retain(|menu_item| *menu_item != item);
+            }
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Menu item not found!"));
+        }
+    });
+}
+
+pub fn get_items(menu: Menu) -> Vec<MenuItem>
+{
+    return with_menu(menu, |menu_object| menu_object.items.clone()).unwrap_or(Vec::new());
+}
+
+pub fn get_item_kind(item: MenuItem) -> Option<MenuItemKind>
+{
+    return with_item(item, |item_object| item_object.kind);
+}
+
+pub fn get_item_menu(item: MenuItem) -> Option<Menu>
+{
+    return with_item(item, |item_object| item_object.menu);
+}
+
+pub fn set_item_title(item: MenuItem, title: &str)
+{
+    with_item(item, |item_object| item_object.title = String::from(title));
+}
+
+pub fn get_item_title(item: MenuItem) -> String
+{
+    return with_item(item, |item_object| item_object.title.clone()).unwrap_or(String::from(""));
+}
+
+// disabled items are drawn dimmed and cannot be activated
+pub fn set_item_enabled(item: MenuItem, enabled: bool)
+{
+    with_item(item, |item_object| item_object.enabled = enabled);
+}
+
+pub fn is_item_enabled(item: MenuItem) -> bool
+{
+    return with_item(item, |item_object| item_object.enabled).unwrap_or(false);
+}
+
+pub fn set_item_checkable(item: MenuItem, checkable: bool)
+{
+    with_item(item, |item_object| item_object.checkable = checkable);
+}
+
+pub fn is_item_checkable(item: MenuItem) -> bool
+{
+    return with_item(item, |item_object| item_object.checkable).unwrap_or(false);
+}
+
+pub fn set_item_checked(item: MenuItem, checked: bool)
+{
+    with_item(item, |item_object| item_object.checked = checked);
+}
+
+pub fn is_item_checked(item: MenuItem) -> bool
+{
+    return with_item(item, |item_object| item_object.checked).unwrap_or(false);
+}
+
+pub fn set_item_accelerator(item: MenuItem, accelerator: Option<KeyChord>)
+{
+    with_item(item, |item_object| item_object.accelerator = accelerator.map(|chord| KeyChord::new(chord.key, chord.modifiers)));
+}
+
+pub fn get_item_accelerator(item: MenuItem) -> Option<KeyChord>
+{
+    return with_item(item, |item_object| item_object.accelerator).flatten();
+}
+
+// called with the item whenever it is chosen from a menu or by its accelerator
+pub fn set_item_action<F: FnMut(MenuItem) + 'static>(item: MenuItem, handler: F)
+{
+    with_item(item, |item_object| item_object.action = Some(Box::new(handler)));
+}
+
+// the item is disabled while its command is, and shows the command's key chord when it has no accelerator
+pub fn set_item_command(item: MenuItem, command: Option<&str>)
+{
+    with_item(item, |item_object| item_object.command = command.map(String::from));
+}
+
+pub fn get_item_command(item: MenuItem) -> Option<String>
+{
+    return with_item(item, |item_object| item_object.command.clone()).flatten();
+}
+
+// toggles a checkable item then runs its action and command, returns false for disabled, separator and submenu items
+pub fn activate_item(item: MenuItem, window: Option<Window>) -> bool
+{
+    if !is_item_available(item)
+    {
+        return false;
+    }
+
+    let is_action = with_item(item, |item_object| {
+        if item_object.kind != MenuItemKind::Action
+        {
+            return false;
+        }
+
+        if item_object.checkable
+        {
+            item_object.checked = !item_object.checked;
+        }
+
+        return true;
+    }).unwrap_or(false);
+
+    if !is_action
+    {
+        return false;
+    }
+
+    crate::widget::call_taken(|slot| with_item(item, |item_object| slot(&mut item_object.action)), |action| action(item));
+
+    if let Some(command) = get_item_command(item)
+    {
+        crate::app::command::execute(&command, window);
+    }
+
+    return true;
+}
+
+// looks through the menu and its enabled submenus for an enabled item with a matching accelerator
+pub fn activate_accelerator(menu: Menu, key: Key, modifiers: Modifiers, window: Option<Window>) -> bool
+{
+    for item in get_items(menu)
+    {
+        let item_properties = with_item(item, |item_object| (item_object.kind, item_object.enabled, item_object.accelerator));
+
+        match item_properties
+        {
+            Some((MenuItemKind::Submenu(submenu), true, _)) if activate_accelerator(submenu, key, modifiers, window) =>
+            {
+                return true;
+            },
+            // an unavailable item leaves the chord to the items after it
+            Some((MenuItemKind::Action, _, Some(accelerator))) if accelerator.matches(key, modifiers) && is_item_available(item) =>
+            {
+                return activate_item(item, window);
+            },
+            _ => {}
+        }
+    }
+
+    return false;
+}
+
+// opens the menu with its top left corner at a point in window coordinates, closing any open menu first
+pub fn popup(menu: Menu, window: Window, point: Point)
+{
+    close(window);
+
+    let previous_focus = crate::window::get_focus(window);
+
+    if let Some(popup) = open_popup(menu, window, None, point, None)
+    {
+        with_popup(popup, |popup_object| popup_object.previous_focus = previous_focus);
+
+        // the open menu takes the keyboard until it closes
+        crate::window::set_focus(window, Some(popup));
+    }
+}
+
+// closes every open menu level in the window
+pub fn close(window: Window)
+{
+    let root_popups: Vec<View> = MENU_STATE.with(|state| {
+        return state.borrow().popups.iter()
+            .filter(|(_, popup_object)| popup_object.window == window && popup_object.parent.is_none())
+            .map(|(popup, _)| *popup)
+            .collect();
+    });
+
+    for root_popup in root_popups
+    {
+        close_popup(root_popup);
+    }
+}
+
+// the menu shown at the root level of the window's open popup, if any
+pub fn get_open_menu(window: Window) -> Option<Menu>
+{
+    return MENU_STATE.with(|state| {
+        return state.borrow().popups.values()
+            .find(|popup_object| popup_object.window == window && popup_object.parent.is_none())
+            .map(|popup_object| popup_object.menu);
+    });
+}
+
+// right clicks on the view or its descendants that are not otherwise handled open this menu
+pub fn set_context_menu(view: View, menu: Option<Menu>)
+{
+    MENU_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        match menu
+        {
+            Some(menu) => { state.context_menus.insert(view, menu); },
+            None => { state.context_menus.remove(&view); }
+        }
+    });
+
+    if menu.is_some()
+    {
+        crate::view::add_destroy_handler(view, forget_context_menu);
+    }
+}
+
+pub fn get_context_menu(view: View) -> Option<Menu>
+{
+    return MENU_STATE.with(|state| {
+        return state.borrow().context_menus.get(&view).copied();
+    });
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget_context_menu(view: View)
+{
+    MENU_STATE.with(|state| state.borrow_mut().context_menus.remove(&view));
+}
+
+fn with_menu<T, F: FnOnce(&mut MenuObject) -> T>(menu: Menu, action: F) -> Option<T>
+{
+    return MENU_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(menu_object) = state.menus.get_mut(&menu)
+        {
+            return Some(action(menu_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Menu not found!"));
+            return None;
+        }
+    });
+}
+
+fn with_item<T, F: FnOnce(&mut MenuItemObject) -> T>(item: MenuItem, action: F) -> Option<T>
+{
+    return MENU_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(item_object) = state.items.get_mut(&item)
+        {
+            return Some(action(item_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Menu item not found!"));
+            return None;
+        }
+    });
+}
+
+fn with_popup<T, F: FnOnce(&mut PopupObject) -> T>(popup: View, action: F) -> Option<T>
+{
+    return MENU_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(popup_object) = state.popups.get_mut(&popup)
+        {
+            return Some(action(popup_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Menu popup not found!"));
+            return None;
+        }
+    });
+}
+
+fn is_popup(view: View) -> bool
+{
+    return MENU_STATE.with(|state| state.borrow().popups.contains_key(&view));
+}
+
+// open or recycled popup views
+fn is_popup_view(view: View) -> bool
+{
+    return MENU_STATE.with(|state| {
+        let state = state.borrow();
+        return state.popups.contains_key(&view) || state.free_popups.contains(&view);
+    });
+}
+
+fn insert_item(menu: Menu, kind: MenuItemKind, title: &str) -> Option<MenuItem>
+{
+    return MENU_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let new_item = state.item_id_allocator;
+
+        if let Some(menu_object) = state.menus.get_mut(&menu)
+        {
+            menu_object.items.push(new_item);
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Menu not found!"));
+            return None;
+        }
+
+        state.item_id_allocator += 1;
+
+        state.items.insert(new_item, MenuItemObject {
+            menu: menu,
+            kind: kind,
+            title: String::from(title),
+            enabled: true,
+            checkable: false,
+            checked: false,
+            accelerator: None,
+            action: None,
+            command: None
+        });
+
+        return Some(new_item);
+    });
+}
+
+// enabled items whose command, if they have one, is enabled too
+fn is_item_available(item: MenuItem) -> bool
+{
+    let (enabled, command) = match with_item(item, |item_object| (item_object.enabled, item_object.command.clone()))
+    {
+        Some(item_properties) => item_properties,
+        None => return false
+    };
+
+    return enabled && command.is_none_or(|command| crate::app::command::is_enabled(&command));
+}
+
+// the item's own accelerator, or the chord bound to its command
+fn get_display_chord(item: MenuItem, window: Window) -> Option<KeyChord>
+{
+    let (accelerator, command) = with_item(item, |item_object| (item_object.accelerator, item_object.command.clone()))?;
+
+    return accelerator.or_else(|| command.and_then(|command| crate::app::command::get_display_chord(&command, Some(window))));
+}
+
+// separators and disabled items are skipped by the keyboard and the pointer
+fn is_selectable(item: MenuItem) -> bool
+{
+    return get_item_kind(item).is_some_and(|kind| kind != MenuItemKind::Separator) && is_item_available(item);
+}
+
+fn item_height(item: MenuItem) -> f32
+{
+    let is_separator = with_item(item, |item_object| item_object.kind == MenuItemKind::Separator).unwrap_or(false);
+    return if is_separator { SEPARATOR_HEIGHT } else { ITEM_HEIGHT };
+}
+
+fn measure_popup(menu: Menu, window: Window, font: &FontDescriptor) -> Size
+{
+    let mut width: f32 = MIN_MENU_WIDTH;
+    let mut height = MENU_PADDING * 2.0;
+
+    for item in get_items(menu)
+    {
+        height += item_height(item);
+
+        let title = get_item_title(item);
+
+        let accelerator_width = match get_display_chord(item, window)
+        {
+            Some(chord) => ACCELERATOR_SPACING + crate::drawing::font::measure_text(&chord.to_text(), font).width,
+            None => 0.0
+        };
+
+        width = width.max(CHECK_WIDTH + crate::drawing::font::measure_text(&title, font).width + accelerator_width + ARROW_WIDTH);
+    }
+
+    return Size { width: width.ceil(), height: height };
+}
+
+// rect of the item at an index, in window coordinates
+fn item_rect(popup_rect: Rect, items: &[MenuItem], index: usize) -> Rect
+{
+    let top: f32 = items[..index].iter().map(|item| item_height(*item)).sum();
+
+    return Rect {
+        origin: Point { x: popup_rect.origin.x, y: popup_rect.origin.y + MENU_PADDING + top },
+        size: Size { width: popup_rect.size.width, height: item_height(items[index]) }
+    };
+}
+
+fn item_at_point(popup: View, point: Point) -> Option<usize>
+{
+    let rect = crate::view::get_rect(popup);
+
+    if !crate::view::rect_contains(rect, point)
+    {
+        return None;
+    }
+
+    let items = match with_popup(popup, |popup_object| popup_object.menu)
+    {
+        Some(menu) => get_items(menu),
+        None => return None
+    };
+
+    let mut top = rect.origin.y + MENU_PADDING;
+
+    for (index, item) in items.iter().enumerate()
+    {
+        let height = item_height(*item);
+
+        if point.y >= top && point.y < top + height
+        {
+            return Some(index);
+        }
+
+        top += height;
+    }
+
+    return None;
+}
+
+// shows one menu level, flipping to the left of flip_x when it would run off the right edge
+fn open_popup(menu: Menu, window: Window, parent: Option<View>, origin: Point, flip_x: Option<f32>) -> Option<View>
+{
+    if get_items(menu).is_empty()
+    {
+        return None;
+    }
+
+    let recycled = MENU_STATE.with(|state| state.borrow_mut().free_popups.pop());
+
+    let popup = match recycled
+    {
+        Some(popup) => popup,
+        None =>
+        {
+            let popup = crate::view::create();
+
+            crate::view::set_background_color(popup, POPUP_BACKGROUND);
+            crate::view::set_focusable(popup, true);
+            crate::view::set_render_handler(popup, render_popup);
+            crate::view::set_event_handler(popup, handle_popup_event);
+
+            popup
+        }
+    };
+
+    let size = measure_popup(menu, window, &crate::view::get_font(popup));
+    let window_size = crate::window::get_size(window);
+
+    let mut x = origin.x;
+    let mut y = origin.y;
+
+    if x + size.width > window_size.width
+    {
+        x = match flip_x
+        {
+            Some(flip_x) => flip_x - size.width,
+            None => window_size.width - size.width
+        };
+    }
+
+    if y + size.height > window_size.height
+    {
+        y = window_size.height - size.height;
+    }
+
+    let rect = Rect { origin: Point { x: x.max(0.0), y: y.max(0.0) }, size: size };
+
+    MENU_STATE.with(|state| {
+        state.borrow_mut().popups.insert(popup, PopupObject {
+            menu: menu,
+            window: window,
+            parent: parent,
+            child: None,
+            highlighted_index: None,
+            previous_focus: None
+        });
+    });
+
+    if let Some(parent) = parent
+    {
+        with_popup(parent, |popup_object| popup_object.child = Some(popup));
+    }
+
+    crate::window::add_overlay(window, popup, rect, true);
+
+    return Some(popup);
+}
+
+// closes a menu level and everything opened from it, the root level hands focus back
+fn close_popup(popup: View)
+{
+    let popup_object = match MENU_STATE.with(|state| state.borrow_mut().popups.remove(&popup))
+    {
+        Some(popup_object) => popup_object,
+        None => return
+    };
+
+    if let Some(child) = popup_object.child
+    {
+        close_popup(child);
+    }
+
+    crate::window::remove_overlay(popup_object.window, popup);
+
+    MENU_STATE.with(|state| state.borrow_mut().free_popups.push(popup));
+
+    match popup_object.parent
+    {
+        Some(parent) =>
+        {
+            MENU_STATE.with(|state| {
+                if let Some(parent_object) = state.borrow_mut().popups.get_mut(&parent)
+                {
+                    parent_object.child = None;
+                }
+            });
+        },
+        None =>
+        {
+            // focus only goes back if nothing outside the menu took it while the menu was open
+            let focused_view = crate::window::get_focus(popup_object.window);
+
+            if focused_view.is_none_or(is_popup_view)
+            {
+                crate::window::set_focus(popup_object.window, popup_object.previous_focus);
+            }
+        }
+    }
+}
+
+fn close_child(popup: View)
+{
+    if let Some(child) = with_popup(popup, |popup_object| popup_object.child).flatten()
+    {
+        close_popup(child);
+    }
+}
+
+// opens the submenu of the item at an index beside its row, unless it is already open
+fn open_submenu(popup: View, index: usize) -> Option<View>
+{
+    let (menu, window, child) = with_popup(popup, |popup_object| (popup_object.menu, popup_object.window, popup_object.child))?;
+
+    let items = get_items(menu);
+    let item = *items.get(index)?;
+
+    let submenu = match with_item(item, |item_object| (item_object.kind, item_object.enabled))
+    {
+        Some((MenuItemKind::Submenu(submenu), true)) => submenu,
+        _ => return None
+    };
+
+    if let Some(child) = child
+    {
+        if with_popup(child, |popup_object| popup_object.menu) == Some(submenu)
+        {
+            return Some(child);
+        }
+
+        close_popup(child);
+    }
+
+    let popup_rect = crate::view::get_rect(popup);
+    let row_rect = item_rect(popup_rect, &items, index);
+
+    let origin = Point { x: popup_rect.origin.x + popup_rect.size.width - SUBMENU_OVERLAP, y: row_rect.origin.y - MENU_PADDING };
+
+    return open_popup(submenu, window, Some(popup), origin, Some(popup_rect.origin.x + SUBMENU_OVERLAP));
+}
+
+// the level the keyboard acts on is the deepest one with a highlighted item
+fn get_keyboard_popup(root: View) -> View
+{
+    let mut current = root;
+
+    loop
+    {
+        let child = with_popup(current, |popup_object| popup_object.child).flatten();
+
+        match child
+        {
+            Some(child) if with_popup(child, |popup_object| popup_object.highlighted_index.is_some()).unwrap_or(false) => current = child,
+            _ => return current
+        }
+    }
+}
+
+fn get_root_popup(popup: View) -> View
+{
+    let mut current = popup;
+
+    while let Some(parent) = with_popup(current, |popup_object| popup_object.parent).flatten()
+    {
+        current = parent;
+    }
+
+    return current;
+}
+
+// moves the highlight to the next selectable item in a direction, wrapping around
+fn step_highlight(popup: View, forward: bool)
+{
+    let (menu, highlighted_index) = match with_popup(popup, |popup_object| (popup_object.menu, popup_object.highlighted_index))
+    {
+        Some(popup_properties) => popup_properties,
+        None => return
+    };
+
+    let items = get_items(menu);
+    let count = items.len();
+
+    let start = match (highlighted_index, forward)
+    {
+        (Some(index), true) => index + 1,
+        (Some(index), false) => index + count - 1,
+        (None, true) => 0,
+        (None, false) => count - 1
+    };
+
+    let next = (0..count)
+        .map(|offset| if forward { (start + offset) % count } else { (start + count - offset) % count })
+        .find(|index| is_selectable(items[*index]));
+
+    if next.is_some() && next != highlighted_index
+    {
+        close_child(popup);
+        with_popup(popup, |popup_object| popup_object.highlighted_index = next);
+    }
+}
+
+fn set_highlight(popup: View, index: Option<usize>)
+{
+    with_popup(popup, |popup_object| popup_object.highlighted_index = index);
+}
+
+// chooses the item at an index, submenus open and highlight their first item instead
+fn choose(popup: View, index: usize)
+{
+    let (menu, window) = match with_popup(popup, |popup_object| (popup_object.menu, popup_object.window))
+    {
+        Some(popup_properties) => popup_properties,
+        None => return
+    };
+
+    let item = match get_items(menu).get(index)
+    {
+        Some(item) => *item,
+        None => return
+    };
+
+    if let Some(MenuItemKind::Submenu(_)) = get_item_kind(item)
+    {
+        if let Some(child) = open_submenu(popup, index)
+        {
+            set_highlight(child, None);
+            step_highlight(child, true);
+        }
+
+        return;
+    }
+
+    if !is_selectable(item)
+    {
+        return;
+    }
+
+    // the menu is gone before the action runs so the action can open another one
+    close(window);
+    activate_item(item, Some(window));
+}
+
+fn handle_popup_event(popup: View, event: &Event) -> bool
+{
+    if !is_popup(popup)
+    {
+        return false;
+    }
+
+    match event
+    {
+        Event::MouseMove { point } =>
+        {
+            let index = match item_at_point(popup, *point)
+            {
+                Some(index) => index,
+                None => return true
+            };
+
+            let menu = match with_popup(popup, |popup_object| popup_object.menu)
+            {
+                Some(menu) => menu,
+                None => return true
+            };
+
+            if !get_items(menu).get(index).is_some_and(|item| is_selectable(*item))
+            {
+                return true;
+            }
+
+            set_highlight(popup, Some(index));
+
+            // hovering a submenu opens it, hovering anything else closes the open one
+            if open_submenu(popup, index).is_none()
+            {
+                close_child(popup);
+            }
+
+            return true;
+        },
+        Event::MouseLeave =>
+        {
+            // the highlight stays on a submenu item while the pointer is over its submenu
+            if with_popup(popup, |popup_object| popup_object.child.is_none()).unwrap_or(false)
+            {
+                set_highlight(popup, None);
+            }
+
+            return true;
+        },
+        Event::MouseDown { .. } => return true,
+        Event::MouseUp { point, button: MouseButton::Left | MouseButton::Right, .. } =>
+        {
+            if let Some(index) = item_at_point(popup, *point)
+            {
+                choose(popup, index);
+            }
+
+            return true;
+        },
+        Event::KeyDown { key, .. } =>
+        {
+            let keyboard_popup = get_keyboard_popup(get_root_popup(popup));
+
+            let (highlighted_index, parent) = match with_popup(keyboard_popup, |popup_object| (popup_object.highlighted_index, popup_object.parent))
+            {
+                Some(popup_properties) => popup_properties,
+                None => return true
+            };
+
+            match key
+            {
+                Key::Down => step_highlight(keyboard_popup, true),
+                Key::Up => step_highlight(keyboard_popup, false),
+                Key::Home => { set_highlight(keyboard_popup, None); step_highlight(keyboard_popup, true); },
+                Key::End => { set_highlight(keyboard_popup, None); step_highlight(keyboard_popup, false); },
+                Key::Right =>
+                {
+                    if let Some(child) = highlighted_index.and_then(|index| open_submenu(keyboard_popup, index))
+                    {
+                        set_highlight(child, None);
+                        step_highlight(child, true);
+                    }
+                },
+                Key::Left if parent.is_some() => close_popup(keyboard_popup),
+                Key::Enter | Key::Space =>
+                {
+                    if let Some(index) = highlighted_index
+                    {
+                        choose(keyboard_popup, index);
+                    }
+                },
+                Key::Escape =>
+                {
+                    close_popup(if parent.is_some() { keyboard_popup } else { get_root_popup(popup) });
+                },
+                _ => {}
+            }
+
+            // an open menu keeps every key to itself
+            return true;
+        },
+        Event::Dismissed =>
+        {
+            close_popup(popup);
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn render_popup(popup: View, context: &mut DrawingContext)
+{
+    let (menu, window, highlighted_index) = match MENU_STATE.with(|state| {
+        state.borrow().popups.get(&popup).map(|popup_object| (popup_object.menu, popup_object.window, popup_object.highlighted_index))
+    })
+    {
+        Some(popup_properties) => popup_properties,
+        None => return
+    };
+
+    let items = get_items(menu);
+
+    let rect = crate::view::get_rect(popup);
+    let color = crate::view::get_foreground_color(popup);
+    let font = crate::view::get_font(popup);
+    let metrics = crate::drawing::font::get_metrics(&font);
+
+    context.push_clip(rect);
+
+    for (index, item) in items.iter().enumerate()
+    {
+        let row_rect = item_rect(rect, &items, index);
+
+        let (kind, title, checked) = match with_item(*item, |item_object| (item_object.kind, item_object.title.clone(), item_object.checked))
+        {
+            Some(item_properties) => item_properties,
+            None => continue
+        };
+
+        let enabled = is_item_available(*item);
+
+        if kind == MenuItemKind::Separator
+        {
+            context.fill_rect(Rect {
+                origin: Point { x: row_rect.origin.x + TEXT_PADDING, y: (row_rect.origin.y + SEPARATOR_HEIGHT / 2.0).floor() },
+                size: Size { width: row_rect.size.width - TEXT_PADDING * 2.0, height: 1.0 }
+            }, crate::widget::with_alpha(color, SEPARATOR_ALPHA));
+
+            continue;
+        }
+
+        if highlighted_index == Some(index)
+        {
+            context.fill_rect(row_rect, crate::widget::with_alpha(color, HIGHLIGHT_ALPHA));
+        }
+
+        let item_color = if enabled { color } else { crate::widget::with_alpha(color, DISABLED_ALPHA) };
+        let center_y = row_rect.origin.y + ITEM_HEIGHT / 2.0;
+
+        if checked
+        {
+            let check_x = row_rect.origin.x + (CHECK_WIDTH - CHECK_MARK_SIZE) / 2.0;
+
+            crate::widget::stroke_polyline(context, &[
+                Point { x: check_x, y: center_y },
+                Point { x: check_x + CHECK_MARK_SIZE * 0.35, y: center_y + CHECK_MARK_SIZE * 0.35 },
+                Point { x: check_x + CHECK_MARK_SIZE, y: center_y - CHECK_MARK_SIZE * 0.45 }
+            ], 1.5, item_color);
+        }
+
+        let text_y = row_rect.origin.y + (ITEM_HEIGHT - metrics.line_height) / 2.0;
+        context.draw_text(Point { x: row_rect.origin.x + CHECK_WIDTH, y: text_y }, &title, &font, item_color);
+
+        if let Some(chord) = get_display_chord(*item, window)
+        {
+            let text = chord.to_text();
+            let text_width = crate::drawing::font::measure_text(&text, &font).width;
+            let text_x = row_rect.origin.x + row_rect.size.width - ARROW_WIDTH - text_width;
+
+            context.draw_text(Point { x: text_x, y: text_y }, &text, &font, crate::widget::with_alpha(item_color, 0.7));
+        }
+
+        if let MenuItemKind::Submenu(_) = kind
+        {
+            // right pointing arrow in the trailing column
+            let arrow_x = row_rect.origin.x + row_rect.size.width - ARROW_WIDTH + (ARROW_WIDTH - ARROW_SIZE / 2.0) / 2.0;
+
+            context.fill_polygon(&[
+                Point { x: arrow_x, y: center_y - ARROW_SIZE / 2.0 },
+                Point { x: arrow_x + ARROW_SIZE / 2.0, y: center_y },
+                Point { x: arrow_x, y: center_y + ARROW_SIZE / 2.0 }
+            ], item_color);
+        }
+    }
+
+    context.pop_clip();
+
+    context.stroke_rounded_rect(rect, 0.0, BORDER_WIDTH, crate::widget::with_alpha(color, BORDER_ALPHA));
+}
+
+// opens the context menu of the nearest view up from the target that has one
+pub(crate) fn show_context_menu(window: Window, target: View, point: Point) -> bool
+{
+    let mut current = Some(target);
+
+    while let Some(view) = current
+    {
+        if let Some(menu) = get_context_menu(view)
+        {
+            popup(menu, window, point);
+            return true;
+        }
+
+        current = crate::view::get_parent(view);
+    }
+
+    return false;
+}
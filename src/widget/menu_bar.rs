@@ -0,0 +1,280 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  menu_bar.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the MenuBar widget, an in-window row of
+**                 menu titles that open popup menus.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect, debug::*};
+
+pub use crate::{View, Window};
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, Modifiers, MouseButton};
+use crate::widget::menu::{Menu, MenuItem, MenuItemKind};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+struct MenuBarObject
+{
+    // each item of this menu is one title on the bar
+    pub menu: Option<Menu>
+}
+
+struct MenuBarState
+{
+    pub menu_bars: HashMap<View, MenuBarObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static MENU_BAR_STATE: RefCell<MenuBarState> = RefCell::new(MenuBarState {
+        menu_bars: HashMap::new()
+    });
+}
+
+const DEFAULT_MENU_BAR_SIZE: Size = Size { width: 0.0, height: 26.0 };
+const TITLE_PADDING: f32 = 10.0;
+
+const HIGHLIGHT_ALPHA: f32 = 0.15;
+const DISABLED_ALPHA: f32 = 0.4;
+const SEPARATOR_ALPHA: f32 = 0.25;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+
+    MENU_BAR_STATE.with(|state| {
+        state.borrow_mut().menu_bars.insert(new_view, MenuBarObject { menu: None });
+    });
+
+    crate::view::set_size(new_view, DEFAULT_MENU_BAR_SIZE);
+    crate::view::set_render_handler(new_view, render);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+// accelerators anywhere in the menu work while the bar is in a window
+pub fn set_menu(view: View, menu: Option<Menu>)
+{
+    with_menu_bar(view, |menu_bar_object| menu_bar_object.menu = menu);
+}
+
+pub fn get_menu(view: View) -> Option<Menu>
+{
+    return with_menu_bar(view, |menu_bar_object| menu_bar_object.menu).flatten();
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    MENU_BAR_STATE.with(|state| state.borrow_mut().menu_bars.remove(&view));
+}
+
+fn with_menu_bar<T, F: FnOnce(&mut MenuBarObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return MENU_BAR_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(menu_bar_object) = state.menu_bars.get_mut(&view)
+        {
+            return Some(action(menu_bar_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Menu bar not found!"));
+            return None;
+        }
+    });
+}
+
+// the bar's items with the rect of each title, in window coordinates
+fn title_rects(view: View) -> Vec<(MenuItem, Rect)>
+{
+    let menu = match get_menu(view)
+    {
+        Some(menu) => menu,
+        None => return Vec::new()
+    };
+
+    let rect = crate::view::get_rect(view);
+    let font = crate::view::get_font(view);
+
+    let mut x = rect.origin.x;
+
+    return crate::widget::menu::get_items(menu).into_iter().map(|item| {
+        let width = match crate::widget::menu::get_item_kind(item)
+        {
+            Some(MenuItemKind::Separator) => TITLE_PADDING,
+            _ => crate::drawing::font::measure_text(&crate::widget::menu::get_item_title(item), &font).width + TITLE_PADDING * 2.0
+        };
+
+        let title_rect = Rect { origin: Point { x: x, y: rect.origin.y }, size: Size { width: width, height: rect.size.height } };
+        x += width;
+
+        (item, title_rect)
+    }).collect();
+}
+
+fn title_at_point(view: View, point: Point) -> Option<(MenuItem, Rect)>
+{
+    return title_rects(view).into_iter().find(|(_, title_rect)| crate::view::rect_contains(*title_rect, point));
+}
+
+// drops a title's submenu down from under it
+fn open_title(view: View, item: MenuItem, title_rect: Rect)
+{
+    let window = match crate::view::get_window(view)
+    {
+        Some(window) => window,
+        None => return
+    };
+
+    if !crate::widget::menu::is_item_enabled(item)
+    {
+        return;
+    }
+
+    match crate::widget::menu::get_item_kind(item)
+    {
+        Some(MenuItemKind::Submenu(submenu)) =>
+        {
+            let origin = Point { x: title_rect.origin.x, y: title_rect.origin.y + title_rect.size.height };
+            crate::widget::menu::popup(submenu, window, origin);
+        },
+        Some(MenuItemKind::Action) =>
+        {
+            crate::widget::menu::close(window);
+            crate::widget::menu::activate_item(item, Some(window));
+        },
+        _ => {}
+    }
+}
+
+// the bar item whose submenu is open in the window
+fn get_open_item(view: View) -> Option<MenuItem>
+{
+    let open_menu = crate::view::get_window(view).and_then(crate::widget::menu::get_open_menu)?;
+
+    return title_rects(view).into_iter()
+        .map(|(item, _)| item)
+        .find(|item| crate::widget::menu::get_item_kind(*item) == Some(MenuItemKind::Submenu(open_menu)));
+}
+
+fn handle_event(view: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { point, button: MouseButton::Left, .. } =>
+        {
+            if let Some((item, title_rect)) = title_at_point(view, *point)
+            {
+                open_title(view, item, title_rect);
+            }
+
+            return true;
+        },
+        Event::MouseMove { point } =>
+        {
+            // while one of the bar's menus is open, pointing at another title switches to it
+            let open_item = match get_open_item(view)
+            {
+                Some(open_item) => open_item,
+                None => return false
+            };
+
+            if let Some((item, title_rect)) = title_at_point(view, *point)
+            {
+                let is_submenu = matches!(crate::widget::menu::get_item_kind(item), Some(MenuItemKind::Submenu(_)));
+
+                if item != open_item && is_submenu
+                {
+                    open_title(view, item, title_rect);
+                }
+            }
+
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn render(view: View, context: &mut DrawingContext)
+{
+    let rect = crate::view::get_rect(view);
+    let color = crate::view::get_foreground_color(view);
+    let font = crate::view::get_font(view);
+    let metrics = crate::drawing::font::get_metrics(&font);
+
+    let open_item = get_open_item(view);
+
+    context.push_clip(rect);
+
+    for (item, title_rect) in title_rects(view)
+    {
+        if crate::widget::menu::get_item_kind(item) == Some(MenuItemKind::Separator)
+        {
+            context.fill_rect(Rect {
+                origin: Point { x: (title_rect.origin.x + title_rect.size.width / 2.0).floor(), y: title_rect.origin.y + 4.0 },
+                size: Size { width: 1.0, height: title_rect.size.height - 8.0 }
+            }, crate::widget::with_alpha(color, SEPARATOR_ALPHA));
+
+            continue;
+        }
+
+        if open_item == Some(item)
+        {
+            context.fill_rect(title_rect, crate::widget::with_alpha(color, HIGHLIGHT_ALPHA));
+        }
+
+        let title_color = if crate::widget::menu::is_item_enabled(item) { color } else { crate::widget::with_alpha(color, DISABLED_ALPHA) };
+        let origin = Point { x: title_rect.origin.x + TITLE_PADDING, y: title_rect.origin.y + (title_rect.size.height - metrics.line_height) / 2.0 };
+
+        context.draw_text(origin, &crate::widget::menu::get_item_title(item), &font, title_color);
+    }
+
+    context.pop_clip();
+}
+
+// runs the first matching accelerator in the menus of the bars in a window
+pub(crate) fn handle_accelerator(window: Window, key: Key, modifiers: Modifiers) -> bool
+{
+    let menus: Vec<Menu> = MENU_BAR_STATE.with(|state| {
+        return state.borrow().menu_bars.iter()
+            .filter_map(|(view, menu_bar_object)| menu_bar_object.menu.map(|menu| (*view, menu)))
+            .collect::<Vec<(View, Menu)>>();
+    }).into_iter()
+        .filter(|(view, _)| crate::view::get_window(*view) == Some(window))
+        .map(|(_, menu)| menu)
+        .collect();
+
+    return menus.into_iter().any(|menu| crate::widget::menu::activate_accelerator(menu, key, modifiers, Some(window)));
+}
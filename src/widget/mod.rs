@@ -0,0 +1,203 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  mod.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the standard NanoUI widgets, each built
+**                 on top of a regular view.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect};
+
+pub use crate::View;
+
+use crate::drawing::{DrawingContext, FillRule};
+
+/***************************************************************
+* MARK: CHILD MODULES
+***************************************************************/
+
+pub mod label;
+pub mod checkbox;
+pub mod radio_button;
+pub mod toggle;
+pub mod slider;
+pub mod progress_bar;
+pub mod combo_box;
+pub mod list_view;
+pub mod tree_view;
+pub mod data_grid;
+pub mod tab_view;
+pub mod split_view;
+pub mod menu;
+pub mod menu_bar;
+pub mod tooltip;
+pub mod dialog;
+pub mod image_view;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation
+{
+    Horizontal,
+    Vertical
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+// gap between an indicator such as a check box and its text
+pub(crate) const INDICATOR_SPACING: f32 = 6.0;
+
+pub(crate) const FOCUS_RING_WIDTH: f32 = 2.0;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// calls a handler kept in a widget's state, where with_slot hands the handler's field to the function it is given,
+// the handler is taken out while it runs so it can call back into the widget's module, and put back unless the call replaced it
+pub(crate) fn call_taken<H, T, R>(with_slot: impl Fn(&mut dyn FnMut(&mut Option<H>)) -> R, call: impl FnOnce(&mut H) -> T) -> Option<T>
+{
+    let mut handler = None;
+    with_slot(&mut |slot| handler = slot.take());
+
+    let mut handler = handler?;
+    let result = call(&mut handler);
+
+    let mut handler = Some(handler);
+
+    with_slot(&mut |slot| {
+        if slot.is_none()
+        {
+            *slot = handler.take();
+        }
+    });
+
+    return Some(result);
+}
+
+// draws a single line of the view's text at x, vertically centred in the view
+pub(crate) fn draw_view_text(view: View, context: &mut DrawingContext, x: f32, text: &str)
+{
+    if text.is_empty()
+    {
+        return;
+    }
+
+    let rect = crate::view::get_rect(view);
+    let font = crate::view::get_font(view);
+    let color = crate::view::get_foreground_color(view);
+
+    let text_size = crate::drawing::font::measure_text(text, &font);
+    let origin = Point { x: x, y: rect.origin.y + (rect.size.height - text_size.height) / 2.0 };
+
+    context.push_clip(rect);
+    context.draw_text(origin, text, &font, color);
+    context.pop_clip();
+}
+
+// outlines the indicator of a focused widget
+pub(crate) fn draw_focus_ring(view: View, context: &mut DrawingContext, rect: Rect, radius: f32)
+{
+    if !crate::view::is_focused(view)
+    {
+        return;
+    }
+
+    let color = with_alpha(crate::view::get_foreground_color(view), 0.4);
+
+    let ring = Rect {
+        origin: Point { x: rect.origin.x - FOCUS_RING_WIDTH * 1.5, y: rect.origin.y - FOCUS_RING_WIDTH * 1.5 },
+        size: Size { width: rect.size.width + FOCUS_RING_WIDTH * 3.0, height: rect.size.height + FOCUS_RING_WIDTH * 3.0 }
+    };
+
+    context.stroke_rounded_rect(ring, radius + FOCUS_RING_WIDTH * 1.5, FOCUS_RING_WIDTH, color);
+}
+
+// fills a line of connected segments with the given width
+pub(crate) fn stroke_polyline(context: &mut DrawingContext, points: &[Point], width: f32, color: Color)
+{
+    if points.len() < 2
+    {
+        return;
+    }
+
+    let half_width = width / 2.0;
+
+    let mut bounds = Rect { origin: points[0], size: Size { width: 0.0, height: 0.0 } };
+
+    for point in points.iter()
+    {
+        bounds = union_rect(bounds, Rect {
+            origin: Point { x: point.x - half_width, y: point.y - half_width },
+            size: Size { width: width, height: width }
+        });
+    }
+
+    context.fill_shape(bounds, FillRule::NonZero, color, |rasterizer| {
+        // every segment is a quad wound the same way so the overlaps merge
+        for segment in points.windows(2)
+        {
+            let (from, to) = (segment[0], segment[1]);
+
+            let length = ((to.x - from.x).powi(2) + (to.y - from.y).powi(2)).sqrt();
+
+            if length <= 0.0
+            {
+                continue;
+            }
+
+            let normal = Point { x: -(to.y - from.y) / length * half_width, y: (to.x - from.x) / length * half_width };
+
+            rasterizer.move_to(Point { x: from.x + normal.x, y: from.y + normal.y });
+            rasterizer.line_to(Point { x: to.x + normal.x, y: to.y + normal.y });
+            rasterizer.line_to(Point { x: to.x - normal.x, y: to.y - normal.y });
+            rasterizer.line_to(Point { x: from.x - normal.x, y: from.y - normal.y });
+            rasterizer.close();
+        }
+    });
+}
+
+// square indicator rect at the leading edge of a widget, vertically centred
+pub(crate) fn indicator_rect(view: View, size: Size) -> Rect
+{
+    let rect = crate::view::get_rect(view);
+
+    return Rect {
+        origin: Point { x: rect.origin.x + FOCUS_RING_WIDTH * 2.0, y: rect.origin.y + (rect.size.height - size.height) / 2.0 },
+        size: size
+    };
+}
+
+pub(crate) fn with_alpha(color: Color, alpha: f32) -> Color
+{
+    return Color { r: color.r, g: color.g, b: color.b, a: color.a * alpha };
+}
+
+pub(crate) fn union_rect(a: Rect, b: Rect) -> Rect
+{
+    let left = a.origin.x.min(b.origin.x);
+    let top = a.origin.y.min(b.origin.y);
+    let right = (a.origin.x + a.size.width).max(b.origin.x + b.size.width);
+    let bottom = (a.origin.y + a.size.height).max(b.origin.y + b.size.height);
+
+    return Rect { origin: Point { x: left, y: top }, size: Size { width: right - left, height: bottom - top } };
+}
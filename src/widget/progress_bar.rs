@@ -0,0 +1,195 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  progress_bar.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the ProgressBar widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect, debug::*};
+
+pub use crate::View;
+
+use crate::drawing::DrawingContext;
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::time::Instant;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+struct ProgressBarObject
+{
+    pub progress: f32,
+    pub indeterminate: bool,
+
+    // the indeterminate animation is timed from when it was started
+    pub animation_start: Instant
+}
+
+struct ProgressBarState
+{
+    pub progress_bars: HashMap<View, ProgressBarObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static PROGRESS_BAR_STATE: RefCell<ProgressBarState> = RefCell::new(ProgressBarState {
+        progress_bars: HashMap::new()
+    });
+}
+
+const DEFAULT_PROGRESS_BAR_SIZE: Size = Size { width: 160.0, height: 8.0 };
+
+// alpha of the foreground color used for the unfilled track
+const TRACK_ALPHA: f32 = 0.3;
+
+// the indeterminate indicator is this fraction of the track and sweeps across it once per period
+const INDETERMINATE_WIDTH: f32 = 0.3;
+const INDETERMINATE_PERIOD: f32 = 1.5;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+
+    PROGRESS_BAR_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.progress_bars.insert(new_view, ProgressBarObject {
+            progress: 0.0,
+            indeterminate: false,
+            animation_start: crate::app::clock::now()
+        });
+    });
+
+    crate::view::set_size(new_view, DEFAULT_PROGRESS_BAR_SIZE);
+    crate::view::set_render_handler(new_view, render);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+// progress runs from 0.0 to 1.0
+pub fn set_progress(view: View, progress: f32)
+{
+    with_progress_bar(view, |progress_bar_object| progress_bar_object.progress = progress.clamp(0.0, 1.0));
+}
+
+pub fn get_progress(view: View) -> f32
+{
+    return with_progress_bar(view, |progress_bar_object| progress_bar_object.progress).unwrap_or(0.0);
+}
+
+// an indeterminate bar animates continuously instead of showing its progress
+pub fn set_indeterminate(view: View, indeterminate: bool)
+{
+    with_progress_bar(view, |progress_bar_object| {
+        if indeterminate && !progress_bar_object.indeterminate
+        {
+            progress_bar_object.animation_start = crate::app::clock::now();
+        }
+
+        progress_bar_object.indeterminate = indeterminate;
+    });
+
+    // the first frame starts the animation, each frame after it asks for the next
+    if let Some(window) = crate::view::get_window(view)
+    {
+        crate::window::request_redraw(window);
+    }
+}
+
+pub fn is_indeterminate(view: View) -> bool
+{
+    return with_progress_bar(view, |progress_bar_object| progress_bar_object.indeterminate).unwrap_or(false);
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    PROGRESS_BAR_STATE.with(|state| state.borrow_mut().progress_bars.remove(&view));
+}
+
+fn with_progress_bar<T, F: FnOnce(&mut ProgressBarObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return PROGRESS_BAR_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(progress_bar_object) = state.progress_bars.get_mut(&view)
+        {
+            return Some(action(progress_bar_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Progress bar not found!"));
+            return None;
+        }
+    });
+}
+
+fn render(view: View, context: &mut DrawingContext)
+{
+    let (progress, indeterminate, elapsed) = match with_progress_bar(view, |progress_bar_object| {
+        let elapsed = crate::app::clock::now().saturating_duration_since(progress_bar_object.animation_start);
+        (progress_bar_object.progress, progress_bar_object.indeterminate, elapsed.as_secs_f32())
+    })
+    {
+        Some(progress_bar_properties) => progress_bar_properties,
+        None => return
+    };
+
+    let rect = crate::view::get_rect(view);
+    let color = crate::view::get_foreground_color(view);
+    let radius = rect.size.height / 2.0;
+
+    context.fill_rounded_rect(rect, radius, crate::widget::with_alpha(color, TRACK_ALPHA));
+
+    if indeterminate
+    {
+        // the indicator enters from the leading edge and leaves past the trailing edge
+        let phase = (elapsed / INDETERMINATE_PERIOD).fract();
+        let indicator_width = rect.size.width * INDETERMINATE_WIDTH;
+        let x = rect.origin.x - indicator_width + phase * (rect.size.width + indicator_width);
+
+        context.push_clip(rect);
+        context.fill_rounded_rect(Rect {
+            origin: Point { x: x, y: rect.origin.y },
+            size: Size { width: indicator_width, height: rect.size.height }
+        }, radius, color);
+        context.pop_clip();
+
+        // the indicator keeps moving while nothing else happens in the window
+        if let Some(window) = crate::view::get_window(view)
+        {
+            crate::window::request_redraw(window);
+        }
+    }
+    else if progress > 0.0
+    {
+        context.fill_rounded_rect(Rect {
+            origin: rect.origin,
+            size: Size { width: (rect.size.width * progress).max(rect.size.height.min(rect.size.width)), height: rect.size.height }
+        }, radius, color);
+    }
+}
@@ -0,0 +1,321 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  radio_button.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the RadioButton widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect, debug::*};
+
+pub use crate::View;
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, MouseButton};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+pub type RadioButtonChangedHandler = Box<dyn FnMut(View, bool)>;
+
+struct RadioButtonObject
+{
+    pub text: String,
+    pub group: String,
+    pub selected: bool,
+    pub pressed: bool,
+
+    pub changed_handler: Option<RadioButtonChangedHandler>
+}
+
+struct RadioButtonState
+{
+    pub radio_buttons: HashMap<View, RadioButtonObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static RADIO_BUTTON_STATE: RefCell<RadioButtonState> = RefCell::new(RadioButtonState {
+        radio_buttons: HashMap::new()
+    });
+}
+
+const DEFAULT_RADIO_BUTTON_SIZE: Size = Size { width: 160.0, height: 24.0 };
+const CIRCLE_SIZE: Size = Size { width: 16.0, height: 16.0 };
+const CIRCLE_BORDER_WIDTH: f32 = 1.5;
+const DOT_INSET: f32 = 4.0;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+
+    RADIO_BUTTON_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.radio_buttons.insert(new_view, RadioButtonObject {
+            text: String::from(""),
+            group: String::from(""),
+            selected: false,
+            pressed: false,
+            changed_handler: None
+        });
+    });
+
+    crate::view::set_size(new_view, DEFAULT_RADIO_BUTTON_SIZE);
+    crate::view::set_focusable(new_view, true);
+    crate::view::set_render_handler(new_view, render);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+pub fn set_text(view: View, text: &str)
+{
+    with_radio_button(view, |radio_button_object| radio_button_object.text = String::from(text));
+}
+
+pub fn get_text(view: View) -> String
+{
+    return with_radio_button(view, |radio_button_object| radio_button_object.text.clone()).unwrap_or(String::from(""));
+}
+
+// buttons sharing a group name within the same view tree are exclusive, buttons without a group are exclusive with their ungrouped siblings
+pub fn set_group(view: View, group: &str)
+{
+    with_radio_button(view, |radio_button_object| radio_button_object.group = String::from(group));
+}
+
+pub fn get_group(view: View) -> String
+{
+    return with_radio_button(view, |radio_button_object| radio_button_object.group.clone()).unwrap_or(String::from(""));
+}
+
+// selecting from code deselects the rest of the group but does not call the changed handlers
+pub fn set_selected(view: View, selected: bool)
+{
+    if selected
+    {
+        for member in get_group_members(view)
+        {
+            with_radio_button(member, |radio_button_object| radio_button_object.selected = member == view);
+        }
+    }
+    else
+    {
+        with_radio_button(view, |radio_button_object| radio_button_object.selected = false);
+    }
+}
+
+pub fn is_selected(view: View) -> bool
+{
+    return with_radio_button(view, |radio_button_object| radio_button_object.selected).unwrap_or(false);
+}
+
+// returns the selected button in the same group as view
+pub fn get_selected_in_group(view: View) -> Option<View>
+{
+    return get_group_members(view).into_iter().find(|member| is_selected(*member));
+}
+
+// called for every button in the group whose selection changed because of the user
+pub fn set_changed_handler<F: FnMut(View, bool) + 'static>(view: View, handler: F)
+{
+    with_radio_button(view, |radio_button_object| radio_button_object.changed_handler = Some(Box::new(handler)));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    RADIO_BUTTON_STATE.with(|state| state.borrow_mut().radio_buttons.remove(&view));
+}
+
+fn with_radio_button<T, F: FnOnce(&mut RadioButtonObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return RADIO_BUTTON_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(radio_button_object) = state.radio_buttons.get_mut(&view)
+        {
+            return Some(action(radio_button_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Radio button not found!"));
+            return None;
+        }
+    });
+}
+
+// the topmost ancestor, so the same group name in two windows or dialogs makes two groups
+fn get_root(view: View) -> View
+{
+    let mut root = view;
+
+    while let Some(parent) = crate::view::get_parent(root)
+    {
+        root = parent;
+    }
+
+    return root;
+}
+
+// members of the group in creation order, including view itself
+fn get_group_members(view: View) -> Vec<View>
+{
+    let group = get_group(view);
+    let parent = crate::view::get_parent(view);
+    let root = get_root(view);
+
+    let candidates: Vec<(View, String)> = RADIO_BUTTON_STATE.with(|state| {
+        let state = state.borrow();
+
+        return state.radio_buttons.iter()
+            .map(|(id, radio_button_object)| (*id, radio_button_object.group.clone()))
+            .collect();
+    });
+
+    let mut members: Vec<View> = candidates.into_iter()
+        .filter(|(id, member_group)| {
+            if group.is_empty()
+            {
+                return member_group.is_empty() && (*id == view || crate::view::get_parent(*id) == parent);
+            }
+
+            return *member_group == group && get_root(*id) == root;
+        })
+        .map(|(id, _)| id)
+        .collect();
+
+    members.sort();
+
+    return members;
+}
+
+fn select(view: View)
+{
+    let changed: Vec<View> = get_group_members(view).into_iter()
+        .filter(|member| is_selected(*member) != (*member == view))
+        .collect();
+
+    set_selected(view, true);
+
+    for member in changed
+    {
+        notify_changed(member, member == view);
+    }
+}
+
+fn notify_changed(view: View, selected: bool)
+{
+    crate::widget::call_taken(|slot| with_radio_button(view, |radio_button_object| slot(&mut radio_button_object.changed_handler)), |handler| handler(view, selected));
+}
+
+// arrow keys move the selection and the focus through the group
+fn select_adjacent(view: View, forward: bool)
+{
+    let members = get_group_members(view);
+
+    if let Some(index) = members.iter().position(|member| *member == view)
+    {
+        let count = members.len();
+        let next = if forward { (index + 1) % count } else { (index + count - 1) % count };
+
+        select(members[next]);
+
+        if let Some(window) = crate::view::get_window(view)
+        {
+            crate::window::set_focus(window, Some(members[next]));
+        }
+    }
+}
+
+fn handle_event(view: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { button: MouseButton::Left, .. } =>
+        {
+            with_radio_button(view, |radio_button_object| radio_button_object.pressed = true);
+            return true;
+        },
+        Event::MouseUp { point, button: MouseButton::Left, .. } =>
+        {
+            let was_pressed = with_radio_button(view, |radio_button_object| std::mem::replace(&mut radio_button_object.pressed, false)).unwrap_or(false);
+
+            if was_pressed && crate::view::rect_contains(crate::view::get_rect(view), *point)
+            {
+                select(view);
+            }
+
+            return true;
+        },
+        Event::KeyDown { key: Key::Space, .. } =>
+        {
+            select(view);
+            return true;
+        },
+        Event::KeyDown { key: Key::Down, .. } | Event::KeyDown { key: Key::Right, .. } =>
+        {
+            select_adjacent(view, true);
+            return true;
+        },
+        Event::KeyDown { key: Key::Up, .. } | Event::KeyDown { key: Key::Left, .. } =>
+        {
+            select_adjacent(view, false);
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn render(view: View, context: &mut DrawingContext)
+{
+    let (text, selected) = match with_radio_button(view, |radio_button_object| (radio_button_object.text.clone(), radio_button_object.selected))
+    {
+        Some(radio_button_properties) => radio_button_properties,
+        None => return
+    };
+
+    let color = crate::view::get_foreground_color(view);
+    let circle_rect = crate::widget::indicator_rect(view, CIRCLE_SIZE);
+    let radius = CIRCLE_SIZE.width / 2.0;
+
+    crate::widget::draw_focus_ring(view, context, circle_rect, radius);
+
+    context.stroke_rounded_rect(circle_rect, radius, CIRCLE_BORDER_WIDTH, color);
+
+    if selected
+    {
+        context.fill_ellipse(Rect {
+            origin: Point { x: circle_rect.origin.x + DOT_INSET, y: circle_rect.origin.y + DOT_INSET },
+            size: Size { width: circle_rect.size.width - 2.0 * DOT_INSET, height: circle_rect.size.height - 2.0 * DOT_INSET }
+        }, color);
+    }
+
+    let text_x = circle_rect.origin.x + circle_rect.size.width + crate::widget::INDICATOR_SPACING;
+    crate::widget::draw_view_text(view, context, text_x, &text);
+}
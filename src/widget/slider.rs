@@ -0,0 +1,364 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  slider.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the Slider widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect, debug::*};
+
+pub use crate::View;
+pub use crate::widget::Orientation;
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, MouseButton};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+pub type SliderChangedHandler = Box<dyn FnMut(View, f32)>;
+
+struct SliderObject
+{
+    pub orientation: Orientation,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    pub value: f32,
+    pub dragging: bool,
+
+    pub changed_handler: Option<SliderChangedHandler>
+}
+
+struct SliderState
+{
+    pub sliders: HashMap<View, SliderObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static SLIDER_STATE: RefCell<SliderState> = RefCell::new(SliderState {
+        sliders: HashMap::new()
+    });
+}
+
+const DEFAULT_SLIDER_LENGTH: f32 = 160.0;
+const DEFAULT_SLIDER_THICKNESS: f32 = 24.0;
+
+const TRACK_THICKNESS: f32 = 4.0;
+const THUMB_SIZE: f32 = 16.0;
+
+// alpha of the foreground color used for the part of the track past the thumb
+const TRACK_ALPHA: f32 = 0.3;
+
+// page up and page down move the value by this many steps
+const PAGE_STEPS: f32 = 10.0;
+
+// keyboard step used for continuous sliders, as a fraction of the range
+const CONTINUOUS_KEY_STEP: f32 = 0.01;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create(orientation: Orientation) -> View
+{
+    let new_view = crate::view::create();
+
+    SLIDER_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.sliders.insert(new_view, SliderObject {
+            orientation: orientation,
+            min: 0.0,
+            max: 1.0,
+            step: 0.0,
+            value: 0.0,
+            dragging: false,
+            changed_handler: None
+        });
+    });
+
+    let size = match orientation
+    {
+        Orientation::Horizontal => Size { width: DEFAULT_SLIDER_LENGTH, height: DEFAULT_SLIDER_THICKNESS },
+        Orientation::Vertical => Size { width: DEFAULT_SLIDER_THICKNESS, height: DEFAULT_SLIDER_LENGTH }
+    };
+
+    crate::view::set_size(new_view, size);
+    crate::view::set_focusable(new_view, true);
+    crate::view::set_render_handler(new_view, render);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+pub fn set_range(view: View, min: f32, max: f32)
+{
+    with_slider(view, |slider_object| {
+        slider_object.min = min.min(max);
+        slider_object.max = max.max(min);
+        slider_object.value = constrain(slider_object, slider_object.value);
+    });
+}
+
+pub fn get_min(view: View) -> f32
+{
+    return with_slider(view, |slider_object| slider_object.min).unwrap_or(0.0);
+}
+
+pub fn get_max(view: View) -> f32
+{
+    return with_slider(view, |slider_object| slider_object.max).unwrap_or(0.0);
+}
+
+// a step of zero makes the slider continuous
+pub fn set_step(view: View, step: f32)
+{
+    with_slider(view, |slider_object| {
+        slider_object.step = step.max(0.0);
+        slider_object.value = constrain(slider_object, slider_object.value);
+    });
+}
+
+pub fn get_step(view: View) -> f32
+{
+    return with_slider(view, |slider_object| slider_object.step).unwrap_or(0.0);
+}
+
+// the value is clamped to the range and snapped to the step, the changed handler is not called
+pub fn set_value(view: View, value: f32)
+{
+    with_slider(view, |slider_object| slider_object.value = constrain(slider_object, value));
+}
+
+pub fn get_value(view: View) -> f32
+{
+    return with_slider(view, |slider_object| slider_object.value).unwrap_or(0.0);
+}
+
+pub fn get_orientation(view: View) -> Orientation
+{
+    return with_slider(view, |slider_object| slider_object.orientation).unwrap_or(Orientation::Horizontal);
+}
+
+// called whenever the user drags the thumb or steps the value with the keyboard
+pub fn set_changed_handler<F: FnMut(View, f32) + 'static>(view: View, handler: F)
+{
+    with_slider(view, |slider_object| slider_object.changed_handler = Some(Box::new(handler)));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    SLIDER_STATE.with(|state| state.borrow_mut().sliders.remove(&view));
+}
+
+fn with_slider<T, F: FnOnce(&mut SliderObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return SLIDER_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(slider_object) = state.sliders.get_mut(&view)
+        {
+            return Some(action(slider_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Slider not found!"));
+            return None;
+        }
+    });
+}
+
+fn constrain(slider_object: &SliderObject, value: f32) -> f32
+{
+    let mut value = value.clamp(slider_object.min, slider_object.max);
+
+    if slider_object.step > 0.0
+    {
+        value = slider_object.min + ((value - slider_object.min) / slider_object.step).round() * slider_object.step;
+        value = value.clamp(slider_object.min, slider_object.max);
+    }
+
+    return value;
+}
+
+fn change_value(view: View, value: f32)
+{
+    let changed = with_slider(view, |slider_object| {
+        let value = constrain(slider_object, value);
+        let changed = value != slider_object.value;
+        slider_object.value = value;
+        return changed;
+    }).unwrap_or(false);
+
+    if !changed
+    {
+        return;
+    }
+
+    crate::widget::call_taken(|slot| with_slider(view, |slider_object| slot(&mut slider_object.changed_handler)), |handler| handler(view, get_value(view)));
+}
+
+// the thumb travels between half a thumb from either end, vertical sliders grow upwards
+fn value_from_point(view: View, point: Point) -> f32
+{
+    let rect = crate::view::get_rect(view);
+
+    let (min, max, orientation) = with_slider(view, |slider_object| (slider_object.min, slider_object.max, slider_object.orientation)).unwrap_or((0.0, 1.0, Orientation::Horizontal));
+
+    let fraction = match orientation
+    {
+        Orientation::Horizontal => (point.x - rect.origin.x - THUMB_SIZE / 2.0) / (rect.size.width - THUMB_SIZE),
+        Orientation::Vertical => 1.0 - (point.y - rect.origin.y - THUMB_SIZE / 2.0) / (rect.size.height - THUMB_SIZE)
+    };
+
+    if !fraction.is_finite()
+    {
+        return min;
+    }
+
+    return min + fraction.clamp(0.0, 1.0) * (max - min);
+}
+
+fn key_step(view: View) -> f32
+{
+    return with_slider(view, |slider_object| {
+        if slider_object.step > 0.0
+        {
+            return slider_object.step;
+        }
+
+        return (slider_object.max - slider_object.min) * CONTINUOUS_KEY_STEP;
+    }).unwrap_or(0.0);
+}
+
+fn handle_event(view: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { point, button: MouseButton::Left, .. } =>
+        {
+            with_slider(view, |slider_object| slider_object.dragging = true);
+            change_value(view, value_from_point(view, *point));
+            return true;
+        },
+        Event::MouseMove { point } =>
+        {
+            if with_slider(view, |slider_object| slider_object.dragging).unwrap_or(false)
+            {
+                change_value(view, value_from_point(view, *point));
+                return true;
+            }
+
+            return false;
+        },
+        Event::MouseUp { button: MouseButton::Left, .. } =>
+        {
+            with_slider(view, |slider_object| slider_object.dragging = false);
+            return true;
+        },
+        Event::KeyDown { key, .. } =>
+        {
+            let step = key_step(view);
+            let value = get_value(view);
+
+            let new_value = match key
+            {
+                Key::Right | Key::Up => value + step,
+                Key::Left | Key::Down => value - step,
+                Key::PageUp => value + step * PAGE_STEPS,
+                Key::PageDown => value - step * PAGE_STEPS,
+                Key::Home => get_min(view),
+                Key::End => get_max(view),
+                _ => return false
+            };
+
+            change_value(view, new_value);
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn render(view: View, context: &mut DrawingContext)
+{
+    let (orientation, fraction) = match with_slider(view, |slider_object| {
+        let range = slider_object.max - slider_object.min;
+        let fraction = if range > 0.0 { (slider_object.value - slider_object.min) / range } else { 0.0 };
+        (slider_object.orientation, fraction)
+    })
+    {
+        Some(slider_properties) => slider_properties,
+        None => return
+    };
+
+    let rect = crate::view::get_rect(view);
+    let color = crate::view::get_foreground_color(view);
+    let track_color = crate::widget::with_alpha(color, TRACK_ALPHA);
+
+    let (track, filled, thumb_center) = match orientation
+    {
+        Orientation::Horizontal =>
+        {
+            let track = Rect {
+                origin: Point { x: rect.origin.x + THUMB_SIZE / 2.0, y: rect.origin.y + (rect.size.height - TRACK_THICKNESS) / 2.0 },
+                size: Size { width: (rect.size.width - THUMB_SIZE).max(0.0), height: TRACK_THICKNESS }
+            };
+
+            let filled = Rect { origin: track.origin, size: Size { width: track.size.width * fraction, height: TRACK_THICKNESS } };
+            let thumb_center = Point { x: track.origin.x + filled.size.width, y: rect.origin.y + rect.size.height / 2.0 };
+
+            (track, filled, thumb_center)
+        },
+        Orientation::Vertical =>
+        {
+            let track = Rect {
+                origin: Point { x: rect.origin.x + (rect.size.width - TRACK_THICKNESS) / 2.0, y: rect.origin.y + THUMB_SIZE / 2.0 },
+                size: Size { width: TRACK_THICKNESS, height: (rect.size.height - THUMB_SIZE).max(0.0) }
+            };
+
+            let filled_height = track.size.height * fraction;
+            let filled = Rect {
+                origin: Point { x: track.origin.x, y: track.origin.y + track.size.height - filled_height },
+                size: Size { width: TRACK_THICKNESS, height: filled_height }
+            };
+            let thumb_center = Point { x: rect.origin.x + rect.size.width / 2.0, y: filled.origin.y };
+
+            (track, filled, thumb_center)
+        }
+    };
+
+    context.fill_rounded_rect(track, TRACK_THICKNESS / 2.0, track_color);
+    context.fill_rounded_rect(filled, TRACK_THICKNESS / 2.0, color);
+
+    let thumb = Rect {
+        origin: Point { x: thumb_center.x - THUMB_SIZE / 2.0, y: thumb_center.y - THUMB_SIZE / 2.0 },
+        size: Size { width: THUMB_SIZE, height: THUMB_SIZE }
+    };
+
+    crate::widget::draw_focus_ring(view, context, thumb, THUMB_SIZE / 2.0);
+    context.fill_ellipse(thumb, color);
+}
@@ -0,0 +1,628 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  split_view.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the SplitView container widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect, debug::*};
+
+pub use crate::View;
+pub use crate::widget::Orientation;
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, MouseButton};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// receives the share of the space given to each pane, for saving the layout
+pub type SplitRatiosChangedHandler = Box<dyn FnMut(View, &[f32])>;
+
+struct PaneObject
+{
+    pub view: View,
+
+    // the share of the space is kept while the split view resizes, and while the pane is collapsed
+    pub ratio: f32,
+    pub min_size: f32,
+    pub max_size: f32,
+
+    pub collapsible: bool,
+    pub collapsed: bool
+}
+
+// a divider being dragged, with the sizes of the panes either side when the drag started
+#[derive(Clone, Copy)]
+struct DividerDrag
+{
+    pub divider: usize,
+    pub start_position: f32,
+    pub start_sizes: (f32, f32)
+}
+
+struct SplitViewObject
+{
+    pub orientation: Orientation,
+    pub panes: Vec<PaneObject>,
+
+    // sizes and divider positions along the split axis from the last layout
+    pub pane_sizes: Vec<f32>,
+    pub divider_positions: Vec<f32>,
+
+    pub drag: Option<DividerDrag>,
+
+    pub ratios_changed_handler: Option<SplitRatiosChangedHandler>
+}
+
+struct SplitViewState
+{
+    pub split_views: HashMap<View, SplitViewObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static SPLIT_VIEW_STATE: RefCell<SplitViewState> = RefCell::new(SplitViewState {
+        split_views: HashMap::new()
+    });
+}
+
+const DEFAULT_SPLIT_VIEW_SIZE: Size = Size { width: 400.0, height: 300.0 };
+
+const DIVIDER_THICKNESS: f32 = 6.0;
+const GRIP_LENGTH: f32 = 24.0;
+
+// divider color is the foreground color at this alpha
+const DIVIDER_ALPHA: f32 = 0.25;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+// horizontal split views place their panes side by side, vertical ones stack them
+pub fn create(orientation: Orientation) -> View
+{
+    let new_view = crate::view::create();
+
+    SPLIT_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.split_views.insert(new_view, SplitViewObject {
+            orientation: orientation,
+            panes: Vec::new(),
+            pane_sizes: Vec::new(),
+            divider_positions: Vec::new(),
+            drag: None,
+            ratios_changed_handler: None
+        });
+    });
+
+    crate::view::set_size(new_view, DEFAULT_SPLIT_VIEW_SIZE);
+    crate::view::set_render_handler(new_view, render);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::set_layout_handler(new_view, layout);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+// adds a pane after the existing ones, new panes get an equal share of the space
+pub fn add_pane(view: View, pane: View)
+{
+    let added = with_split_view(view, |split_view_object| {
+        if split_view_object.panes.iter().any(|pane_object| pane_object.view == pane)
+        {
+            log(LogLevel::WARN, &format!("Pane already added!"));
+            return false;
+        }
+
+        // ratios are relative, so the mean of the others gives the new pane one equal share of the whole
+        let ratio = if split_view_object.panes.is_empty()
+        {
+            1.0
+        }
+        else
+        {
+            split_view_object.panes.iter().map(|pane_object| pane_object.ratio).sum::<f32>() / split_view_object.panes.len() as f32
+        };
+
+        split_view_object.panes.push(PaneObject {
+            view: pane,
+            ratio: ratio,
+            min_size: 0.0,
+            max_size: f32::INFINITY,
+            collapsible: false,
+            collapsed: false
+        });
+
+        return true;
+    }).unwrap_or(false);
+
+    if added
+    {
+        crate::view::add_child(view, pane);
+        relayout(view);
+    }
+}
+
+pub fn remove_pane(view: View, pane: View)
+{
+    let removed = with_split_view(view, |split_view_object| {
+        let index = split_view_object.panes.iter().position(|pane_object| pane_object.view == pane)?;
+        return Some(split_view_object.panes.remove(index).collapsed);
+    }).flatten();
+
+    match removed
+    {
+        Some(collapsed) =>
+        {
+            // collapsed panes are already out of the view tree
+            if !collapsed
+            {
+                crate::view::remove_child(view, pane);
+            }
+
+            relayout(view);
+        },
+        None => log(LogLevel::FAIL, &format!("Pane not found!"))
+    }
+}
+
+pub fn get_panes(view: View) -> Vec<View>
+{
+    return with_split_view(view, |split_view_object| split_view_object.panes.iter().map(|pane_object| pane_object.view).collect()).unwrap_or(Vec::new());
+}
+
+pub fn get_orientation(view: View) -> Orientation
+{
+    return with_split_view(view, |split_view_object| split_view_object.orientation).unwrap_or(Orientation::Horizontal);
+}
+
+// the size along the split axis that the pane keeps while the dividers move and the view resizes
+pub fn set_pane_min_size(view: View, pane: View, min_size: f32)
+{
+    with_pane(view, pane, |pane_object| pane_object.min_size = min_size.max(0.0));
+
+    relayout(view);
+}
+
+pub fn set_pane_max_size(view: View, pane: View, max_size: f32)
+{
+    with_pane(view, pane, |pane_object| pane_object.max_size = max_size.max(0.0));
+
+    relayout(view);
+}
+
+// dragging the divider of a collapsible pane past half its minimum size collapses it to the edge
+pub fn set_pane_collapsible(view: View, pane: View, collapsible: bool)
+{
+    with_pane(view, pane, |pane_object| pane_object.collapsible = collapsible);
+}
+
+// a collapsed pane takes no space and remembers its share for when it is expanded again
+pub fn set_pane_collapsed(view: View, pane: View, collapsed: bool)
+{
+    let changed = with_pane(view, pane, |pane_object| {
+        let changed = pane_object.collapsed != collapsed;
+        pane_object.collapsed = collapsed;
+        return changed;
+    }).unwrap_or(false);
+
+    if !changed
+    {
+        return;
+    }
+
+    // collapsed panes leave the view tree so nothing inside them is drawn or hit
+    if collapsed
+    {
+        crate::view::remove_child(view, pane);
+    }
+    else
+    {
+        crate::view::add_child(view, pane);
+    }
+
+    relayout(view);
+}
+
+pub fn is_pane_collapsed(view: View, pane: View) -> bool
+{
+    return with_pane(view, pane, |pane_object| pane_object.collapsed).unwrap_or(false);
+}
+
+// sets the share of the space for each pane in order, the ratios are normalized
+pub fn set_ratios(view: View, ratios: &[f32])
+{
+    with_split_view(view, |split_view_object| {
+        for (pane_object, ratio) in split_view_object.panes.iter_mut().zip(ratios.iter())
+        {
+            pane_object.ratio = ratio.max(0.0);
+        }
+    });
+
+    relayout(view);
+}
+
+// the share of the space for each pane in order, collapsed panes report the share they will get back
+pub fn get_ratios(view: View) -> Vec<f32>
+{
+    return with_split_view(view, |split_view_object| {
+        let total: f32 = split_view_object.panes.iter().map(|pane_object| pane_object.ratio).sum();
+
+        return split_view_object.panes.iter()
+            .map(|pane_object| if total > 0.0 { pane_object.ratio / total } else { 0.0 })
+            .collect();
+    }).unwrap_or(Vec::new());
+}
+
+// called when the user finishes dragging a divider
+pub fn set_ratios_changed_handler<F: FnMut(View, &[f32]) + 'static>(view: View, handler: F)
+{
+    with_split_view(view, |split_view_object| split_view_object.ratios_changed_handler = Some(Box::new(handler)));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    SPLIT_VIEW_STATE.with(|state| state.borrow_mut().split_views.remove(&view));
+}
+
+fn with_split_view<T, F: FnOnce(&mut SplitViewObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return SPLIT_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(split_view_object) = state.split_views.get_mut(&view)
+        {
+            return Some(action(split_view_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Split view not found!"));
+            return None;
+        }
+    });
+}
+
+fn with_pane<T, F: FnOnce(&mut PaneObject) -> T>(view: View, pane: View, action: F) -> Option<T>
+{
+    return with_split_view(view, |split_view_object| {
+        if let Some(pane_object) = split_view_object.panes.iter_mut().find(|pane_object| pane_object.view == pane)
+        {
+            return Some(action(pane_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Pane not found!"));
+            return None;
+        }
+    }).flatten();
+}
+
+fn relayout(view: View)
+{
+    crate::view::layout_down(view, crate::view::get_rect(view));
+}
+
+// the start of a rect along the split axis and its length
+fn axis(orientation: Orientation, rect: Rect) -> (f32, f32)
+{
+    return match orientation
+    {
+        Orientation::Horizontal => (rect.origin.x, rect.size.width),
+        Orientation::Vertical => (rect.origin.y, rect.size.height)
+    };
+}
+
+fn axis_position(orientation: Orientation, point: Point) -> f32
+{
+    return match orientation
+    {
+        Orientation::Horizontal => point.x,
+        Orientation::Vertical => point.y
+    };
+}
+
+// shares the space between the panes by ratio, panes pushed past their limits are held at them
+// and the rest is shared again between the others
+fn pane_sizes(panes: &[PaneObject], available: f32) -> Vec<f32>
+{
+    let mut sizes: Vec<f32> = vec![0.0; panes.len()];
+    let mut fixed: Vec<bool> = panes.iter().map(|pane_object| pane_object.collapsed).collect();
+
+    loop
+    {
+        let free = available - sizes.iter().zip(fixed.iter()).filter(|(_, fixed)| **fixed).map(|(size, _)| *size).sum::<f32>();
+        let ratio_total: f32 = panes.iter().zip(fixed.iter()).filter(|(_, fixed)| !**fixed).map(|(pane_object, _)| pane_object.ratio).sum();
+
+        let open: Vec<usize> = (0..panes.len()).filter(|index| !fixed[*index]).collect();
+
+        if open.is_empty()
+        {
+            break;
+        }
+
+        let mut clamped = false;
+
+        for index in open.iter()
+        {
+            let share = if ratio_total > 0.0 { panes[*index].ratio / ratio_total } else { 1.0 / open.len() as f32 };
+            let size = free.max(0.0) * share;
+
+            if size < panes[*index].min_size
+            {
+                sizes[*index] = panes[*index].min_size;
+                fixed[*index] = true;
+                clamped = true;
+            }
+            else if size > panes[*index].max_size
+            {
+                sizes[*index] = panes[*index].max_size;
+                fixed[*index] = true;
+                clamped = true;
+            }
+            else
+            {
+                sizes[*index] = size;
+            }
+        }
+
+        if !clamped
+        {
+            break;
+        }
+    }
+
+    return sizes;
+}
+
+// panes are placed along the split axis with a divider between each pair
+fn layout(view: View, rect: Rect)
+{
+    let placed = with_split_view(view, |split_view_object| {
+        let (start, length) = axis(split_view_object.orientation, rect);
+        let divider_count = split_view_object.panes.len().saturating_sub(1);
+        let available = (length - divider_count as f32 * DIVIDER_THICKNESS).max(0.0);
+
+        let sizes = pane_sizes(&split_view_object.panes, available);
+
+        let mut position = start;
+        let mut pane_rects: Vec<(View, Rect)> = Vec::new();
+        let mut divider_positions: Vec<f32> = Vec::new();
+
+        for (index, pane_object) in split_view_object.panes.iter().enumerate()
+        {
+            let pane_rect = match split_view_object.orientation
+            {
+                Orientation::Horizontal => Rect { origin: Point { x: position, y: rect.origin.y }, size: Size { width: sizes[index], height: rect.size.height } },
+                Orientation::Vertical => Rect { origin: Point { x: rect.origin.x, y: position }, size: Size { width: rect.size.width, height: sizes[index] } }
+            };
+
+            if !pane_object.collapsed
+            {
+                pane_rects.push((pane_object.view, pane_rect));
+            }
+
+            position += sizes[index];
+
+            if index < divider_count
+            {
+                divider_positions.push(position);
+                position += DIVIDER_THICKNESS;
+            }
+        }
+
+        split_view_object.pane_sizes = sizes;
+        split_view_object.divider_positions = divider_positions;
+
+        return pane_rects;
+    }).unwrap_or(Vec::new());
+
+    for (pane, pane_rect) in placed
+    {
+        crate::view::layout_down(pane, pane_rect);
+    }
+}
+
+fn divider_at(view: View, point: Point) -> Option<usize>
+{
+    return with_split_view(view, |split_view_object| {
+        let position = axis_position(split_view_object.orientation, point);
+
+        return split_view_object.divider_positions.iter()
+            .position(|divider_position| position >= *divider_position && position < divider_position + DIVIDER_THICKNESS);
+    }).flatten();
+}
+
+// moves a divider so the panes either side trade space, collapsing a collapsible pane pushed below
+// half its minimum size and expanding a collapsed pane the divider is dragged away from
+fn drag_divider(view: View, drag: DividerDrag, position: f32)
+{
+    let toggled = with_split_view(view, |split_view_object| {
+        let (first, second) = (drag.divider, drag.divider + 1);
+
+        if second >= split_view_object.panes.len()
+        {
+            return Vec::new();
+        }
+
+        let total = drag.start_sizes.0 + drag.start_sizes.1;
+        let mut first_size = (drag.start_sizes.0 + position - drag.start_position).clamp(0.0, total);
+
+        let first_pane = &split_view_object.panes[first];
+        let second_pane = &split_view_object.panes[second];
+
+        let collapse_first = first_pane.collapsible && first_size < first_pane.min_size / 2.0;
+        let collapse_second = second_pane.collapsible && total - first_size < second_pane.min_size / 2.0;
+
+        if !collapse_first && !collapse_second
+        {
+            let lower = first_pane.min_size.max(total - second_pane.max_size);
+            let upper = first_pane.max_size.min(total - second_pane.min_size);
+            first_size = first_size.clamp(lower.min(upper), upper.max(lower));
+        }
+
+        let mut toggled: Vec<(View, bool)> = Vec::new();
+
+        for (index, collapse) in [(first, collapse_first), (second, collapse_second)]
+        {
+            let pane_object = &mut split_view_object.panes[index];
+
+            if pane_object.collapsed != collapse
+            {
+                pane_object.collapsed = collapse;
+                toggled.push((pane_object.view, collapse));
+            }
+        }
+
+        // the open panes take their shares from their sizes, a collapsed pane keeps its old share
+        let available: f32 = split_view_object.pane_sizes.iter().sum::<f32>().max(1.0);
+
+        for (index, pane_object) in split_view_object.panes.iter_mut().enumerate()
+        {
+            let size = match index
+            {
+                _ if pane_object.collapsed => continue,
+                _ if index == first => if collapse_second { total } else { first_size },
+                _ if index == second => if collapse_first { total } else { total - first_size },
+                _ => split_view_object.pane_sizes[index]
+            };
+
+            pane_object.ratio = size / available;
+        }
+
+        return toggled;
+    }).unwrap_or(Vec::new());
+
+    for (pane, collapsed) in toggled
+    {
+        if collapsed
+        {
+            crate::view::remove_child(view, pane);
+        }
+        else
+        {
+            crate::view::add_child(view, pane);
+        }
+    }
+
+    relayout(view);
+}
+
+fn notify_ratios_changed(view: View)
+{
+    crate::widget::call_taken(|slot| with_split_view(view, |split_view_object| slot(&mut split_view_object.ratios_changed_handler)), |handler| handler(view, &get_ratios(view)));
+}
+
+// the dividers are the gaps between the panes, so presses on them reach the split view itself
+fn handle_event(view: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { point, button: MouseButton::Left, .. } =>
+        {
+            let divider = match divider_at(view, *point)
+            {
+                Some(divider) => divider,
+                None => return false
+            };
+
+            with_split_view(view, |split_view_object| {
+                let start_sizes = (split_view_object.pane_sizes[divider], split_view_object.pane_sizes[divider + 1]);
+
+                split_view_object.drag = Some(DividerDrag {
+                    divider: divider,
+                    start_position: axis_position(split_view_object.orientation, *point),
+                    start_sizes: start_sizes
+                });
+            });
+
+            return true;
+        },
+        Event::MouseMove { point } =>
+        {
+            let drag = with_split_view(view, |split_view_object| split_view_object.drag).flatten();
+
+            if let Some(drag) = drag
+            {
+                drag_divider(view, drag, axis_position(get_orientation(view), *point));
+                return true;
+            }
+
+            return false;
+        },
+        Event::MouseUp { button: MouseButton::Left, .. } =>
+        {
+            let drag = with_split_view(view, |split_view_object| split_view_object.drag.take()).flatten();
+
+            if drag.is_some()
+            {
+                notify_ratios_changed(view);
+                return true;
+            }
+
+            return false;
+        },
+        _ => return false
+    }
+}
+
+// each divider is a thin line with a short grip in the middle
+fn render(view: View, context: &mut DrawingContext)
+{
+    let rect = crate::view::get_rect(view);
+    let color = crate::widget::with_alpha(crate::view::get_foreground_color(view), DIVIDER_ALPHA);
+
+    let (orientation, divider_positions) = match with_split_view(view, |split_view_object| (split_view_object.orientation, split_view_object.divider_positions.clone()))
+    {
+        Some(split_view_properties) => split_view_properties,
+        None => return
+    };
+
+    for divider_position in divider_positions
+    {
+        let center = divider_position + DIVIDER_THICKNESS / 2.0;
+
+        let (line, grip) = match orientation
+        {
+            Orientation::Horizontal =>
+            {
+                let grip_y = rect.origin.y + (rect.size.height - GRIP_LENGTH) / 2.0;
+
+                (Rect { origin: Point { x: center - 0.5, y: rect.origin.y }, size: Size { width: 1.0, height: rect.size.height } },
+                 Rect { origin: Point { x: center - 1.5, y: grip_y }, size: Size { width: 3.0, height: GRIP_LENGTH } })
+            },
+            Orientation::Vertical =>
+            {
+                let grip_x = rect.origin.x + (rect.size.width - GRIP_LENGTH) / 2.0;
+
+                (Rect { origin: Point { x: rect.origin.x, y: center - 0.5 }, size: Size { width: rect.size.width, height: 1.0 } },
+                 Rect { origin: Point { x: grip_x, y: center - 1.5 }, size: Size { width: GRIP_LENGTH, height: 3.0 } })
+            }
+        };
+
+        context.fill_rect(line, color);
+        context.fill_rounded_rect(grip, 1.5, color);
+    }
+}
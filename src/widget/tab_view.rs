@@ -0,0 +1,797 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  tab_view.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the TabView container widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect, debug::*};
+
+pub use crate::View;
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, MouseButton};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// tabs are identified by their content view, which stays the same when tabs move
+pub type TabSelectedHandler = Box<dyn FnMut(View, Option<View>)>;
+
+// returns false to keep the tab open, for example while it has unsaved changes
+pub type TabClosingHandler = Box<dyn FnMut(View, View) -> bool>;
+
+// receives the tab that was dragged and its new index
+pub type TabMovedHandler = Box<dyn FnMut(View, View, usize)>;
+
+struct TabObject
+{
+    pub content: View,
+    pub title: String,
+    pub closable: bool
+}
+
+// a press on a tab that turns into a drag once the pointer moves far enough
+#[derive(Clone, Copy)]
+struct TabPress
+{
+    pub content: View,
+    pub start_x: f32,
+    pub on_close_button: bool,
+    pub dragging: bool
+}
+
+struct TabViewObject
+{
+    pub strip: View,
+
+    pub tabs: Vec<TabObject>,
+    pub selected_tab: Option<View>,
+
+    // tabs that do not fit scroll sideways in the strip
+    pub scroll_offset: f32,
+
+    pub press: Option<TabPress>,
+
+    pub selected_handler: Option<TabSelectedHandler>,
+    pub closing_handler: Option<TabClosingHandler>,
+    pub moved_handler: Option<TabMovedHandler>
+}
+
+struct TabViewState
+{
+    pub tab_views: HashMap<View, TabViewObject>,
+    pub strips: HashMap<View, View>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static TAB_VIEW_STATE: RefCell<TabViewState> = RefCell::new(TabViewState {
+        tab_views: HashMap::new(),
+        strips: HashMap::new()
+    });
+}
+
+const DEFAULT_TAB_VIEW_SIZE: Size = Size { width: 400.0, height: 300.0 };
+
+const STRIP_HEIGHT: f32 = 30.0;
+const TAB_PADDING: f32 = 12.0;
+const MIN_TAB_WIDTH: f32 = 60.0;
+const MAX_TAB_WIDTH: f32 = 200.0;
+
+const CLOSE_BUTTON_SIZE: f32 = 14.0;
+const CLOSE_CROSS_INSET: f32 = 4.0;
+const CLOSE_CROSS_WIDTH: f32 = 1.5;
+
+// overflowing strips end with a pair of buttons that scroll the tabs by this much
+const SCROLL_BUTTON_WIDTH: f32 = 20.0;
+const SCROLL_STEP: f32 = 80.0;
+const SCROLL_ARROW_SIZE: f32 = 8.0;
+
+// the pointer has to move this far with the button down before a tab is dragged
+const DRAG_THRESHOLD: f32 = 4.0;
+
+// selected tab and divider colors are the foreground color at these alphas
+const SELECTED_TAB_ALPHA: f32 = 0.12;
+const DIVIDER_ALPHA: f32 = 0.25;
+const SELECTED_LINE_WIDTH: f32 = 2.0;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+    let strip = crate::view::create();
+
+    TAB_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.tab_views.insert(new_view, TabViewObject {
+            strip: strip,
+            tabs: Vec::new(),
+            selected_tab: None,
+            scroll_offset: 0.0,
+            press: None,
+            selected_handler: None,
+            closing_handler: None,
+            moved_handler: None
+        });
+
+        state.strips.insert(strip, new_view);
+    });
+
+    crate::view::set_size(new_view, DEFAULT_TAB_VIEW_SIZE);
+    crate::view::set_clips_children(new_view, true);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::set_layout_handler(new_view, layout);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    crate::view::set_render_handler(strip, render_strip);
+    crate::view::set_event_handler(strip, handle_strip_event);
+    crate::view::add_destroy_handler(strip, forget_strip);
+    crate::view::add_child(new_view, strip);
+
+    return new_view;
+}
+
+// appends a tab showing the content view, the first tab added is selected
+pub fn add_tab(view: View, title: &str, content: View)
+{
+    let added = with_tab_view(view, |tab_view_object| {
+        if tab_view_object.tabs.iter().any(|tab_object| tab_object.content == content)
+        {
+            log(LogLevel::WARN, &format!("Tab already added!"));
+            return false;
+        }
+
+        tab_view_object.tabs.push(TabObject { content: content, title: String::from(title), closable: false });
+        return true;
+    }).unwrap_or(false);
+
+    if added && get_selected_tab(view).is_none()
+    {
+        select_tab(view, Some(content));
+    }
+    else
+    {
+        relayout(view);
+    }
+}
+
+// removes a tab without asking the closing handler, the selection moves to a neighbouring tab
+pub fn remove_tab(view: View, content: View)
+{
+    let removed = with_tab_view(view, |tab_view_object| {
+        let index = tab_view_object.tabs.iter().position(|tab_object| tab_object.content == content)?;
+        tab_view_object.tabs.remove(index);
+
+        let neighbour = tab_view_object.tabs.get(index).or(tab_view_object.tabs.last()).map(|tab_object| tab_object.content);
+        return Some((tab_view_object.selected_tab == Some(content), neighbour));
+    }).flatten();
+
+    match removed
+    {
+        Some((true, neighbour)) => select_tab(view, neighbour),
+        Some((false, _)) => relayout(view),
+        None => log(LogLevel::FAIL, &format!("Tab not found!"))
+    }
+}
+
+// moves a tab to a new position in the strip
+pub fn move_tab(view: View, content: View, index: usize)
+{
+    let moved = with_tab_view(view, |tab_view_object| {
+        if let Some(current) = tab_view_object.tabs.iter().position(|tab_object| tab_object.content == content)
+        {
+            let tab_object = tab_view_object.tabs.remove(current);
+            let index = index.min(tab_view_object.tabs.len());
+            tab_view_object.tabs.insert(index, tab_object);
+            return true;
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Tab not found!"));
+            return false;
+        }
+    }).unwrap_or(false);
+
+    if moved
+    {
+        relayout(view);
+    }
+}
+
+// content views of the tabs in strip order
+pub fn get_tabs(view: View) -> Vec<View>
+{
+    return with_tab_view(view, |tab_view_object| tab_view_object.tabs.iter().map(|tab_object| tab_object.content).collect()).unwrap_or(Vec::new());
+}
+
+pub fn set_tab_title(view: View, content: View, title: &str)
+{
+    with_tab(view, content, |tab_object| tab_object.title = String::from(title));
+}
+
+pub fn get_tab_title(view: View, content: View) -> String
+{
+    return with_tab(view, content, |tab_object| tab_object.title.clone()).unwrap_or(String::from(""));
+}
+
+// closable tabs show a close button
+pub fn set_tab_closable(view: View, content: View, closable: bool)
+{
+    with_tab(view, content, |tab_object| tab_object.closable = closable);
+}
+
+pub fn is_tab_closable(view: View, content: View) -> bool
+{
+    return with_tab(view, content, |tab_object| tab_object.closable).unwrap_or(false);
+}
+
+// selecting from code does not call the selected handler
+pub fn select_tab(view: View, content: Option<View>)
+{
+    let valid = content.is_none_or(|content| get_tabs(view).contains(&content));
+
+    if !valid
+    {
+        log(LogLevel::FAIL, &format!("Tab not found!"));
+        return;
+    }
+
+    let previous = with_tab_view(view, |tab_view_object| std::mem::replace(&mut tab_view_object.selected_tab, content)).flatten();
+
+    // only the selected tab's content is part of the view tree
+    if previous != content
+    {
+        if let Some(previous) = previous
+        {
+            crate::view::remove_child(view, previous);
+        }
+
+        if let Some(content) = content
+        {
+            crate::view::add_child(view, content);
+        }
+    }
+
+    relayout(view);
+
+    if let Some(content) = content
+    {
+        scroll_to_tab(view, content);
+    }
+}
+
+pub fn get_selected_tab(view: View) -> Option<View>
+{
+    return with_tab_view(view, |tab_view_object| tab_view_object.selected_tab).flatten();
+}
+
+// called whenever the user selects a tab, or closing the selected tab moves the selection
+pub fn set_selected_handler<F: FnMut(View, Option<View>) + 'static>(view: View, handler: F)
+{
+    with_tab_view(view, |tab_view_object| tab_view_object.selected_handler = Some(Box::new(handler)));
+}
+
+pub fn set_closing_handler<F: FnMut(View, View) -> bool + 'static>(view: View, handler: F)
+{
+    with_tab_view(view, |tab_view_object| tab_view_object.closing_handler = Some(Box::new(handler)));
+}
+
+pub fn set_moved_handler<F: FnMut(View, View, usize) + 'static>(view: View, handler: F)
+{
+    with_tab_view(view, |tab_view_object| tab_view_object.moved_handler = Some(Box::new(handler)));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// the selected content is a child and goes with the tab view, the others are not so they are destroyed here
+fn forget(view: View)
+{
+    let tab_view_object = TAB_VIEW_STATE.with(|state| state.borrow_mut().tab_views.remove(&view));
+
+    if let Some(tab_view_object) = tab_view_object
+    {
+        for tab_object in tab_view_object.tabs.iter().filter(|tab_object| Some(tab_object.content) != tab_view_object.selected_tab)
+        {
+            crate::view::destroy(tab_object.content);
+        }
+    }
+}
+
+fn forget_strip(strip: View)
+{
+    TAB_VIEW_STATE.with(|state| state.borrow_mut().strips.remove(&strip));
+}
+
+fn with_tab_view<T, F: FnOnce(&mut TabViewObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return TAB_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(tab_view_object) = state.tab_views.get_mut(&view)
+        {
+            return Some(action(tab_view_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Tab view not found!"));
+            return None;
+        }
+    });
+}
+
+fn with_tab<T, F: FnOnce(&mut TabObject) -> T>(view: View, content: View, action: F) -> Option<T>
+{
+    return with_tab_view(view, |tab_view_object| {
+        if let Some(tab_object) = tab_view_object.tabs.iter_mut().find(|tab_object| tab_object.content == content)
+        {
+            return Some(action(tab_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Tab not found!"));
+            return None;
+        }
+    }).flatten();
+}
+
+fn get_strip_owner(strip: View) -> Option<View>
+{
+    return TAB_VIEW_STATE.with(|state| state.borrow().strips.get(&strip).cloned());
+}
+
+fn relayout(view: View)
+{
+    crate::view::layout_down(view, crate::view::get_rect(view));
+}
+
+// the strip runs along the top and the selected content fills the rest
+fn layout(view: View, rect: Rect)
+{
+    let layout_properties = with_tab_view(view, |tab_view_object| (tab_view_object.strip, tab_view_object.selected_tab));
+
+    if let Some((strip, selected_tab)) = layout_properties
+    {
+        let strip_height = STRIP_HEIGHT.min(rect.size.height);
+
+        crate::view::layout_down(strip, Rect {
+            origin: rect.origin,
+            size: Size { width: rect.size.width, height: strip_height }
+        });
+
+        if let Some(selected_tab) = selected_tab
+        {
+            crate::view::layout_down(selected_tab, Rect {
+                origin: Point { x: rect.origin.x, y: rect.origin.y + strip_height },
+                size: Size { width: rect.size.width, height: rect.size.height - strip_height }
+            });
+        }
+
+        clamp_scroll_offset(view);
+    }
+}
+
+// returns each tab's content with its left edge and width, measured from the start of the strip content
+fn tab_spans(view: View) -> Vec<(View, f32, f32)>
+{
+    let font = crate::view::get_font(view);
+
+    let tabs: Vec<(View, String, bool)> = with_tab_view(view, |tab_view_object| {
+        tab_view_object.tabs.iter().map(|tab_object| (tab_object.content, tab_object.title.clone(), tab_object.closable)).collect()
+    }).unwrap_or(Vec::new());
+
+    let mut left = 0.0;
+
+    return tabs.into_iter().map(|(content, title, closable)| {
+        let close_width = if closable { CLOSE_BUTTON_SIZE + TAB_PADDING / 2.0 } else { 0.0 };
+        let width = (crate::drawing::font::measure_run(&title, &font) + 2.0 * TAB_PADDING + close_width).clamp(MIN_TAB_WIDTH, MAX_TAB_WIDTH);
+        let span = (content, left, width);
+        left += width;
+        return span;
+    }).collect();
+}
+
+fn content_width(spans: &[(View, f32, f32)]) -> f32
+{
+    return spans.last().map_or(0.0, |(_, left, width)| left + width);
+}
+
+// the area of the strip that shows tabs, narrowed by the scroll buttons when the tabs overflow
+fn tabs_rect(view: View, strip_rect: Rect) -> (Rect, bool)
+{
+    let overflow = content_width(&tab_spans(view)) > strip_rect.size.width;
+    let width = if overflow { (strip_rect.size.width - 2.0 * SCROLL_BUTTON_WIDTH).max(0.0) } else { strip_rect.size.width };
+
+    return (Rect { origin: strip_rect.origin, size: Size { width: width, height: strip_rect.size.height } }, overflow);
+}
+
+fn get_strip_rect(view: View) -> Rect
+{
+    return with_tab_view(view, |tab_view_object| crate::view::get_rect(tab_view_object.strip)).unwrap_or(crate::view::get_rect(view));
+}
+
+fn clamp_scroll_offset(view: View)
+{
+    let (tabs_rect, _) = tabs_rect(view, get_strip_rect(view));
+    let max_offset = (content_width(&tab_spans(view)) - tabs_rect.size.width).max(0.0);
+
+    with_tab_view(view, |tab_view_object| tab_view_object.scroll_offset = tab_view_object.scroll_offset.clamp(0.0, max_offset));
+}
+
+fn scroll_by(view: View, delta: f32)
+{
+    with_tab_view(view, |tab_view_object| tab_view_object.scroll_offset += delta);
+    clamp_scroll_offset(view);
+}
+
+// scrolls the strip the least distance that shows the whole tab
+fn scroll_to_tab(view: View, content: View)
+{
+    let (tabs_rect, _) = tabs_rect(view, get_strip_rect(view));
+
+    if let Some((_, left, width)) = tab_spans(view).into_iter().find(|(tab_content, _, _)| *tab_content == content)
+    {
+        with_tab_view(view, |tab_view_object| {
+            if left < tab_view_object.scroll_offset
+            {
+                tab_view_object.scroll_offset = left;
+            }
+            else if left + width > tab_view_object.scroll_offset + tabs_rect.size.width
+            {
+                tab_view_object.scroll_offset = left + width - tabs_rect.size.width;
+            }
+        });
+
+        clamp_scroll_offset(view);
+    }
+}
+
+// returns the tab under a point in the strip and whether the point is on its close button
+fn tab_at_point(view: View, point: Point) -> Option<(View, bool)>
+{
+    let strip_rect = get_strip_rect(view);
+    let (tabs_rect, _) = tabs_rect(view, strip_rect);
+
+    if !crate::view::rect_contains(tabs_rect, point)
+    {
+        return None;
+    }
+
+    let scroll_offset = with_tab_view(view, |tab_view_object| tab_view_object.scroll_offset).unwrap_or(0.0);
+    let x = point.x - tabs_rect.origin.x + scroll_offset;
+
+    let (content, left, width) = tab_spans(view).into_iter().find(|(_, left, width)| x >= *left && x < left + width)?;
+
+    let tab_rect = Rect {
+        origin: Point { x: tabs_rect.origin.x + left - scroll_offset, y: tabs_rect.origin.y },
+        size: Size { width: width, height: tabs_rect.size.height }
+    };
+
+    let on_close_button = is_tab_closable(view, content) && crate::view::rect_contains(close_button_rect(tab_rect), point);
+
+    return Some((content, on_close_button));
+}
+
+fn close_button_rect(tab_rect: Rect) -> Rect
+{
+    return Rect {
+        origin: Point {
+            x: tab_rect.origin.x + tab_rect.size.width - TAB_PADDING / 2.0 - CLOSE_BUTTON_SIZE,
+            y: tab_rect.origin.y + (tab_rect.size.height - CLOSE_BUTTON_SIZE) / 2.0
+        },
+        size: Size { width: CLOSE_BUTTON_SIZE, height: CLOSE_BUTTON_SIZE }
+    };
+}
+
+// selects a tab and tells the selected handler
+fn change_selection(view: View, content: Option<View>)
+{
+    if get_selected_tab(view) == content
+    {
+        return;
+    }
+
+    select_tab(view, content);
+    notify_selected(view);
+}
+
+// the user clicked a close button, the closing handler may keep the tab open
+fn close_tab(view: View, content: View)
+{
+    let close = crate::widget::call_taken(|slot| with_tab_view(view, |tab_view_object| slot(&mut tab_view_object.closing_handler)), |handler| handler(view, content)).unwrap_or(true);
+
+    if close && get_tabs(view).contains(&content)
+    {
+        let was_selected = get_selected_tab(view) == Some(content);
+        remove_tab(view, content);
+
+        // closing the selected tab selects a neighbour
+        if was_selected
+        {
+            notify_selected(view);
+        }
+    }
+}
+
+fn notify_selected(view: View)
+{
+    crate::widget::call_taken(|slot| with_tab_view(view, |tab_view_object| slot(&mut tab_view_object.selected_handler)), |handler| handler(view, get_selected_tab(view)));
+}
+
+fn notify_moved(view: View, content: View)
+{
+    let index = match get_tabs(view).iter().position(|tab_content| *tab_content == content)
+    {
+        Some(index) => index,
+        None => return
+    };
+
+    crate::widget::call_taken(|slot| with_tab_view(view, |tab_view_object| slot(&mut tab_view_object.moved_handler)), |handler| handler(view, content, index));
+}
+
+// while dragging, the tab swaps places with a neighbour once the pointer passes the neighbour's middle
+fn drag_tab(view: View, content: View, point: Point)
+{
+    let strip_rect = get_strip_rect(view);
+    let (tabs_rect, _) = tabs_rect(view, strip_rect);
+    let scroll_offset = with_tab_view(view, |tab_view_object| tab_view_object.scroll_offset).unwrap_or(0.0);
+    let x = point.x - tabs_rect.origin.x + scroll_offset;
+
+    let spans = tab_spans(view);
+
+    let current = match spans.iter().position(|(tab_content, _, _)| *tab_content == content)
+    {
+        Some(current) => current,
+        None => return
+    };
+
+    let mut target = current;
+
+    while target + 1 < spans.len() && x > spans[target + 1].1 + spans[target + 1].2 / 2.0
+    {
+        target += 1;
+    }
+
+    while target > 0 && x < spans[target - 1].1 + spans[target - 1].2 / 2.0
+    {
+        target -= 1;
+    }
+
+    if target != current
+    {
+        move_tab(view, content, target);
+    }
+}
+
+fn handle_strip_event(strip: View, event: &Event) -> bool
+{
+    let view = match get_strip_owner(strip)
+    {
+        Some(view) => view,
+        None => return false
+    };
+
+    match event
+    {
+        Event::MouseDown { point, button: MouseButton::Left, .. } =>
+        {
+            let strip_rect = crate::view::get_rect(strip);
+            let (tabs_rect, overflow) = tabs_rect(view, strip_rect);
+
+            // the scroll buttons sit past the end of the visible tabs
+            if overflow && point.x >= tabs_rect.origin.x + tabs_rect.size.width
+            {
+                let backward = point.x < tabs_rect.origin.x + tabs_rect.size.width + SCROLL_BUTTON_WIDTH;
+                scroll_by(view, if backward { -SCROLL_STEP } else { SCROLL_STEP });
+                return true;
+            }
+
+            if let Some((content, on_close_button)) = tab_at_point(view, *point)
+            {
+                with_tab_view(view, |tab_view_object| {
+                    tab_view_object.press = Some(TabPress { content: content, start_x: point.x, on_close_button: on_close_button, dragging: false });
+                });
+
+                if !on_close_button
+                {
+                    change_selection(view, Some(content));
+                }
+            }
+
+            return true;
+        },
+        Event::MouseMove { point } =>
+        {
+            let press = with_tab_view(view, |tab_view_object| {
+                if let Some(press) = tab_view_object.press.as_mut()
+                {
+                    if !press.on_close_button && (point.x - press.start_x).abs() >= DRAG_THRESHOLD
+                    {
+                        press.dragging = true;
+                    }
+                }
+
+                return tab_view_object.press;
+            }).flatten();
+
+            if let Some(press) = press.filter(|press| press.dragging)
+            {
+                drag_tab(view, press.content, *point);
+                return true;
+            }
+
+            return false;
+        },
+        Event::MouseUp { point, button: MouseButton::Left, .. } =>
+        {
+            let press = with_tab_view(view, |tab_view_object| tab_view_object.press.take()).flatten();
+
+            if let Some(press) = press
+            {
+                if press.dragging
+                {
+                    scroll_to_tab(view, press.content);
+                    notify_moved(view, press.content);
+                }
+                else if press.on_close_button && tab_at_point(view, *point) == Some((press.content, true))
+                {
+                    close_tab(view, press.content);
+                }
+            }
+
+            return true;
+        },
+        Event::Scroll { delta, .. } =>
+        {
+            let offset = with_tab_view(view, |tab_view_object| tab_view_object.scroll_offset).unwrap_or(0.0);
+            scroll_by(view, delta.x + delta.y);
+            return with_tab_view(view, |tab_view_object| tab_view_object.scroll_offset != offset).unwrap_or(false);
+        },
+        _ => return false
+    }
+}
+
+// control tab and control shift tab arrive here from the focused content and cycle through the tabs
+fn handle_event(view: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::KeyDown { key: Key::Tab, modifiers } if modifiers.control =>
+        {
+            let tabs = get_tabs(view);
+
+            if tabs.is_empty()
+            {
+                return false;
+            }
+
+            let count = tabs.len();
+            let current = get_selected_tab(view).and_then(|selected_tab| tabs.iter().position(|content| *content == selected_tab)).unwrap_or(0);
+            let next = if modifiers.shift { (current + count - 1) % count } else { (current + 1) % count };
+
+            change_selection(view, Some(tabs[next]));
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn render_strip(strip: View, context: &mut DrawingContext)
+{
+    let view = match get_strip_owner(strip)
+    {
+        Some(view) => view,
+        None => return
+    };
+
+    let strip_rect = crate::view::get_rect(strip);
+    let (tabs_rect, overflow) = tabs_rect(view, strip_rect);
+
+    let font = crate::view::get_font(view);
+    let color = crate::view::get_foreground_color(view);
+    let divider_color = crate::widget::with_alpha(color, DIVIDER_ALPHA);
+
+    let (scroll_offset, selected_tab) = with_tab_view(view, |tab_view_object| (tab_view_object.scroll_offset, tab_view_object.selected_tab)).unwrap_or((0.0, None));
+
+    // the line along the bottom of the strip separates it from the content
+    context.fill_rect(Rect {
+        origin: Point { x: strip_rect.origin.x, y: strip_rect.origin.y + strip_rect.size.height - 1.0 },
+        size: Size { width: strip_rect.size.width, height: 1.0 }
+    }, divider_color);
+
+    context.push_clip(tabs_rect);
+
+    for (content, left, width) in tab_spans(view)
+    {
+        let tab_rect = Rect {
+            origin: Point { x: tabs_rect.origin.x + left - scroll_offset, y: tabs_rect.origin.y },
+            size: Size { width: width, height: tabs_rect.size.height }
+        };
+
+        if tab_rect.origin.x + width < tabs_rect.origin.x || tab_rect.origin.x > tabs_rect.origin.x + tabs_rect.size.width
+        {
+            continue;
+        }
+
+        if selected_tab == Some(content)
+        {
+            context.fill_rect(tab_rect, crate::widget::with_alpha(color, SELECTED_TAB_ALPHA));
+            context.fill_rect(Rect {
+                origin: Point { x: tab_rect.origin.x, y: tab_rect.origin.y + tab_rect.size.height - SELECTED_LINE_WIDTH },
+                size: Size { width: width, height: SELECTED_LINE_WIDTH }
+            }, color);
+        }
+
+        let (title, closable) = with_tab(view, content, |tab_object| (tab_object.title.clone(), tab_object.closable)).unwrap_or((String::from(""), false));
+
+        let close_width = if closable { CLOSE_BUTTON_SIZE + TAB_PADDING / 2.0 } else { 0.0 };
+        let text_size = crate::drawing::font::measure_text(&title, &font);
+
+        context.push_clip(Rect {
+            origin: tab_rect.origin,
+            size: Size { width: (width - TAB_PADDING - close_width).max(0.0), height: tab_rect.size.height }
+        });
+        context.draw_text(Point { x: tab_rect.origin.x + TAB_PADDING, y: tab_rect.origin.y + (tab_rect.size.height - text_size.height) / 2.0 }, &title, &font, color);
+        context.pop_clip();
+
+        if closable
+        {
+            let button = close_button_rect(tab_rect);
+            let (x0, y0) = (button.origin.x + CLOSE_CROSS_INSET, button.origin.y + CLOSE_CROSS_INSET);
+            let (x1, y1) = (button.origin.x + button.size.width - CLOSE_CROSS_INSET, button.origin.y + button.size.height - CLOSE_CROSS_INSET);
+
+            crate::widget::stroke_polyline(context, &[Point { x: x0, y: y0 }, Point { x: x1, y: y1 }], CLOSE_CROSS_WIDTH, color);
+            crate::widget::stroke_polyline(context, &[Point { x: x1, y: y0 }, Point { x: x0, y: y1 }], CLOSE_CROSS_WIDTH, color);
+        }
+
+        context.fill_rect(Rect {
+            origin: Point { x: tab_rect.origin.x + width - 1.0, y: tab_rect.origin.y + TAB_PADDING / 2.0 },
+            size: Size { width: 1.0, height: (tab_rect.size.height - TAB_PADDING).max(0.0) }
+        }, divider_color);
+    }
+
+    context.pop_clip();
+
+    if overflow
+    {
+        let center_y = strip_rect.origin.y + strip_rect.size.height / 2.0;
+        let half = SCROLL_ARROW_SIZE / 2.0;
+        let back_x = tabs_rect.origin.x + tabs_rect.size.width + SCROLL_BUTTON_WIDTH / 2.0;
+        let forward_x = back_x + SCROLL_BUTTON_WIDTH;
+
+        context.fill_polygon(&[
+            Point { x: back_x + half / 2.0, y: center_y - half },
+            Point { x: back_x + half / 2.0, y: center_y + half },
+            Point { x: back_x - half / 2.0, y: center_y }
+        ], color);
+
+        context.fill_polygon(&[
+            Point { x: forward_x - half / 2.0, y: center_y - half },
+            Point { x: forward_x - half / 2.0, y: center_y + half },
+            Point { x: forward_x + half / 2.0, y: center_y }
+        ], color);
+    }
+}
@@ -0,0 +1,226 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  toggle.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the Toggle switch widget.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect, debug::*};
+
+pub use crate::View;
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, MouseButton};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+pub type ToggleChangedHandler = Box<dyn FnMut(View, bool)>;
+
+struct ToggleObject
+{
+    pub text: String,
+    pub on: bool,
+    pub pressed: bool,
+
+    pub changed_handler: Option<ToggleChangedHandler>
+}
+
+struct ToggleState
+{
+    pub toggles: HashMap<View, ToggleObject>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static TOGGLE_STATE: RefCell<ToggleState> = RefCell::new(ToggleState {
+        toggles: HashMap::new()
+    });
+}
+
+const DEFAULT_TOGGLE_SIZE: Size = Size { width: 160.0, height: 24.0 };
+const TRACK_SIZE: Size = Size { width: 34.0, height: 18.0 };
+const KNOB_INSET: f32 = 2.0;
+const KNOB_COLOR: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+
+// alpha of the foreground color used for the track when the switch is off
+const OFF_TRACK_ALPHA: f32 = 0.3;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+
+    TOGGLE_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.toggles.insert(new_view, ToggleObject {
+            text: String::from(""),
+            on: false,
+            pressed: false,
+            changed_handler: None
+        });
+    });
+
+    crate::view::set_size(new_view, DEFAULT_TOGGLE_SIZE);
+    crate::view::set_focusable(new_view, true);
+    crate::view::set_render_handler(new_view, render);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::add_destroy_handler(new_view, forget);
+
+    return new_view;
+}
+
+pub fn set_text(view: View, text: &str)
+{
+    with_toggle(view, |toggle_object| toggle_object.text = String::from(text));
+}
+
+pub fn get_text(view: View) -> String
+{
+    return with_toggle(view, |toggle_object| toggle_object.text.clone()).unwrap_or(String::from(""));
+}
+
+// switching from code does not call the changed handler
+pub fn set_on(view: View, on: bool)
+{
+    with_toggle(view, |toggle_object| toggle_object.on = on);
+}
+
+pub fn is_on(view: View) -> bool
+{
+    return with_toggle(view, |toggle_object| toggle_object.on).unwrap_or(false);
+}
+
+// called whenever the user switches the toggle by pointer or keyboard
+pub fn set_changed_handler<F: FnMut(View, bool) + 'static>(view: View, handler: F)
+{
+    with_toggle(view, |toggle_object| toggle_object.changed_handler = Some(Box::new(handler)));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    TOGGLE_STATE.with(|state| state.borrow_mut().toggles.remove(&view));
+}
+
+fn with_toggle<T, F: FnOnce(&mut ToggleObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return TOGGLE_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(toggle_object) = state.toggles.get_mut(&view)
+        {
+            return Some(action(toggle_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Toggle not found!"));
+            return None;
+        }
+    });
+}
+
+fn switch_to(view: View, on: bool)
+{
+    if is_on(view) == on
+    {
+        return;
+    }
+
+    set_on(view, on);
+
+    crate::widget::call_taken(|slot| with_toggle(view, |toggle_object| slot(&mut toggle_object.changed_handler)), |handler| handler(view, on));
+}
+
+fn handle_event(view: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { button: MouseButton::Left, .. } =>
+        {
+            with_toggle(view, |toggle_object| toggle_object.pressed = true);
+            return true;
+        },
+        Event::MouseUp { point, button: MouseButton::Left, .. } =>
+        {
+            let was_pressed = with_toggle(view, |toggle_object| std::mem::replace(&mut toggle_object.pressed, false)).unwrap_or(false);
+
+            if was_pressed && crate::view::rect_contains(crate::view::get_rect(view), *point)
+            {
+                switch_to(view, !is_on(view));
+            }
+
+            return true;
+        },
+        Event::KeyDown { key: Key::Space, .. } =>
+        {
+            switch_to(view, !is_on(view));
+            return true;
+        },
+        Event::KeyDown { key: Key::Left, .. } =>
+        {
+            switch_to(view, false);
+            return true;
+        },
+        Event::KeyDown { key: Key::Right, .. } =>
+        {
+            switch_to(view, true);
+            return true;
+        },
+        _ => return false
+    }
+}
+
+fn render(view: View, context: &mut DrawingContext)
+{
+    let (text, on) = match with_toggle(view, |toggle_object| (toggle_object.text.clone(), toggle_object.on))
+    {
+        Some(toggle_properties) => toggle_properties,
+        None => return
+    };
+
+    let color = crate::view::get_foreground_color(view);
+    let track_rect = crate::widget::indicator_rect(view, TRACK_SIZE);
+    let radius = TRACK_SIZE.height / 2.0;
+
+    crate::widget::draw_focus_ring(view, context, track_rect, radius);
+
+    let track_color = if on { color } else { crate::widget::with_alpha(color, OFF_TRACK_ALPHA) };
+    context.fill_rounded_rect(track_rect, radius, track_color);
+
+    // the knob sits at the trailing end of the track when the switch is on
+    let knob_size = TRACK_SIZE.height - 2.0 * KNOB_INSET;
+    let knob_x = if on { track_rect.origin.x + track_rect.size.width - KNOB_INSET - knob_size } else { track_rect.origin.x + KNOB_INSET };
+
+    context.fill_ellipse(Rect {
+        origin: Point { x: knob_x, y: track_rect.origin.y + KNOB_INSET },
+        size: Size { width: knob_size, height: knob_size }
+    }, KNOB_COLOR);
+
+    let text_x = track_rect.origin.x + track_rect.size.width + crate::widget::INDICATOR_SPACING;
+    crate::widget::draw_view_text(view, context, text_x, &text);
+}
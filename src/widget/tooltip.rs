@@ -0,0 +1,402 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  tooltip.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the tooltip popup that windows show for
+**                 views with a tooltip after a hover delay.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Color, Rect, debug::*};
+
+pub use crate::{View, Window, Timer};
+
+use crate::drawing::DrawingContext;
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+// each window has one tooltip popup, made when the pointer first rests on a view with a tooltip
+struct TooltipObject
+{
+    pub popup: View,
+
+    // the view whose tooltip is pending or shown, the pointer is over it or one of its children
+    pub owner: Option<View>,
+    pub hover_start: Instant,
+
+    // fires when the hover delay runs out, so a window that is not drawing frames renders the tooltip
+    pub delay_timer: Option<Timer>,
+    pub point: Point,
+
+    pub text: String,
+    pub visible: bool,
+
+    // a click hides the tooltip until the pointer moves on to another view
+    pub suppressed: bool
+}
+
+struct TooltipState
+{
+    pub tooltips: HashMap<Window, TooltipObject>,
+
+    // windows without an entry use the default delay
+    pub delays: HashMap<Window, Duration>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static TOOLTIP_STATE: RefCell<TooltipState> = RefCell::new(TooltipState {
+        tooltips: HashMap::new(),
+        delays: HashMap::new()
+    });
+}
+
+const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+
+const FONT_SIZE: f32 = 12.0;
+const HORIZONTAL_PADDING: f32 = 6.0;
+const VERTICAL_PADDING: f32 = 4.0;
+const CORNER_RADIUS: f32 = 3.0;
+
+// the tooltip sits this far below the pointer so it does not cover what is being pointed at
+const POINTER_OFFSET: f32 = 20.0;
+const WINDOW_MARGIN: f32 = 4.0;
+
+const BACKGROUND_COLOR: Color = Color { r: 0.2, g: 0.2, b: 0.2, a: 0.95 };
+const TEXT_COLOR: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+// how long the pointer has to rest on a view before its tooltip shows
+pub fn set_delay(window: Window, delay: Duration)
+{
+    TOOLTIP_STATE.with(|state| {
+        state.borrow_mut().delays.insert(window, delay);
+    });
+}
+
+pub fn get_delay(window: Window) -> Duration
+{
+    return TOOLTIP_STATE.with(|state| state.borrow().delays.get(&window).copied().unwrap_or(DEFAULT_DELAY));
+}
+
+pub fn is_visible(window: Window) -> bool
+{
+    return TOOLTIP_STATE.with(|state| {
+        return state.borrow().tooltips.get(&window).is_some_and(|tooltip_object| tooltip_object.visible);
+    });
+}
+
+// the text of the tooltip on screen, if one is showing
+pub fn get_visible_text(window: Window) -> Option<String>
+{
+    return TOOLTIP_STATE.with(|state| {
+        return state.borrow().tooltips.get(&window)
+            .filter(|tooltip_object| tooltip_object.visible)
+            .map(|tooltip_object| tooltip_object.text.clone());
+    });
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+// nearest view up from the target that has a tooltip
+fn find_owner(target: Option<View>) -> Option<View>
+{
+    let mut current = target;
+
+    while let Some(view) = current
+    {
+        if crate::view::get_tooltip(view).is_some()
+        {
+            return Some(view);
+        }
+
+        current = crate::view::get_parent(view);
+    }
+
+    return None;
+}
+
+fn remove_popup(window: Window, popup: View)
+{
+    if crate::window::has_overlay(window, popup)
+    {
+        crate::window::remove_overlay(window, popup);
+    }
+}
+
+// sizes the tooltip to its text and keeps it inside the window, flipping above the pointer at the bottom edge
+fn popup_rect(window: Window, popup: View, point: Point, text: &str) -> Rect
+{
+    let font = crate::view::get_font(popup);
+    let metrics = crate::drawing::font::get_metrics(&font);
+
+    let lines: Vec<&str> = text.lines().collect();
+    let text_width = lines.iter().map(|line| crate::drawing::font::measure_text(line, &font).width).fold(0.0, f32::max);
+
+    let size = Size {
+        width: (text_width + HORIZONTAL_PADDING * 2.0).ceil(),
+        height: (lines.len().max(1) as f32 * metrics.line_height + VERTICAL_PADDING * 2.0).ceil()
+    };
+
+    let window_size = crate::window::get_size(window);
+
+    let x = point.x.min(window_size.width - WINDOW_MARGIN - size.width);
+    let mut y = point.y + POINTER_OFFSET;
+
+    if y + size.height > window_size.height - WINDOW_MARGIN
+    {
+        y = point.y - WINDOW_MARGIN - size.height;
+    }
+
+    return Rect { origin: Point { x: x.max(0.0), y: y.max(0.0) }, size: size };
+}
+
+fn render(popup: View, context: &mut DrawingContext)
+{
+    let text = TOOLTIP_STATE.with(|state| {
+        return state.borrow().tooltips.values()
+            .find(|tooltip_object| tooltip_object.popup == popup)
+            .map(|tooltip_object| tooltip_object.text.clone());
+    });
+
+    let text = match text
+    {
+        Some(text) => text,
+        None => return
+    };
+
+    let rect = crate::view::get_rect(popup);
+    let font = crate::view::get_font(popup);
+    let metrics = crate::drawing::font::get_metrics(&font);
+
+    context.fill_rounded_rect(rect, CORNER_RADIUS, BACKGROUND_COLOR);
+
+    for (index, line) in text.lines().enumerate()
+    {
+        let origin = Point { x: rect.origin.x + HORIZONTAL_PADDING, y: rect.origin.y + VERTICAL_PADDING + index as f32 * metrics.line_height };
+        context.draw_text(origin, line, &font, TEXT_COLOR);
+    }
+}
+
+// restarts the hover delay whenever the pointer moves on to a view with a different tooltip owner
+pub(crate) fn pointer_moved(window: Window, target: Option<View>, point: Point)
+{
+    let owner = find_owner(target);
+
+    let changed = TOOLTIP_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let tooltip_object = match state.tooltips.get_mut(&window)
+        {
+            Some(tooltip_object) => tooltip_object,
+            None =>
+            {
+                // the popup is only made once there is a tooltip to show
+                owner?;
+
+                let popup = crate::view::create();
+
+                crate::view::set_font_size(popup, FONT_SIZE);
+                crate::view::set_render_handler(popup, render);
+
+                state.tooltips.entry(window).or_insert(TooltipObject {
+                    popup: popup,
+                    owner: None,
+                    hover_start: crate::app::clock::now(),
+                    delay_timer: None,
+                    point: point,
+                    text: String::from(""),
+                    visible: false,
+                    suppressed: false
+                })
+            }
+        };
+
+        if tooltip_object.owner == owner
+        {
+            // the tooltip follows the pointer until it appears, then stays put
+            if !tooltip_object.visible
+            {
+                tooltip_object.point = point;
+            }
+
+            return None;
+        }
+
+        tooltip_object.owner = owner;
+        tooltip_object.hover_start = crate::app::clock::now();
+        tooltip_object.point = point;
+        tooltip_object.suppressed = false;
+
+        let was_visible = tooltip_object.visible;
+        tooltip_object.visible = false;
+
+        return Some((if was_visible { Some(tooltip_object.popup) } else { None }, tooltip_object.delay_timer.take()));
+    });
+
+    let (hidden_popup, old_timer) = match changed
+    {
+        Some(changed) => changed,
+        None => return
+    };
+
+    if let Some(popup) = hidden_popup
+    {
+        remove_popup(window, popup);
+    }
+
+    if let Some(old_timer) = old_timer
+    {
+        crate::app::cancel(old_timer);
+    }
+
+    if owner.is_some()
+    {
+        let delay_timer = crate::app::set_timeout(get_delay(window), move || {
+            TOOLTIP_STATE.with(|state| {
+                if let Some(tooltip_object) = state.borrow_mut().tooltips.get_mut(&window)
+                {
+                    tooltip_object.delay_timer = None;
+                }
+            });
+
+            crate::window::request_redraw(window);
+        });
+
+        TOOLTIP_STATE.with(|state| {
+            if let Some(tooltip_object) = state.borrow_mut().tooltips.get_mut(&window)
+            {
+                tooltip_object.delay_timer = Some(delay_timer);
+            }
+        });
+    }
+}
+
+// drops the window's tooltip along with its popup, when the window or its content goes away
+pub(crate) fn forget_window(window: Window)
+{
+    let tooltip_object = TOOLTIP_STATE.with(|state| state.borrow_mut().tooltips.remove(&window));
+
+    if let Some(tooltip_object) = tooltip_object
+    {
+        if let Some(delay_timer) = tooltip_object.delay_timer
+        {
+            crate::app::cancel(delay_timer);
+        }
+
+        crate::view::destroy(tooltip_object.popup);
+    }
+}
+
+// hides the tooltip until the pointer moves on to another view
+pub(crate) fn hide(window: Window)
+{
+    let hidden_popup = TOOLTIP_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let tooltip_object = state.tooltips.get_mut(&window)?;
+
+        tooltip_object.suppressed = true;
+
+        let was_visible = tooltip_object.visible;
+        tooltip_object.visible = false;
+
+        return if was_visible { Some(tooltip_object.popup) } else { None };
+    });
+
+    if let Some(popup) = hidden_popup
+    {
+        remove_popup(window, popup);
+    }
+}
+
+// shows the pending tooltip once its delay has passed, and follows changes to the owner's text
+pub(crate) fn update(window: Window)
+{
+    let delay = get_delay(window);
+
+    let pending = TOOLTIP_STATE.with(|state| {
+        let state = state.borrow();
+
+        let tooltip_object = state.tooltips.get(&window)?;
+
+        return Some((tooltip_object.popup, tooltip_object.owner, tooltip_object.visible, tooltip_object.suppressed,
+            crate::app::clock::now().saturating_duration_since(tooltip_object.hover_start) >= delay, tooltip_object.point, tooltip_object.text.clone()));
+    });
+
+    let (popup, owner, visible, suppressed, delay_passed, point, shown_text) = match pending
+    {
+        Some(pending) => pending,
+        None => return
+    };
+
+    let text = owner.and_then(crate::view::get_tooltip).filter(|text| !text.is_empty());
+
+    let text = match text
+    {
+        Some(text) if !suppressed && (visible || delay_passed) => text,
+        _ =>
+        {
+            if visible
+            {
+                TOOLTIP_STATE.with(|state| {
+                    if let Some(tooltip_object) = state.borrow_mut().tooltips.get_mut(&window)
+                    {
+                        tooltip_object.visible = false;
+                    }
+                });
+
+                remove_popup(window, popup);
+            }
+
+            return;
+        }
+    };
+
+    if visible && text == shown_text
+    {
+        return;
+    }
+
+    TOOLTIP_STATE.with(|state| {
+        if let Some(tooltip_object) = state.borrow_mut().tooltips.get_mut(&window)
+        {
+            tooltip_object.text = text.clone();
+            tooltip_object.visible = true;
+        }
+    });
+
+    let rect = popup_rect(window, popup, point, &text);
+
+    if visible
+    {
+        crate::window::set_overlay_rect(window, popup, rect);
+    }
+    else
+    {
+        crate::window::add_passive_overlay(window, popup, rect);
+    }
+}
@@ -0,0 +1,741 @@
+/***************************************************************
+**
+** NanoKit Library Source File
+**
+** File         :  tree_view.rs
+** Module       :  widget
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Contains the TreeView widget, which shows the
+**                 expanded part of a tree through a ListView.
+**
+***************************************************************/
+
+/***************************************************************
+* MARK: EXTERNAL MODULES
+***************************************************************/
+
+pub use nanowin::{Size, Point, Rect, debug::*};
+
+pub use crate::View;
+
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, MouseButton};
+use crate::widget::list_view::{self, SelectionMode};
+
+use std::collections::HashMap;
+use std::cell::RefCell;
+
+/***************************************************************
+* MARK: TYPE DEFINITIONS
+***************************************************************/
+
+pub type TreeNode = usize;
+
+// called the first time a node marked as having children is expanded, it adds the children
+pub type TreeLoadChildrenHandler = Box<dyn FnMut(View, TreeNode)>;
+
+pub type TreeExpansionChangedHandler = Box<dyn FnMut(View, TreeNode, bool)>;
+pub type TreeSelectionChangedHandler = Box<dyn FnMut(View, Option<TreeNode>)>;
+
+struct TreeNodeObject
+{
+    pub text: String,
+    pub parent: Option<TreeNode>,
+    pub children: Vec<TreeNode>,
+    pub expanded: bool,
+
+    // lazily loaded nodes show a disclosure indicator before their children exist
+    pub has_children: bool,
+    pub children_loaded: bool
+}
+
+struct TreeViewObject
+{
+    // the rows are shown by a list view filling the tree view
+    pub list: View,
+
+    pub nodes: HashMap<TreeNode, TreeNodeObject>,
+    pub root_nodes: Vec<TreeNode>,
+
+    // the expanded part of the tree in display order, with the depth of each node
+    pub visible_nodes: Vec<(TreeNode, usize)>,
+
+    pub selected_node: Option<TreeNode>,
+
+    // changes made between begin_update and end_update are shown together at the end
+    pub update_depth: usize,
+    pub needs_refresh: bool,
+
+    pub load_children_handler: Option<TreeLoadChildrenHandler>,
+    pub expansion_changed_handler: Option<TreeExpansionChangedHandler>,
+    pub selection_changed_handler: Option<TreeSelectionChangedHandler>
+}
+
+struct TreeViewState
+{
+    pub node_id_allocator: TreeNode,
+    pub tree_views: HashMap<View, TreeViewObject>,
+
+    // each row view shows the visible node at an index of its tree
+    pub rows: HashMap<View, (View, usize)>
+}
+
+/***************************************************************
+* MARK: STATIC VARIABLES & CONSTANTS
+***************************************************************/
+
+thread_local! {
+    static TREE_VIEW_STATE: RefCell<TreeViewState> = RefCell::new(TreeViewState {
+        node_id_allocator: 1,
+        tree_views: HashMap::new(),
+        rows: HashMap::new()
+    });
+}
+
+const DEFAULT_TREE_VIEW_SIZE: Size = Size { width: 200.0, height: 240.0 };
+
+const INDENT_WIDTH: f32 = 16.0;
+const ROW_PADDING: f32 = 4.0;
+const DISCLOSURE_SIZE: f32 = 16.0;
+const DISCLOSURE_ARROW_SIZE: f32 = 8.0;
+
+/***************************************************************
+* MARK: PUBLIC FUNCTIONS
+***************************************************************/
+
+pub fn create() -> View
+{
+    let new_view = crate::view::create();
+    let list = list_view::create();
+
+    TREE_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        state.tree_views.insert(new_view, TreeViewObject {
+            list: list,
+            nodes: HashMap::new(),
+            root_nodes: Vec::new(),
+            visible_nodes: Vec::new(),
+            selected_node: None,
+            update_depth: 0,
+            needs_refresh: false,
+            load_children_handler: None,
+            expansion_changed_handler: None,
+            selection_changed_handler: None
+        });
+    });
+
+    crate::view::set_size(new_view, DEFAULT_TREE_VIEW_SIZE);
+    crate::view::set_event_handler(new_view, handle_event);
+    crate::view::add_destroy_handler(new_view, forget);
+    crate::view::add_child(new_view, list);
+
+    list_view::set_selection_mode(list, SelectionMode::Single);
+    list_view::set_row_builder(list, move |_, index| create_row(new_view, index));
+    list_view::set_row_recycler(list, move |_, index, row| bind_row(new_view, row, index));
+    list_view::set_selection_changed_handler(list, move |_, _| list_selection_changed(new_view));
+
+    return new_view;
+}
+
+// adds a collapsed node at the end of the parent's children, or of the top level without a parent
+pub fn add_node(view: View, parent: Option<TreeNode>, text: &str) -> Option<TreeNode>
+{
+    let new_node = TREE_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let new_node = state.node_id_allocator;
+
+        if let Some(tree_view_object) = state.tree_views.get_mut(&view)
+        {
+            match parent
+            {
+                Some(parent) =>
+                {
+                    if let Some(parent_object) = tree_view_object.nodes.get_mut(&parent)
+                    {
+                        parent_object.children.push(new_node);
+                        parent_object.has_children = true;
+                        parent_object.children_loaded = true;
+                    }
+                    else
+                    {
+                        log(LogLevel::FAIL, &format!("Tree node not found!"));
+                        return None;
+                    }
+                },
+                None => tree_view_object.root_nodes.push(new_node)
+            }
+
+            tree_view_object.nodes.insert(new_node, TreeNodeObject {
+                text: String::from(text),
+                parent: parent,
+                children: Vec::new(),
+                expanded: false,
+                has_children: false,
+                children_loaded: false
+            });
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Tree view not found!"));
+            return None;
+        }
+
+        state.node_id_allocator += 1;
+
+        return Some(new_node);
+    });
+
+    if new_node.is_some()
+    {
+        refresh(view);
+    }
+
+    return new_node;
+}
+
+// removes the node together with all of its descendants
+pub fn remove_node(view: View, node: TreeNode)
+{
+    with_tree_view(view, |tree_view_object| {
+        let parent = match tree_view_object.nodes.get(&node)
+        {
+            Some(node_object) => node_object.parent,
+            None =>
+            {
+                log(LogLevel::FAIL, &format!("Tree node not found!"));
+                return;
+            }
+        };
+
+        match parent.and_then(|parent| tree_view_object.nodes.get_mut(&parent))
+        {
+            Some(parent_object) => parent_object.children.retain(|child| *child != node),
+            None => tree_view_object.root_nodes.retain(|root_node| *root_node != node)
+        }
+
+        let mut removed = vec![node];
+
+        while let Some(removed_node) = removed.pop()
+        {
+            if let Some(node_object) = tree_view_object.nodes.remove(&removed_node)
+            {
+                removed.extend(node_object.children);
+            }
+        }
+    });
+
+    refresh(view);
+}
+
+pub fn clear(view: View)
+{
+    with_tree_view(view, |tree_view_object| {
+        tree_view_object.nodes.clear();
+        tree_view_object.root_nodes.clear();
+    });
+
+    refresh(view);
+}
+
+pub fn set_node_text(view: View, node: TreeNode, text: &str)
+{
+    with_node(view, node, |node_object| node_object.text = String::from(text));
+}
+
+pub fn get_node_text(view: View, node: TreeNode) -> String
+{
+    return with_node(view, node, |node_object| node_object.text.clone()).unwrap_or(String::from(""));
+}
+
+pub fn get_node_parent(view: View, node: TreeNode) -> Option<TreeNode>
+{
+    return with_node(view, node, |node_object| node_object.parent).flatten();
+}
+
+pub fn get_node_children(view: View, node: TreeNode) -> Vec<TreeNode>
+{
+    return with_node(view, node, |node_object| node_object.children.clone()).unwrap_or(Vec::new());
+}
+
+pub fn get_root_nodes(view: View) -> Vec<TreeNode>
+{
+    return with_tree_view(view, |tree_view_object| tree_view_object.root_nodes.clone()).unwrap_or(Vec::new());
+}
+
+// marks a node as expandable before its children are added, they are asked for on first expand
+pub fn set_node_has_children(view: View, node: TreeNode, has_children: bool)
+{
+    with_node(view, node, |node_object| node_object.has_children = has_children || !node_object.children.is_empty());
+}
+
+// expanding from code loads lazy children but does not call the expansion changed handler
+pub fn set_expanded(view: View, node: TreeNode, expanded: bool)
+{
+    if expanded
+    {
+        load_children(view, node);
+    }
+
+    with_node(view, node, |node_object| node_object.expanded = expanded);
+
+    refresh(view);
+}
+
+pub fn is_expanded(view: View, node: TreeNode) -> bool
+{
+    return with_node(view, node, |node_object| node_object.expanded).unwrap_or(false);
+}
+
+// selecting from code expands the ancestors so the node can be seen, but does not call the handler
+pub fn set_selected_node(view: View, node: Option<TreeNode>)
+{
+    if let Some(node) = node
+    {
+        let mut ancestor = get_node_parent(view, node);
+
+        while let Some(parent) = ancestor
+        {
+            load_children(view, parent);
+            with_node(view, parent, |node_object| node_object.expanded = true);
+            ancestor = get_node_parent(view, parent);
+        }
+    }
+
+    with_tree_view(view, |tree_view_object| tree_view_object.selected_node = node);
+
+    refresh(view);
+
+    if let (Some(node), Some(list)) = (node, get_list(view))
+    {
+        if let Some(index) = visible_index(view, node)
+        {
+            list_view::scroll_to_index(list, index);
+        }
+    }
+}
+
+pub fn get_selected_node(view: View) -> Option<TreeNode>
+{
+    return with_tree_view(view, |tree_view_object| tree_view_object.selected_node).flatten();
+}
+
+// defers showing changes to the tree until the matching end_update, for adding many nodes at once
+pub fn begin_update(view: View)
+{
+    with_tree_view(view, |tree_view_object| tree_view_object.update_depth += 1);
+}
+
+pub fn end_update(view: View)
+{
+    let done = with_tree_view(view, |tree_view_object| {
+        tree_view_object.update_depth = tree_view_object.update_depth.saturating_sub(1);
+        return tree_view_object.update_depth == 0 && tree_view_object.needs_refresh;
+    }).unwrap_or(false);
+
+    if done
+    {
+        refresh(view);
+    }
+}
+
+pub fn set_load_children_handler<F: FnMut(View, TreeNode) + 'static>(view: View, handler: F)
+{
+    with_tree_view(view, |tree_view_object| tree_view_object.load_children_handler = Some(Box::new(handler)));
+}
+
+// called whenever the user expands or collapses a node
+pub fn set_expansion_changed_handler<F: FnMut(View, TreeNode, bool) + 'static>(view: View, handler: F)
+{
+    with_tree_view(view, |tree_view_object| tree_view_object.expansion_changed_handler = Some(Box::new(handler)));
+}
+
+// called whenever the user selects a node, or the selection moves because its node was hidden
+pub fn set_selection_changed_handler<F: FnMut(View, Option<TreeNode>) + 'static>(view: View, handler: F)
+{
+    with_tree_view(view, |tree_view_object| tree_view_object.selection_changed_handler = Some(Box::new(handler)));
+}
+
+/***************************************************************
+* MARK: LOCAL FUNCTIONS
+***************************************************************/
+
+fn forget(view: View)
+{
+    TREE_VIEW_STATE.with(|state| state.borrow_mut().tree_views.remove(&view));
+}
+
+fn forget_row(row: View)
+{
+    TREE_VIEW_STATE.with(|state| state.borrow_mut().rows.remove(&row));
+}
+
+fn with_tree_view<T, F: FnOnce(&mut TreeViewObject) -> T>(view: View, action: F) -> Option<T>
+{
+    return TREE_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(tree_view_object) = state.tree_views.get_mut(&view)
+        {
+            return Some(action(tree_view_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Tree view not found!"));
+            return None;
+        }
+    });
+}
+
+fn with_node<T, F: FnOnce(&mut TreeNodeObject) -> T>(view: View, node: TreeNode, action: F) -> Option<T>
+{
+    return with_tree_view(view, |tree_view_object| {
+        if let Some(node_object) = tree_view_object.nodes.get_mut(&node)
+        {
+            return Some(action(node_object));
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Tree node not found!"));
+            return None;
+        }
+    }).flatten();
+}
+
+fn get_list(view: View) -> Option<View>
+{
+    return with_tree_view(view, |tree_view_object| tree_view_object.list);
+}
+
+fn visible_index(view: View, node: TreeNode) -> Option<usize>
+{
+    return with_tree_view(view, |tree_view_object| {
+        return tree_view_object.visible_nodes.iter().position(|(visible_node, _)| *visible_node == node);
+    }).flatten();
+}
+
+fn is_expandable(node_object: &TreeNodeObject) -> bool
+{
+    return !node_object.children.is_empty() || (node_object.has_children && !node_object.children_loaded);
+}
+
+// the handler can add nodes through this module while it runs
+fn load_children(view: View, node: TreeNode)
+{
+    let needs_loading = with_node(view, node, |node_object| {
+        let needs_loading = node_object.has_children && !node_object.children_loaded;
+        node_object.children_loaded = true;
+        return needs_loading;
+    }).unwrap_or(false);
+
+    if !needs_loading
+    {
+        return;
+    }
+
+    crate::widget::call_taken(|slot| with_tree_view(view, |tree_view_object| slot(&mut tree_view_object.load_children_handler)), |handler| {
+        begin_update(view);
+        handler(view, node);
+        end_update(view);
+    });
+}
+
+// the user expanded or collapsed a node
+fn toggle_expanded(view: View, node: TreeNode, expanded: bool)
+{
+    if is_expanded(view, node) == expanded
+    {
+        return;
+    }
+
+    set_expanded(view, node, expanded);
+
+    crate::widget::call_taken(|slot| with_tree_view(view, |tree_view_object| slot(&mut tree_view_object.expansion_changed_handler)), |handler| handler(view, node, expanded));
+}
+
+// flattens the expanded part of the tree into the list, a selected node that was hidden passes
+// the selection to its nearest visible ancestor
+fn refresh(view: View)
+{
+    let refreshed = with_tree_view(view, |tree_view_object| {
+        if tree_view_object.update_depth > 0
+        {
+            tree_view_object.needs_refresh = true;
+            return None;
+        }
+
+        tree_view_object.needs_refresh = false;
+
+        let mut visible_nodes: Vec<(TreeNode, usize)> = Vec::new();
+        let mut pending: Vec<(TreeNode, usize)> = tree_view_object.root_nodes.iter().rev().map(|node| (*node, 0)).collect();
+
+        while let Some((node, depth)) = pending.pop()
+        {
+            visible_nodes.push((node, depth));
+
+            if let Some(node_object) = tree_view_object.nodes.get(&node)
+            {
+                if node_object.expanded
+                {
+                    pending.extend(node_object.children.iter().rev().map(|child| (*child, depth + 1)));
+                }
+            }
+        }
+
+        let previous_selection = tree_view_object.selected_node;
+        let mut selected_node = previous_selection.filter(|node| tree_view_object.nodes.contains_key(node));
+
+        while let Some(node) = selected_node
+        {
+            if visible_nodes.iter().any(|(visible_node, _)| *visible_node == node)
+            {
+                break;
+            }
+
+            selected_node = tree_view_object.nodes.get(&node).and_then(|node_object| node_object.parent);
+        }
+
+        let selected_index = selected_node.and_then(|node| visible_nodes.iter().position(|(visible_node, _)| *visible_node == node));
+
+        tree_view_object.selected_node = selected_node;
+        tree_view_object.visible_nodes = visible_nodes;
+
+        return Some((tree_view_object.list, tree_view_object.visible_nodes.len(), selected_index, previous_selection != selected_node));
+    }).flatten();
+
+    if let Some((list, count, selected_index, selection_changed)) = refreshed
+    {
+        list_view::set_item_count(list, count);
+        list_view::set_selected_indices(list, &selected_index.into_iter().collect::<Vec<usize>>());
+
+        if selection_changed
+        {
+            notify_selection_changed(view);
+        }
+    }
+}
+
+fn notify_selection_changed(view: View)
+{
+    crate::widget::call_taken(|slot| with_tree_view(view, |tree_view_object| slot(&mut tree_view_object.selection_changed_handler)), |handler| handler(view, get_selected_node(view)));
+}
+
+fn list_selection_changed(view: View)
+{
+    let list = match get_list(view)
+    {
+        Some(list) => list,
+        None => return
+    };
+
+    let selected_index = list_view::get_selected_index(list);
+
+    let changed = with_tree_view(view, |tree_view_object| {
+        let selected_node = selected_index.and_then(|index| tree_view_object.visible_nodes.get(index)).map(|(node, _)| *node);
+        let changed = selected_node != tree_view_object.selected_node;
+        tree_view_object.selected_node = selected_node;
+        return changed;
+    }).unwrap_or(false);
+
+    if changed
+    {
+        notify_selection_changed(view);
+    }
+}
+
+// moves the selection to a node the user navigated to with the keyboard
+fn select_by_user(view: View, node: TreeNode)
+{
+    if get_selected_node(view) == Some(node)
+    {
+        return;
+    }
+
+    set_selected_node(view, Some(node));
+    notify_selection_changed(view);
+}
+
+fn create_row(view: View, index: usize) -> View
+{
+    let row = crate::view::create();
+
+    crate::view::set_foreground_color(row, crate::view::get_foreground_color(view));
+
+    let font = crate::view::get_font(view);
+    crate::view::set_font_family(row, &font.family);
+    crate::view::set_font_size(row, font.size);
+    crate::view::set_font_weight(row, font.weight);
+    crate::view::set_font_italic(row, font.italic);
+
+    crate::view::set_render_handler(row, render_row);
+    crate::view::set_event_handler(row, handle_row_event);
+    crate::view::add_destroy_handler(row, forget_row);
+
+    return bind_row(view, row, index);
+}
+
+fn bind_row(view: View, row: View, index: usize) -> View
+{
+    TREE_VIEW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.rows.insert(row, (view, index));
+    });
+
+    return row;
+}
+
+// returns the tree, node and depth shown by a row
+fn get_row_node(row: View) -> Option<(View, TreeNode, usize)>
+{
+    return TREE_VIEW_STATE.with(|state| {
+        let state = state.borrow();
+
+        let (view, index) = *state.rows.get(&row)?;
+        let (node, depth) = *state.tree_views.get(&view)?.visible_nodes.get(index)?;
+
+        return Some((view, node, depth));
+    });
+}
+
+fn disclosure_rect(row: View, depth: usize) -> Rect
+{
+    let rect = crate::view::get_rect(row);
+
+    return Rect {
+        origin: Point { x: rect.origin.x + ROW_PADDING + depth as f32 * INDENT_WIDTH, y: rect.origin.y + (rect.size.height - DISCLOSURE_SIZE) / 2.0 },
+        size: Size { width: DISCLOSURE_SIZE, height: DISCLOSURE_SIZE }
+    };
+}
+
+// a press on the disclosure indicator toggles the node, elsewhere the list selects the row
+fn handle_row_event(row: View, event: &Event) -> bool
+{
+    match event
+    {
+        Event::MouseDown { point, button: MouseButton::Left, .. } =>
+        {
+            let (view, node, depth) = match get_row_node(row)
+            {
+                Some(row_node) => row_node,
+                None => return false
+            };
+
+            let expandable = with_node(view, node, |node_object| is_expandable(node_object)).unwrap_or(false);
+
+            if expandable && crate::view::rect_contains(disclosure_rect(row, depth), *point)
+            {
+                toggle_expanded(view, node, !is_expanded(view, node));
+                return true;
+            }
+
+            return false;
+        },
+        _ => return false
+    }
+}
+
+// left and right arrive here after the list passed them up
+fn handle_event(view: View, event: &Event) -> bool
+{
+    let node = match get_selected_node(view)
+    {
+        Some(node) => node,
+        None => return false
+    };
+
+    let (expandable, expanded, parent, first_child) = match with_node(view, node, |node_object| {
+        (is_expandable(node_object), node_object.expanded, node_object.parent, node_object.children.first().cloned())
+    })
+    {
+        Some(node_properties) => node_properties,
+        None => return false
+    };
+
+    match event
+    {
+        Event::KeyDown { key: Key::Right, .. } =>
+        {
+            if expandable && !expanded
+            {
+                toggle_expanded(view, node, true);
+            }
+            else if let Some(first_child) = first_child.filter(|_| expanded)
+            {
+                select_by_user(view, first_child);
+            }
+
+            return true;
+        },
+        Event::KeyDown { key: Key::Left, .. } =>
+        {
+            if expandable && expanded
+            {
+                toggle_expanded(view, node, false);
+            }
+            else if let Some(parent) = parent
+            {
+                select_by_user(view, parent);
+            }
+
+            return true;
+        },
+        Event::KeyDown { key: Key::Enter, .. } =>
+        {
+            if expandable
+            {
+                toggle_expanded(view, node, !expanded);
+                return true;
+            }
+
+            return false;
+        },
+        _ => return false
+    }
+}
+
+fn render_row(row: View, context: &mut DrawingContext)
+{
+    let (view, node, depth) = match get_row_node(row)
+    {
+        Some(row_node) => row_node,
+        None => return
+    };
+
+    let (text, expandable, expanded) = match with_node(view, node, |node_object| (node_object.text.clone(), is_expandable(node_object), node_object.expanded))
+    {
+        Some(node_properties) => node_properties,
+        None => return
+    };
+
+    let disclosure = disclosure_rect(row, depth);
+
+    if expandable
+    {
+        let color = crate::view::get_foreground_color(row);
+        let center = Point { x: disclosure.origin.x + disclosure.size.width / 2.0, y: disclosure.origin.y + disclosure.size.height / 2.0 };
+        let half = DISCLOSURE_ARROW_SIZE / 2.0;
+
+        // expanded nodes point down, collapsed nodes point towards their content
+        if expanded
+        {
+            context.fill_polygon(&[
+                Point { x: center.x - half, y: center.y - half / 2.0 },
+                Point { x: center.x + half, y: center.y - half / 2.0 },
+                Point { x: center.x, y: center.y + half / 2.0 }
+            ], color);
+        }
+        else
+        {
+            context.fill_polygon(&[
+                Point { x: center.x - half / 2.0, y: center.y - half },
+                Point { x: center.x + half / 2.0, y: center.y },
+                Point { x: center.x - half / 2.0, y: center.y + half }
+            ], color);
+        }
+    }
+
+    crate::widget::draw_view_text(row, context, disclosure.origin.x + disclosure.size.width + ROW_PADDING, &text);
+}
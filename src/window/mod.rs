@@ -20,6 +20,9 @@ pub use nanowin::{Size, Point, Rect, debug::*};
 pub use crate::Window;
 pub use crate::View;
 
+use crate::drawing::DrawingContext;
+use crate::event::{Event, Key, Modifiers, MouseButton};
+
 use std::collections::HashMap;
 use std::cell::RefCell;
 
@@ -41,6 +44,22 @@ pub trait WindowDelegate
     fn window_mouse_clicked(&mut self, window: Window, point: Point);
 }
 
+// views floating above the root view, such as popups, laid out at a fixed rect
+struct OverlayObject
+{
+    pub view: View,
+    pub rect: Rect,
+
+    // light dismiss overlays close when the user clicks anywhere outside them
+    pub light_dismiss: bool,
+
+    // passive overlays, like tooltips, are drawn but never receive pointer input
+    pub passive: bool,
+
+    // modal overlays cover the whole window and keep keyboard focus inside the topmost one
+    pub modal: bool
+}
+
 struct WindowObject
 {
     pub id: Window,
@@ -51,7 +70,12 @@ struct WindowObject
 
     pub system_window: nanowin::Window,
 
-    pub root_view: Option<View>
+    pub root_view: Option<View>,
+    pub overlays: Vec<OverlayObject>,
+
+    pub hovered_view: Option<View>,
+    pub captured_view: Option<View>,
+    pub focused_view: Option<View>
 }
 
 struct WindowState
@@ -89,7 +113,11 @@ pub fn create() -> Window
             size: DEFAULT_WINDOW_SIZE,
             mouse_pos: Point { x: 0.0, y: 0.0 },
             system_window: new_system_window,
-            root_view: None
+            root_view: None,
+            overlays: Vec::new(),
+            hovered_view: None,
+            captured_view: None,
+            focused_view: None
         };
         
         state.windows.insert(new_view.id, new_view);
@@ -100,73 +128,735 @@ pub fn create() -> Window
     nanowin::window::set_resize_handler(new_window, resize);
     nanowin::window::set_render_handler(new_window, render);
 
+    crate::platform::register_input(new_window);
+
     return new_window;
 }
 
 pub fn destroy(window: Window)
 {
+    crate::widget::tooltip::forget_window(window);
+
     log(LogLevel::WARN, &format!("Window destruction not implemented!"));
 }
 
 pub fn set_root_view(window: Window, view: View)
 {
-    WINDOW_STATE.with(|state| {
+    let previous_root = WINDOW_STATE.with(|state| {
         let mut state = state.borrow_mut();
 
         if let Some(window_object) = state.windows.get_mut(&window)
         {
-            window_object.root_view = Some(view);
+            return window_object.root_view.replace(view);
         }
+
+        return None;
     });
 
+    // a tooltip shown for the old content goes with it
+    if previous_root.is_some_and(|previous_root| previous_root != view)
+    {
+        crate::widget::tooltip::forget_window(window);
+    }
+
     crate::view::set_window_down(view, Some(window));
 }
 
 pub fn resize(window: Window, rect: Rect)
 {
-    WINDOW_STATE.with(|state| {
+    let layout = WINDOW_STATE.with(|state| {
         let mut state = state.borrow_mut();
 
         if let Some(window_object) = state.windows.get_mut(&window)
         {
-            if let Some(root_view) = window_object.root_view
+            window_object.size = rect.size;
+
+            for overlay in window_object.overlays.iter_mut().filter(|overlay| overlay.modal)
             {
-                crate::view::layout_down(root_view, rect);
+                overlay.rect = rect;
             }
+
+            return Some(window_object.root_view);
+        }
+        else
+        {
+            return None;
         }
     });
+
+    if let Some(root_view) = layout
+    {
+        if let Some(root_view) = root_view
+        {
+            crate::view::layout_down(root_view, rect);
+        }
+
+        // read after the root is laid out, views that anchor an overlay can move it while it is
+        let overlays: Vec<(View, Rect)> = WINDOW_STATE.with(|state| {
+            return state.borrow().windows.get(&window)
+                .map(|window_object| window_object.overlays.iter().map(|overlay| (overlay.view, overlay.rect)).collect())
+                .unwrap_or_default();
+        });
+
+        for (overlay_view, overlay_rect) in overlays
+        {
+            crate::view::layout_down(overlay_view, overlay_rect);
+        }
+    }
 }
 
-pub fn render(window: Window)
+pub fn get_size(window: Window) -> Size
+{
+    return WINDOW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(window_object) = state.windows.get(&window)
+        {
+            return window_object.size;
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Window not found!"));
+            return Size { width: 0.0, height: 0.0 };
+        }
+    });
+}
+
+// shows a view above the root view at a rect in window coordinates, later overlays are on top
+pub fn add_overlay(window: Window, view: View, rect: Rect, light_dismiss: bool)
+{
+    insert_overlay(window, OverlayObject { view: view, rect: rect, light_dismiss: light_dismiss, passive: false, modal: false });
+}
+
+// shows a view above everything else that the pointer passes straight through
+pub fn add_passive_overlay(window: Window, view: View, rect: Rect)
+{
+    insert_overlay(window, OverlayObject { view: view, rect: rect, light_dismiss: false, passive: true, modal: false });
+}
+
+// covers the whole window with a view that takes all input, the view is resized along with the window
+pub fn add_modal_overlay(window: Window, view: View)
+{
+    let rect = Rect { origin: Point { x: 0.0, y: 0.0 }, size: get_size(window) };
+
+    insert_overlay(window, OverlayObject { view: view, rect: rect, light_dismiss: false, passive: false, modal: true });
+}
+
+// the topmost modal overlay, which holds the keyboard focus while it is open
+pub fn get_modal_overlay(window: Window) -> Option<View>
+{
+    return WINDOW_STATE.with(|state| {
+        return state.borrow().windows.get(&window)
+            .and_then(|window_object| window_object.overlays.iter().rev().find(|overlay| overlay.modal).map(|overlay| overlay.view));
+    });
+}
+
+pub fn remove_overlay(window: Window, view: View)
 {
     WINDOW_STATE.with(|state| {
         let mut state = state.borrow_mut();
 
         if let Some(window_object) = state.windows.get_mut(&window)
         {
-            if let Some(root_view) = window_object.root_view
+            window_object.overlays.retain(|overlay| overlay.view != view);
+
+            // pointer state must not keep pointing into a closed overlay
+            if window_object.hovered_view.is_some_and(|hovered_view| is_in_subtree(hovered_view, view))
             {
-                crate::view::render_down(root_view);
+                window_object.hovered_view = None;
+            }
+
+            if window_object.captured_view.is_some_and(|captured_view| is_in_subtree(captured_view, view))
+            {
+                window_object.captured_view = None;
             }
         }
     });
+}
+
+pub fn set_overlay_rect(window: Window, view: View, rect: Rect)
+{
+    let found = WINDOW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(window_object) = state.windows.get_mut(&window)
+        {
+            if let Some(overlay) = window_object.overlays.iter_mut().find(|overlay| overlay.view == view)
+            {
+                overlay.rect = rect;
+                return true;
+            }
+        }
+
+        return false;
+    });
+
+    if found
+    {
+        crate::view::layout_down(view, rect);
+    }
+}
+
+pub fn has_overlay(window: Window, view: View) -> bool
+{
+    return WINDOW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(window_object) = state.windows.get(&window)
+        {
+            return window_object.overlays.iter().any(|overlay| overlay.view == view);
+        }
+        else
+        {
+            return false;
+        }
+    });
+}
+
+// the views shown above the root view, from the bottom to the top
+pub fn get_overlay_views(window: Window) -> Vec<View>
+{
+    return WINDOW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(window_object) = state.windows.get(&window)
+        {
+            return window_object.overlays.iter().map(|overlay| overlay.view).collect();
+        }
+        else
+        {
+            return Vec::new();
+        }
+    });
+}
+
+pub fn render(window: Window)
+{
+    // timers and posted callbacks run first, so what they change shows in this frame
+    crate::app::process_pending();
+
+    // animations move on to this frame's time before anything is drawn
+    crate::animation::update();
+
+    let root_view = get_root_view(window);
 
     let window_size = nanowin::window::get_window_size(window);
 
-    nanowin::renderer::draw_label(window, Rect { origin: Point { x: 10.0, y: 10.0 }, size: Size { width: 250.0, height: 50.0 } }, nanowin::COLOR_GREEN, "Hello, NanoUI!");
+    // views are drawn in software and the finished frame is handed to nanowin
+    let mut context = DrawingContext::new(window_size);
+
+    if let Some(root_view) = root_view
+    {
+        crate::view::render_down(root_view, &mut context);
+    }
+
+    // a tooltip whose hover delay ran out since the last frame appears in this one
+    crate::widget::tooltip::update(window);
+
+    for overlay_view in get_overlay_views(window)
+    {
+        crate::view::render_down(overlay_view, &mut context);
+    }
+
+    crate::drawing::present(window, &context);
+
+    // running animations need the next frame as soon as this one is shown
+    if crate::animation::has_running()
+    {
+        request_redraw(window);
+    }
+}
+
+// asks for another frame, for views that change over time without any input, such as animations
+pub fn request_redraw(window: Window)
+{
+    crate::platform::request_redraw(window);
+}
+
+// asks every window for a frame, such as after timers ran that may have changed any of them
+pub(crate) fn request_redraw_all()
+{
+    let windows: Vec<Window> = WINDOW_STATE.with(|state| state.borrow().windows.keys().copied().collect());
+
+    for window in windows
+    {
+        request_redraw(window);
+    }
 }
 
 pub fn mouse_move(window: Window, point: Point)
 {
-    
+    let pointer_state = WINDOW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(window_object) = state.windows.get_mut(&window)
+        {
+            window_object.mouse_pos = point;
+            return Some((window_object.captured_view, window_object.hovered_view));
+        }
+        else
+        {
+            return None;
+        }
+    });
+
+    let (captured_view, hovered_view) = match pointer_state
+    {
+        Some(pointer_state) => pointer_state,
+        None => return
+    };
+
+    // a view that took the mouse down keeps receiving moves until the button is released
+    if let Some(captured_view) = captured_view
+    {
+        crate::widget::tooltip::hide(window);
+        crate::view::dispatch_event(captured_view, &Event::MouseMove { point: point });
+        return;
+    }
+
+    let target = hit_test(window, point);
+
+    crate::widget::tooltip::pointer_moved(window, target, point);
+
+    if target != hovered_view
+    {
+        set_hovered_view(window, target);
+
+        if let Some(hovered_view) = hovered_view
+        {
+            crate::view::send_event(hovered_view, &Event::MouseLeave);
+        }
+
+        if let Some(target) = target
+        {
+            crate::view::send_event(target, &Event::MouseEnter);
+        }
+    }
+
+    if let Some(target) = target
+    {
+        crate::view::dispatch_event(target, &Event::MouseMove { point: point });
+    }
+}
+
+// the pointer left the window, so nothing in it is hovered and a pending or shown tooltip goes away
+pub fn mouse_leave(window: Window)
+{
+    let pointer_state = WINDOW_STATE.with(|state| {
+        return state.borrow().windows.get(&window).map(|window_object| (window_object.mouse_pos, window_object.hovered_view));
+    });
+
+    let (point, hovered_view) = match pointer_state
+    {
+        Some(pointer_state) => pointer_state,
+        None => return
+    };
+
+    crate::widget::tooltip::pointer_moved(window, None, point);
+
+    if let Some(hovered_view) = hovered_view
+    {
+        set_hovered_view(window, None);
+        crate::view::send_event(hovered_view, &Event::MouseLeave);
+    }
+}
+
+pub fn mouse_down(window: Window, point: Point, button: MouseButton, modifiers: Modifiers)
+{
+    let target = hit_test(window, point);
+
+    crate::widget::tooltip::hide(window);
+
+    // a click outside the open overlays closes the light dismiss ones and goes no further
+    if dismiss_overlays(window, target)
+    {
+        return;
+    }
+
+    // clicking moves focus to the nearest focusable view, or clears it
+    let mut focus_target = target;
+
+    while let Some(view) = focus_target
+    {
+        if crate::view::is_focusable(view)
+        {
+            break;
+        }
+
+        focus_target = crate::view::get_parent(view);
+    }
+
+    // clicks on unfocusable overlay content, like a popup list, leave the focus where it was
+    let in_overlay = target.is_some_and(|target| get_overlay_views(window).into_iter().any(|overlay_view| is_in_subtree(target, overlay_view)));
+
+    if focus_target.is_some() || !in_overlay
+    {
+        set_focus(window, focus_target);
+    }
+
+    WINDOW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(window_object) = state.windows.get_mut(&window)
+        {
+            window_object.captured_view = target;
+        }
+    });
+
+    if let Some(target) = target
+    {
+        let handled = crate::view::dispatch_event(target, &Event::MouseDown { point: point, button: button, modifiers: modifiers });
+
+        // right clicks nothing else wanted open the nearest context menu
+        if !handled && button == MouseButton::Right
+        {
+            crate::widget::menu::show_context_menu(window, target, point);
+        }
+    }
+}
+
+pub fn mouse_up(window: Window, point: Point, button: MouseButton, modifiers: Modifiers)
+{
+    let captured_view = WINDOW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(window_object) = state.windows.get_mut(&window)
+        {
+            return window_object.captured_view.take();
+        }
+        else
+        {
+            return None;
+        }
+    });
+
+    let target = captured_view.or(hit_test(window, point));
+
+    if let Some(target) = target
+    {
+        crate::view::dispatch_event(target, &Event::MouseUp { point: point, button: button, modifiers: modifiers });
+    }
 }
 
 pub fn mouse_click(window: Window, point: Point)
 {
-    
+    mouse_down(window, point, MouseButton::Left, Modifiers::default());
+    mouse_up(window, point, MouseButton::Left, Modifiers::default());
+}
+
+// wheel and trackpad scrolling goes to the view under the pointer and bubbles up to a scrollable ancestor
+pub fn mouse_scroll(window: Window, point: Point, delta: Point)
+{
+    if let Some(target) = hit_test(window, point)
+    {
+        crate::view::dispatch_event(target, &Event::Scroll { point: point, delta: delta });
+    }
+}
+
+pub fn key_down(window: Window, key: Key, modifiers: Modifiers)
+{
+    let focused_view = get_focus(window);
+
+    let handled = match focused_view
+    {
+        Some(focused_view) => crate::view::dispatch_event(focused_view, &Event::KeyDown { key: key, modifiers: modifiers }),
+        None => false
+    };
+
+    // keys the focused view did not want run bound commands, then menu accelerators, unless a modal blocks them
+    let modal = get_modal_overlay(window).is_some();
+
+    let handled = handled || (!modal && crate::app::command::handle_key(window, key, modifiers));
+    let handled = handled || (!modal && crate::widget::menu_bar::handle_accelerator(window, key, modifiers));
+
+    if !handled && key == Key::Tab
+    {
+        focus_next(window, modifiers.shift);
+    }
 }
 
+pub fn set_focus(window: Window, view: Option<View>)
+{
+    let previous_focus = WINDOW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(window_object) = state.windows.get_mut(&window)
+        {
+            let previous_focus = window_object.focused_view;
+            window_object.focused_view = view;
+            return previous_focus;
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Window not found!"));
+            return view;
+        }
+    });
+
+    if previous_focus == view
+    {
+        return;
+    }
+
+    if let Some(previous_focus) = previous_focus
+    {
+        crate::view::send_event(previous_focus, &Event::FocusLost);
+    }
+
+    if let Some(view) = view
+    {
+        crate::view::send_event(view, &Event::FocusGained);
+    }
+}
+
+pub fn get_focus(window: Window) -> Option<View>
+{
+    return WINDOW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(window_object) = state.windows.get(&window)
+        {
+            return window_object.focused_view;
+        }
+        else
+        {
+            return None;
+        }
+    });
+}
+
+// moves focus to the next focusable view in tree order, wrapping around at the ends, inside the topmost modal if there is one
+pub fn focus_next(window: Window, reverse: bool)
+{
+    let root_view = get_modal_overlay(window).or(get_root_view(window));
+
+    let mut focusable_views: Vec<View> = Vec::new();
+
+    if let Some(root_view) = root_view
+    {
+        collect_focusable(root_view, &mut focusable_views);
+    }
+
+    if focusable_views.is_empty()
+    {
+        return;
+    }
+
+    let current = get_focus(window).and_then(|focused_view| focusable_views.iter().position(|view| *view == focused_view));
+    let count = focusable_views.len();
+
+    let next = match (current, reverse)
+    {
+        (Some(index), false) => (index + 1) % count,
+        (Some(index), true) => (index + count - 1) % count,
+        (None, false) => 0,
+        (None, true) => count - 1
+    };
+
+    set_focus(window, Some(focusable_views[next]));
+}
+
+pub fn get_root_view(window: Window) -> Option<View>
+{
+    return WINDOW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(window_object) = state.windows.get(&window)
+        {
+            return window_object.root_view;
+        }
+        else
+        {
+            return None;
+        }
+    });
+}
 
 /***************************************************************
 * MARK: LOCAL FUNCTIONS
-***************************************************************/
\ No newline at end of file
+***************************************************************/
+
+// overlays are tested from the top down before the root view
+fn hit_test(window: Window, point: Point) -> Option<View>
+{
+    let interactive_overlays: Vec<View> = WINDOW_STATE.with(|state| {
+        return state.borrow().windows.get(&window)
+            .map(|window_object| window_object.overlays.iter().filter(|overlay| !overlay.passive).map(|overlay| overlay.view).collect())
+            .unwrap_or(Vec::new());
+    });
+
+    for overlay_view in interactive_overlays.into_iter().rev()
+    {
+        if let Some(hit) = crate::view::hit_test(overlay_view, point)
+        {
+            return Some(hit);
+        }
+    }
+
+    return get_root_view(window).and_then(|root_view| crate::view::hit_test(root_view, point));
+}
+
+fn insert_overlay(window: Window, overlay: OverlayObject)
+{
+    let (view, rect) = (overlay.view, overlay.rect);
+
+    let added = WINDOW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(window_object) = state.windows.get_mut(&window)
+        {
+            window_object.overlays.retain(|existing| existing.view != view);
+            window_object.overlays.push(overlay);
+            return true;
+        }
+        else
+        {
+            log(LogLevel::FAIL, &format!("Window not found!"));
+            return false;
+        }
+    });
+
+    if added
+    {
+        crate::view::set_window_down(view, Some(window));
+        crate::view::layout_down(view, rect);
+    }
+}
+
+// closes the light dismiss overlays above the one holding the target, or all of them when the target
+// is outside every overlay, returns true if any closed and the target is not in one that stays open
+fn dismiss_overlays(window: Window, target: Option<View>) -> bool
+{
+    let (dismissed, in_overlay): (Vec<View>, bool) = WINDOW_STATE.with(|state| {
+        let state = state.borrow();
+
+        if let Some(window_object) = state.windows.get(&window)
+        {
+            // a click inside a later overlay, like a submenu, leaves the ones beneath it open
+            let holder = target.and_then(|target| window_object.overlays.iter().rposition(|overlay| is_in_subtree(target, overlay.view)));
+            let first = holder.map(|index| index + 1).unwrap_or(0);
+
+            let dismissed = window_object.overlays[first..].iter()
+                .filter(|overlay| overlay.light_dismiss)
+                .map(|overlay| overlay.view)
+                .collect();
+
+            return (dismissed, holder.is_some());
+        }
+        else
+        {
+            return (Vec::new(), false);
+        }
+    });
+
+    for overlay_view in dismissed.iter()
+    {
+        remove_overlay(window, *overlay_view);
+        crate::view::send_event(*overlay_view, &Event::Dismissed);
+    }
+
+    return !dismissed.is_empty() && !in_overlay;
+}
+
+fn is_in_subtree(view: View, root: View) -> bool
+{
+    let mut current = Some(view);
+
+    while let Some(ancestor) = current
+    {
+        if ancestor == root
+        {
+            return true;
+        }
+
+        current = crate::view::get_parent(ancestor);
+    }
+
+    return false;
+}
+
+fn set_hovered_view(window: Window, view: Option<View>)
+{
+    WINDOW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if let Some(window_object) = state.windows.get_mut(&window)
+        {
+            window_object.hovered_view = view;
+        }
+    });
+}
+
+fn collect_focusable(view: View, focusable_views: &mut Vec<View>)
+{
+    // nothing in a hidden subtree can be tabbed to
+    if crate::view::get_visibility(view) != crate::view::Visibility::Visible
+    {
+        return;
+    }
+
+    if crate::view::is_focusable(view)
+    {
+        focusable_views.push(view);
+    }
+
+    for child in crate::view::get_children(view)
+    {
+        collect_focusable(child, focusable_views);
+    }
+}
+
+// forgets pointer and focus state inside a subtree that was taken out of the window,
+// a focused view in it still gets FocusLost so it can close what it opened in the window
+pub(crate) fn detach_view(window: Window, view: View)
+{
+    let detached = WINDOW_STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        let window_object = state.windows.get_mut(&window)?;
+
+        let hovered = window_object.hovered_view.is_some_and(|hovered_view| is_in_subtree(hovered_view, view));
+
+        if hovered
+        {
+            window_object.hovered_view = None;
+        }
+
+        if window_object.captured_view.is_some_and(|captured_view| is_in_subtree(captured_view, view))
+        {
+            window_object.captured_view = None;
+        }
+
+        let root = window_object.root_view == Some(view);
+
+        if root
+        {
+            window_object.root_view = None;
+        }
+
+        let lost_focus = if window_object.focused_view.is_some_and(|focused_view| is_in_subtree(focused_view, view)) { window_object.focused_view.take() } else { None };
+
+        return Some((lost_focus, hovered.then_some(window_object.mouse_pos), root));
+    });
+
+    let (lost_focus, hovered_point, root) = match detached
+    {
+        Some(detached) => detached,
+        None => return
+    };
+
+    // the tooltip popup belongs to the window's content, a pending or shown tooltip only to the hovered views
+    if root
+    {
+        crate::widget::tooltip::forget_window(window);
+    }
+    else if let Some(point) = hovered_point
+    {
+        crate::widget::tooltip::pointer_moved(window, None, point);
+    }
+
+    if let Some(lost_focus) = lost_focus
+    {
+        crate::view::send_event(lost_focus, &Event::FocusLost);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,133 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  animation.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Steps animations on the manual app clock.
+**
+***************************************************************/
+
+use nanoui::animation::{self, AnimatedProperty, Easing};
+use nanoui::{app, view};
+
+use std::time::Duration;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+fn milliseconds(value: u64) -> Duration
+{
+    return Duration::from_millis(value);
+}
+
+#[test]
+fn tweens_on_the_manual_clock()
+{
+    app::clock::set_manual(true);
+
+    let view = view::create();
+    view::set_opacity(view, 1.0);
+
+    let fade = animation::animate(view, AnimatedProperty::Opacity(0.0), milliseconds(100));
+    animation::set_easing(fade, Easing::Linear);
+
+    animation::update();
+    assert_eq!(view::get_opacity(view), 1.0);
+    assert!(animation::has_running());
+
+    app::clock::advance(milliseconds(25));
+    animation::update();
+    assert!((view::get_opacity(view) - 0.75).abs() < 0.001);
+
+    app::clock::advance(milliseconds(100));
+    animation::update();
+    assert_eq!(view::get_opacity(view), 0.0);
+    assert!(!animation::is_running(fade));
+    assert!(!animation::has_running());
+
+    app::clock::set_manual(false);
+}
+
+#[test]
+fn delayed_animations_chain()
+{
+    app::clock::set_manual(true);
+
+    let view = view::create();
+    view::set_opacity(view, 1.0);
+
+    let finished = Rc::new(RefCell::new(Vec::new()));
+
+    let fade_out = animation::animate(view, AnimatedProperty::Opacity(0.0), milliseconds(100));
+    animation::set_easing(fade_out, Easing::Linear);
+
+    let fade_in = animation::animate(view, AnimatedProperty::Opacity(0.5), milliseconds(100));
+    animation::set_easing(fade_in, Easing::Linear);
+    animation::set_delay(fade_in, milliseconds(100));
+
+    for animation in [fade_out, fade_in]
+    {
+        let finished = finished.clone();
+        animation::set_completion_handler(animation, move |animation, done| finished.borrow_mut().push((animation, done)));
+    }
+
+    // the second animation is still in its delay, so it does not replace the first
+    animation::update();
+    app::clock::advance(milliseconds(50));
+    animation::update();
+    assert!((view::get_opacity(view) - 0.5).abs() < 0.001);
+    assert!(animation::is_running(fade_out));
+
+    app::clock::advance(milliseconds(50));
+    animation::update();
+    assert_eq!(view::get_opacity(view), 0.0);
+    assert_eq!(*finished.borrow(), vec![(fade_out, true)]);
+
+    // and picks up where the first one left off
+    app::clock::advance(milliseconds(50));
+    animation::update();
+    assert!((view::get_opacity(view) - 0.25).abs() < 0.001);
+
+    app::clock::advance(milliseconds(50));
+    animation::update();
+    assert_eq!(view::get_opacity(view), 0.5);
+    assert_eq!(*finished.borrow(), vec![(fade_out, true), (fade_in, true)]);
+
+    app::clock::set_manual(false);
+}
+
+#[test]
+fn started_animations_are_replaced()
+{
+    app::clock::set_manual(true);
+
+    let view = view::create();
+    view::set_opacity(view, 1.0);
+
+    let finished = Rc::new(RefCell::new(Vec::new()));
+
+    let fade_out = animation::animate(view, AnimatedProperty::Opacity(0.0), milliseconds(100));
+    animation::set_easing(fade_out, Easing::Linear);
+
+    let finished_handle = finished.clone();
+    animation::set_completion_handler(fade_out, move |animation, done| finished_handle.borrow_mut().push((animation, done)));
+
+    animation::update();
+    app::clock::advance(milliseconds(50));
+    animation::update();
+
+    let fade_in = animation::animate(view, AnimatedProperty::Opacity(1.0), milliseconds(100));
+    animation::set_easing(fade_in, Easing::Linear);
+
+    animation::update();
+    assert_eq!(*finished.borrow(), vec![(fade_out, false)]);
+    assert!((view::get_opacity(view) - 0.5).abs() < 0.001);
+
+    app::clock::advance(milliseconds(50));
+    animation::update();
+    assert!((view::get_opacity(view) - 0.75).abs() < 0.001);
+
+    app::clock::set_manual(false);
+}
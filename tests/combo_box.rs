@@ -0,0 +1,98 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  combo_box.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Checks where the combo box popup opens, that it
+**                 follows the combo box and that it closes when the
+**                 combo box leaves the window.
+**
+***************************************************************/
+
+use nanoui::view::{self, DockLocation};
+use nanoui::widget::combo_box;
+use nanoui::{window, View, Window};
+
+use nanowin::{Point, Rect, Size};
+
+const POPUP_HEIGHT: f32 = 3.0 * 24.0;
+
+// a 300 pixel wide window with a 30 pixel tall combo box of three items docked to the bottom of the root view
+fn create_window(height: f32) -> (Window, View, View)
+{
+    let window = window::create();
+    let root = view::create();
+    let combo = combo_box::create();
+
+    combo_box::set_items(combo, vec![String::from("One"), String::from("Two"), String::from("Three")]);
+    view::set_size(combo, Size { width: 300.0, height: 30.0 });
+    view::set_dock_location(combo, DockLocation::Bottom);
+
+    view::add_child(root, combo);
+    view::add_child(root, view::create());
+
+    window::set_root_view(window, root);
+    resize(window, height);
+
+    return (window, root, combo);
+}
+
+fn resize(window: Window, height: f32)
+{
+    window::resize(window, Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 300.0, height: height } });
+}
+
+fn popup_rect(combo: View) -> Rect
+{
+    let window = view::get_window(combo).expect("the combo box is in a window");
+    let popup = *window::get_overlay_views(window).first().expect("the popup is open");
+
+    return view::get_rect(popup);
+}
+
+#[test]
+fn opens_where_the_combo_box_is_drawn()
+{
+    let (_, _, combo) = create_window(400.0);
+
+    // moved by its transform rather than by layout
+    view::set_translation(combo, Point { x: 20.0, y: -200.0 });
+    combo_box::open(combo);
+
+    let rect = popup_rect(combo);
+    assert_eq!((rect.origin.x, rect.origin.y), (20.0, 200.0));
+    assert_eq!((rect.size.width, rect.size.height), (300.0, POPUP_HEIGHT));
+}
+
+#[test]
+fn follows_the_combo_box_when_the_window_resizes()
+{
+    let (window, _, combo) = create_window(400.0);
+
+    // there is no room below a combo box at the bottom of the window
+    combo_box::open(combo);
+    assert_eq!(popup_rect(combo).origin.y, 370.0 - POPUP_HEIGHT);
+
+    resize(window, 600.0);
+    assert!(combo_box::is_open(combo));
+    assert_eq!(popup_rect(combo).origin.y, 570.0 - POPUP_HEIGHT);
+}
+
+#[test]
+fn closes_when_removed_while_focused()
+{
+    let (window, root, combo) = create_window(400.0);
+
+    window::set_focus(window, Some(combo));
+    combo_box::open(combo);
+    assert!(combo_box::is_open(combo));
+
+    view::remove_child(root, combo);
+
+    assert!(!combo_box::is_open(combo));
+    assert!(window::get_overlay_views(window).is_empty());
+    assert_eq!(window::get_focus(window), None);
+}
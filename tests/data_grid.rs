@@ -0,0 +1,110 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  data_grid.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Checks that the selected cell stays on its row
+**                 when the data grid is sorted.
+**
+***************************************************************/
+
+use nanoui::view;
+use nanoui::widget::data_grid::{self, DataGridSource, SortDirection};
+
+use nanowin::{Point, Rect, Size};
+
+struct Fruits
+{
+    names: Vec<String>
+}
+
+impl DataGridSource for Fruits
+{
+    fn row_count(&self) -> usize
+    {
+        return self.names.len();
+    }
+
+    fn cell_text(&self, row: usize, _column: usize) -> String
+    {
+        return self.names[row].clone();
+    }
+
+    fn sort(&mut self, _column: usize, direction: SortDirection) -> Option<Vec<usize>>
+    {
+        let mut order: Vec<usize> = (0..self.names.len()).collect();
+        order.sort_by(|a, b| self.names[*a].cmp(&self.names[*b]));
+
+        if direction == SortDirection::Descending
+        {
+            order.reverse();
+        }
+
+        self.names = order.iter().map(|row| self.names[*row].clone()).collect();
+        return Some(order);
+    }
+}
+
+// a source whose sort leaves no trace of where the rows went
+struct Numbers;
+
+impl DataGridSource for Numbers
+{
+    fn row_count(&self) -> usize
+    {
+        return 10;
+    }
+
+    fn cell_text(&self, row: usize, _column: usize) -> String
+    {
+        return row.to_string();
+    }
+}
+
+fn create_grid() -> nanoui::View
+{
+    let grid = data_grid::create();
+
+    data_grid::add_column(grid, "Name", 100.0);
+    data_grid::add_column(grid, "Color", 100.0);
+    view::layout_down(grid, Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 300.0, height: 200.0 } });
+
+    return grid;
+}
+
+#[test]
+fn the_selection_follows_its_row_when_sorted()
+{
+    let grid = create_grid();
+
+    let names = ["pear", "apple", "fig", "banana"];
+    data_grid::set_data_source(grid, Fruits { names: names.iter().map(|name| String::from(*name)).collect() });
+
+    // pear goes from the first row to the last
+    data_grid::set_selected_cell(grid, Some((0, 1)));
+
+    data_grid::sort_by(grid, 0, SortDirection::Ascending);
+    assert_eq!(data_grid::get_selected_cell(grid), Some((3, 1)));
+
+    // banana is second in ascending order and third in descending order
+    data_grid::set_selected_cell(grid, Some((1, 0)));
+
+    data_grid::sort_by(grid, 0, SortDirection::Descending);
+    assert_eq!(data_grid::get_selected_cell(grid), Some((2, 0)));
+    assert_eq!(data_grid::get_sort(grid), Some((0, SortDirection::Descending)));
+}
+
+#[test]
+fn a_source_without_an_order_keeps_the_selection()
+{
+    let grid = create_grid();
+    data_grid::set_data_source(grid, Numbers);
+
+    data_grid::set_selected_cell(grid, Some((4, 1)));
+    data_grid::sort_by(grid, 1, SortDirection::Ascending);
+
+    assert_eq!(data_grid::get_selected_cell(grid), Some((4, 1)));
+}
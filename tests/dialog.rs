@@ -0,0 +1,48 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  dialog.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Checks which views can be shown as a dialog.
+**
+***************************************************************/
+
+use nanoui::widget::dialog;
+use nanoui::{view, window};
+
+use nanowin::{Point, Rect, Size};
+
+#[test]
+fn content_with_a_parent_is_refused()
+{
+    let window = window::create();
+    let root = view::create();
+    let content = view::create();
+
+    window::set_root_view(window, root);
+    window::resize(window, Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 400.0, height: 300.0 } });
+
+    view::add_child(root, content);
+
+    dialog::show(window, content);
+    assert!(!dialog::is_open(content));
+    assert_eq!(view::get_parent(content), Some(root));
+    assert_eq!(window::get_modal_overlay(window), None);
+
+    // once detached it can be shown, and again after it was closed
+    view::remove_child(root, content);
+
+    for _ in 0..2
+    {
+        dialog::show(window, content);
+        assert!(dialog::is_open(content));
+        assert!(window::get_modal_overlay(window).is_some());
+
+        dialog::close(content);
+        assert!(!dialog::is_open(content));
+        assert_eq!(view::get_parent(content), None);
+    }
+}
@@ -0,0 +1,100 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  dispatch.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Dispatches callbacks from worker threads and runs
+**                 them on the UI thread.
+**
+***************************************************************/
+
+use nanoui::app::{self, dispatch};
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::cell::RefCell;
+
+thread_local! {
+    static RECEIVED: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+#[test]
+fn callbacks_from_workers_run_on_the_ui_thread()
+{
+    let wakeups = Arc::new(AtomicUsize::new(0));
+
+    let wakeups_handle = wakeups.clone();
+    dispatch::set_wakeup_handler(move || {
+        wakeups_handle.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let dispatcher = dispatch::dispatcher();
+    assert!(dispatcher.is_current_thread());
+
+    let workers: Vec<std::thread::JoinHandle<()>> = (0..4).map(|worker| {
+        let dispatcher = dispatcher.clone();
+
+        return std::thread::spawn(move || {
+            assert!(!dispatcher.is_current_thread());
+
+            for index in 0..10
+            {
+                dispatcher.dispatch(move || RECEIVED.with(|received| received.borrow_mut().push(worker * 10 + index)));
+            }
+        });
+    }).collect();
+
+    for worker in workers
+    {
+        worker.join().unwrap();
+    }
+
+    // a burst of dispatches wakes the loop once
+    assert!(app::has_pending());
+    assert_eq!(wakeups.load(Ordering::SeqCst), 1);
+
+    app::process_pending();
+
+    // each worker's callbacks run in the order that worker dispatched them
+    RECEIVED.with(|received| {
+        let received = received.borrow();
+
+        assert_eq!(received.len(), 40);
+
+        for worker in 0..4
+        {
+            let from_worker: Vec<usize> = received.iter().copied().filter(|value| value / 10 == worker).collect();
+            assert_eq!(from_worker, (worker * 10..worker * 10 + 10).collect::<Vec<usize>>());
+        }
+    });
+
+    assert!(!app::has_pending());
+
+    dispatcher.dispatch(|| {});
+    assert_eq!(wakeups.load(Ordering::SeqCst), 2);
+
+    app::process_pending();
+    assert!(!app::has_pending());
+}
+
+#[test]
+fn callbacks_wait_for_the_loop_without_a_wakeup_handler()
+{
+    dispatch::clear_wakeup_handler();
+
+    let dispatcher = dispatch::dispatcher();
+    let ran = Arc::new(AtomicUsize::new(0));
+
+    let ran_handle = ran.clone();
+    std::thread::spawn(move || dispatcher.dispatch(move || {
+        ran_handle.fetch_add(1, Ordering::SeqCst);
+    })).join().unwrap();
+
+    assert_eq!(ran.load(Ordering::SeqCst), 0);
+
+    app::process_pending();
+    assert_eq!(ran.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,207 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  font.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Loads a small generated TrueType font, measures
+**                 text with it and draws its glyphs.
+**
+***************************************************************/
+
+use nanoui::drawing::{font, DrawingContext, FontDescriptor};
+
+use nanowin::{Color, Point, Size};
+
+// a font whose only glyph, mapped to 'A', is a 400 by 700 unit box standing on the baseline in a 1000 unit em,
+// with an advance of 600 units, an ascender of 800 and a descender of 200
+fn box_font() -> Vec<u8>
+{
+    let mut head: Vec<u8> = Vec::new();
+    push_u32(&mut head, 0x00010000);
+    push_u32(&mut head, 0x00010000);
+    push_u32(&mut head, 0);
+    push_u32(&mut head, 0x5F0F3CF5);
+    push_u16(&mut head, 0);
+    push_u16(&mut head, 1000);
+    head.extend_from_slice(&[0; 16]);
+    for value in [100, 0, 500, 700]
+    {
+        push_i16(&mut head, value);
+    }
+    push_u16(&mut head, 0);
+    push_u16(&mut head, 8);
+    push_i16(&mut head, 2);
+    push_i16(&mut head, 0);
+    push_i16(&mut head, 0);
+
+    let mut hhea: Vec<u8> = Vec::new();
+    push_u32(&mut hhea, 0x00010000);
+    for value in [800, -200, 0]
+    {
+        push_i16(&mut hhea, value);
+    }
+    push_u16(&mut hhea, 600);
+    for value in [0, 100, 500, 1, 0, 0, 0, 0, 0, 0, 0]
+    {
+        push_i16(&mut hhea, value);
+    }
+    push_u16(&mut hhea, 2);
+
+    let mut maxp: Vec<u8> = Vec::new();
+    push_u32(&mut maxp, 0x00005000);
+    push_u16(&mut maxp, 2);
+
+    let mut hmtx: Vec<u8> = Vec::new();
+    for (advance, bearing) in [(600, 0), (600, 100)]
+    {
+        push_u16(&mut hmtx, advance);
+        push_i16(&mut hmtx, bearing);
+    }
+
+    // a unicode format 12 subtable with one group mapping 'A' to glyph 1
+    let mut cmap: Vec<u8> = Vec::new();
+    push_u16(&mut cmap, 0);
+    push_u16(&mut cmap, 1);
+    push_u16(&mut cmap, 0);
+    push_u16(&mut cmap, 4);
+    push_u32(&mut cmap, 12);
+    push_u16(&mut cmap, 12);
+    push_u16(&mut cmap, 0);
+    push_u32(&mut cmap, 28);
+    push_u32(&mut cmap, 0);
+    push_u32(&mut cmap, 1);
+    for value in [0x41, 0x41, 1]
+    {
+        push_u32(&mut cmap, value);
+    }
+
+    // one contour of four on-curve points, each stored as a delta from the one before
+    let mut glyf: Vec<u8> = Vec::new();
+    push_i16(&mut glyf, 1);
+    for value in [100, 0, 500, 700]
+    {
+        push_i16(&mut glyf, value);
+    }
+    push_u16(&mut glyf, 3);
+    push_u16(&mut glyf, 0);
+    glyf.extend_from_slice(&[0x01; 4]);
+    for value in [100, 400, 0, -400, 0, 0, 700, 0]
+    {
+        push_i16(&mut glyf, value);
+    }
+
+    let mut loca: Vec<u8> = Vec::new();
+    for value in [0, 0, glyf.len() as u16 / 2]
+    {
+        push_u16(&mut loca, value);
+    }
+
+    // table records have to be sorted by tag
+    let tables: [(&[u8; 4], Vec<u8>); 7] = [
+        (b"cmap", cmap),
+        (b"glyf", glyf),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+        (b"maxp", maxp)
+    ];
+
+    let mut data: Vec<u8> = Vec::new();
+    push_u32(&mut data, 0x00010000);
+    push_u16(&mut data, tables.len() as u16);
+    push_u16(&mut data, 64);
+    push_u16(&mut data, 2);
+    push_u16(&mut data, 48);
+
+    let mut offset = 12 + tables.len() * 16;
+
+    for (tag, table) in tables.iter()
+    {
+        data.extend_from_slice(*tag);
+        push_u32(&mut data, 0);
+        push_u32(&mut data, offset as u32);
+        push_u32(&mut data, table.len() as u32);
+
+        offset += table.len().div_ceil(4) * 4;
+    }
+
+    for (_, table) in tables.iter()
+    {
+        data.extend_from_slice(table);
+        data.resize(data.len().div_ceil(4) * 4, 0);
+    }
+
+    return data;
+}
+
+fn push_u16(data: &mut Vec<u8>, value: u16)
+{
+    data.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_i16(data: &mut Vec<u8>, value: i16)
+{
+    data.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_u32(data: &mut Vec<u8>, value: u32)
+{
+    data.extend_from_slice(&value.to_be_bytes());
+}
+
+fn descriptor(size: f32) -> FontDescriptor
+{
+    return FontDescriptor { size: size, ..FontDescriptor::default() };
+}
+
+#[test]
+fn rejects_data_that_is_not_a_font()
+{
+    assert!(font::load_bytes(vec![0; 64]).is_none());
+}
+
+#[test]
+fn measures_runs_and_lines()
+{
+    let loaded = font::load_bytes(box_font()).expect("the generated font parses");
+    assert_eq!(font::get_default(), Some(loaded));
+
+    let descriptor = descriptor(10.0);
+
+    assert!((font::measure_run("A", &descriptor) - 6.0).abs() < 0.001);
+    assert!((font::measure_run("AAA", &descriptor) - 18.0).abs() < 0.001);
+
+    let metrics = font::get_metrics(&descriptor);
+    assert!((metrics.ascent - 8.0).abs() < 0.001);
+    assert!((metrics.descent - 2.0).abs() < 0.001);
+
+    let size = font::measure_text("AA\nA", &descriptor);
+    assert!((size.width - 12.0).abs() < 0.001);
+    assert!((size.height - metrics.line_height * 2.0).abs() < 0.001);
+}
+
+#[test]
+fn draws_glyph_coverage()
+{
+    font::load_bytes(box_font()).expect("the generated font parses");
+
+    let mut context = DrawingContext::new(Size { width: 40.0, height: 30.0 });
+    let white = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+
+    // at 20 pixels the box spans x 2 to 10 and stands 14 pixels tall on the baseline at y 16
+    let pen = context.draw_text(Point { x: 0.0, y: 0.0 }, "AA", &descriptor(20.0), white);
+    assert!((pen.x - 24.0).abs() < 0.001);
+
+    let bitmap = context.bitmap();
+
+    assert!(bitmap.get_pixel(5, 10).a > 0.99);
+    assert!(bitmap.get_pixel(17, 10).a > 0.99);
+    assert!(bitmap.get_pixel(0, 10).a < 0.01);
+    assert!(bitmap.get_pixel(12, 10).a < 0.01);
+    assert!(bitmap.get_pixel(5, 0).a < 0.01);
+    assert!(bitmap.get_pixel(5, 20).a < 0.01);
+}
@@ -0,0 +1,97 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  image.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Decodes a small PNG and checks its pixels when
+**                 drawn with nearest and bilinear sampling.
+**
+***************************************************************/
+
+use nanoui::drawing::{image, DrawingContext, ImageSampling};
+
+use nanowin::{Point, Rect, Size};
+
+use std::io::Cursor;
+
+// red and green on the top row, blue and half transparent white below
+fn checker_png() -> Vec<u8>
+{
+    let pixels: Vec<u8> = vec![
+        255, 0, 0, 255,     0, 255, 0, 255,
+        0, 0, 255, 255,     255, 255, 255, 128
+    ];
+
+    let source = ::image::RgbaImage::from_raw(2, 2, pixels).unwrap();
+
+    let mut data = Cursor::new(Vec::new());
+    source.write_to(&mut data, ::image::ImageOutputFormat::Png).expect("the image encodes");
+
+    return data.into_inner();
+}
+
+fn draw(size: f32, sampling: ImageSampling) -> DrawingContext
+{
+    let loaded = image::load_bytes(&checker_png()).expect("the PNG decodes");
+
+    let mut context = DrawingContext::new(Size { width: size, height: size });
+    context.draw_image(Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: size, height: size } }, loaded, sampling);
+
+    return context;
+}
+
+#[test]
+fn decodes_pixels()
+{
+    assert!(image::load_bytes(b"not an image").is_none());
+
+    let loaded = image::load_bytes(&checker_png()).expect("the PNG decodes");
+    let size = image::get_size(loaded);
+    assert_eq!((size.width, size.height), (2.0, 2.0));
+
+    let context = draw(2.0, ImageSampling::Nearest);
+    let bitmap = context.bitmap();
+
+    let red = bitmap.get_pixel(0, 0);
+    assert!(red.r > 0.99 && red.g < 0.01 && red.a > 0.99);
+
+    let green = bitmap.get_pixel(1, 0);
+    assert!(green.g > 0.99 && green.r < 0.01);
+
+    let blue = bitmap.get_pixel(0, 1);
+    assert!(blue.b > 0.99 && blue.g < 0.01);
+
+    // premultiplied when decoded and back to full white when read
+    let white = bitmap.get_pixel(1, 1);
+    assert!(white.r > 0.98 && white.g > 0.98 && (white.a - 0.5).abs() < 0.01, "{:?}", (white.r, white.a));
+}
+
+#[test]
+fn samples_nearest_and_bilinear()
+{
+    let nearest_context = draw(20.0, ImageSampling::Nearest);
+    let bilinear_context = draw(20.0, ImageSampling::Bilinear);
+
+    let nearest = nearest_context.bitmap();
+    let bilinear = bilinear_context.bitmap();
+
+    // the corners keep the source color either way
+    for bitmap in [nearest, bilinear]
+    {
+        let corner = bitmap.get_pixel(0, 0);
+        assert!(corner.r > 0.99 && corner.g < 0.01);
+    }
+
+    // next to the middle, nearest keeps a hard edge and bilinear blends red into green
+    let hard = nearest.get_pixel(9, 2);
+    assert!(hard.r > 0.99 && hard.g < 0.01);
+
+    let blended = bilinear.get_pixel(9, 2);
+    assert!(blended.r > 0.3 && blended.g > 0.3 && blended.r > blended.g, "{:?}", (blended.r, blended.g));
+
+    let hard = nearest.get_pixel(10, 2);
+    assert!(hard.g > 0.99 && hard.r < 0.01);
+}
@@ -0,0 +1,151 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  list_view.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Checks that list views only build the visible
+**                 rows, recycle them while scrolling and select
+**                 ranges of items.
+**
+***************************************************************/
+
+use nanoui::event::{Event, Key, Modifiers, MouseButton};
+use nanoui::widget::list_view::{self, SelectionMode};
+use nanoui::{view, View};
+
+use nanowin::{Point, Rect, Size};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const ITEM_COUNT: usize = 100_000;
+
+// a 200 pixel tall list of 24 pixel rows, where each row view remembers the item it shows
+fn create_list(rows: &Rc<RefCell<HashMap<View, usize>>>, built: &Rc<RefCell<usize>>) -> View
+{
+    let list = list_view::create();
+    view::layout_down(list, Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 300.0, height: 200.0 } });
+
+    list_view::set_row_height(list, 24.0);
+
+    let builder_rows = rows.clone();
+    let builder_built = built.clone();
+    list_view::set_row_builder(list, move |_, index| {
+        let row = view::create();
+
+        builder_rows.borrow_mut().insert(row, index);
+        *builder_built.borrow_mut() += 1;
+
+        return row;
+    });
+
+    let recycler_rows = rows.clone();
+    list_view::set_row_recycler(list, move |_, index, row| {
+        recycler_rows.borrow_mut().insert(row, index);
+        return row;
+    });
+
+    list_view::set_item_count(list, ITEM_COUNT);
+
+    return list;
+}
+
+fn key_down(list: View, key: Key, modifiers: Modifiers)
+{
+    view::send_event(list, &Event::KeyDown { key: key, modifiers: modifiers });
+}
+
+fn click(list: View, y: f32, modifiers: Modifiers)
+{
+    let point = Point { x: 10.0, y: y };
+
+    view::send_event(list, &Event::MouseDown { point: point, button: MouseButton::Left, modifiers: modifiers });
+    view::send_event(list, &Event::MouseUp { point: point, button: MouseButton::Left, modifiers: modifiers });
+}
+
+#[test]
+fn builds_only_the_visible_rows()
+{
+    let rows = Rc::new(RefCell::new(HashMap::new()));
+    let built = Rc::new(RefCell::new(0));
+
+    let list = create_list(&rows, &built);
+
+    // 200 / 24 rounds up to 9 rows
+    assert_eq!(view::get_children(list).len(), 9);
+    assert_eq!(*built.borrow(), 9);
+    assert_eq!(list_view::get_content_height(list), ITEM_COUNT as f32 * 24.0);
+
+    let first = list_view::get_row_view(list, 0).expect("the first row is built");
+    assert_eq!(rows.borrow()[&first], 0);
+    assert!(list_view::get_row_view(list, 9).is_none());
+}
+
+#[test]
+fn scrolling_recycles_rows()
+{
+    let rows = Rc::new(RefCell::new(HashMap::new()));
+    let built = Rc::new(RefCell::new(0));
+
+    let list = create_list(&rows, &built);
+
+    list_view::set_scroll_offset(list, 240.0);
+
+    let row = list_view::get_row_view(list, 10).expect("row 10 is visible");
+    assert_eq!(rows.borrow()[&row], 10);
+    assert_eq!(view::get_rect(row).origin.y, 0.0);
+
+    // jumping to the end rebinds the same row views instead of building new ones
+    list_view::set_scroll_offset(list, f32::MAX);
+
+    assert_eq!(list_view::get_scroll_offset(list), ITEM_COUNT as f32 * 24.0 - 200.0);
+    assert_eq!(view::get_children(list).len(), 9);
+    assert_eq!(*built.borrow(), 9);
+
+    let last = list_view::get_row_view(list, ITEM_COUNT - 1).expect("the last row is visible");
+    assert_eq!(rows.borrow()[&last], ITEM_COUNT - 1);
+}
+
+#[test]
+fn selects_ranges_of_items()
+{
+    let rows = Rc::new(RefCell::new(HashMap::new()));
+    let built = Rc::new(RefCell::new(0));
+
+    let list = create_list(&rows, &built);
+    list_view::set_selection_mode(list, SelectionMode::Multiple);
+
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let changes_handle = changes.clone();
+    list_view::set_selection_changed_handler(list, move |_, indices| changes_handle.borrow_mut().push(indices.to_vec()));
+
+    let shift = Modifiers { shift: true, ..Modifiers::default() };
+    let control = Modifiers { control: true, ..Modifiers::default() };
+
+    click(list, 30.0, Modifiers::default());
+    assert_eq!(list_view::get_selected_indices(list), vec![1]);
+
+    key_down(list, Key::Down, shift);
+    key_down(list, Key::Down, shift);
+    assert_eq!(list_view::get_selected_indices(list), vec![1, 2, 3]);
+
+    // a control click adds to the range, a shift click extends from the anchor
+    click(list, 130.0, control);
+    assert_eq!(list_view::get_selected_indices(list), vec![1, 2, 3, 5]);
+
+    click(list, 180.0, shift);
+    assert_eq!(list_view::get_selected_indices(list), vec![5, 6, 7]);
+
+    key_down(list, Key::End, shift);
+    assert_eq!(list_view::get_selected_indices(list).len(), ITEM_COUNT - 5);
+    assert!(list_view::is_index_selected(list, ITEM_COUNT - 1));
+    assert!(!list_view::is_index_selected(list, 4));
+
+    key_down(list, Key::Home, Modifiers::default());
+    assert_eq!(list_view::get_selected_indices(list), vec![0]);
+    assert_eq!(changes.borrow().last(), Some(&vec![0]));
+}
@@ -0,0 +1,59 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  menu.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Checks which menu item a keyboard accelerator
+**                 activates.
+**
+***************************************************************/
+
+use nanoui::event::{Key, KeyChord, Modifiers};
+use nanoui::widget::menu;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn accelerators_skip_disabled_items()
+{
+    let chord = KeyChord::parse("Ctrl+S").expect("the chord parses");
+    let activated = Rc::new(RefCell::new(Vec::new()));
+
+    let file = menu::create();
+    let submenu = menu::create();
+    let edit = menu::create();
+
+    let save = menu::add_item(file, "Save").unwrap();
+    menu::add_submenu(file, "More", submenu);
+    let save_copy = menu::add_item(submenu, "Save Copy").unwrap();
+    menu::add_submenu(edit, "File", file);
+
+    for (item, name) in [(save, "save"), (save_copy, "save copy")]
+    {
+        let activated_handle = activated.clone();
+
+        menu::set_item_accelerator(item, Some(chord));
+        menu::set_item_action(item, move |_| activated_handle.borrow_mut().push(name));
+    }
+
+    let control = Modifiers { control: true, ..Modifiers::default() };
+
+    assert!(menu::activate_accelerator(edit, Key::Character('s'), control, None));
+    assert_eq!(*activated.borrow(), vec!["save"]);
+
+    // the disabled item no longer takes the chord, the next match in the submenu does
+    menu::set_item_enabled(save, false);
+
+    assert!(menu::activate_accelerator(edit, Key::Character('s'), control, None));
+    assert_eq!(*activated.borrow(), vec!["save", "save copy"]);
+
+    menu::set_item_enabled(save_copy, false);
+
+    assert!(!menu::activate_accelerator(edit, Key::Character('s'), control, None));
+    assert!(!menu::activate_accelerator(edit, Key::Character('x'), control, None));
+    assert_eq!(activated.borrow().len(), 2);
+}
@@ -0,0 +1,54 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  radio_button.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Checks which radio buttons form a group.
+**
+***************************************************************/
+
+use nanoui::view;
+use nanoui::widget::radio_button;
+use nanoui::View;
+
+// a root view holding a panel with two buttons in the named group
+fn create_tree(group: &str) -> (View, View)
+{
+    let root = view::create();
+    let panel = view::create();
+    let first = radio_button::create();
+    let second = radio_button::create();
+
+    radio_button::set_group(first, group);
+    radio_button::set_group(second, group);
+
+    view::add_child(panel, first);
+    view::add_child(panel, second);
+    view::add_child(root, panel);
+
+    return (first, second);
+}
+
+#[test]
+fn named_groups_stay_within_their_tree()
+{
+    let (first, second) = create_tree("size");
+    let (other_first, _) = create_tree("size");
+
+    radio_button::set_selected(first, true);
+    radio_button::set_selected(other_first, true);
+
+    // the same name in another tree does not deselect this one
+    assert!(radio_button::is_selected(first));
+    assert!(radio_button::is_selected(other_first));
+
+    radio_button::set_selected(second, true);
+    assert!(!radio_button::is_selected(first));
+    assert!(radio_button::is_selected(other_first));
+
+    assert_eq!(radio_button::get_selected_in_group(first), Some(second));
+    assert_eq!(radio_button::get_selected_in_group(other_first), Some(other_first));
+}
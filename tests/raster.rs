@@ -0,0 +1,120 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  raster.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Checks the coverage the rasterizer produces at
+**                 the edges of filled shapes.
+**
+***************************************************************/
+
+use nanoui::drawing::{FillRule, Mask, Rasterizer};
+
+use nanowin::Point;
+
+fn coverage(mask: &Mask, x: usize, y: usize) -> u8
+{
+    return mask.data[y * mask.width + x];
+}
+
+fn add_rect(rasterizer: &mut Rasterizer, x0: f32, y0: f32, x1: f32, y1: f32)
+{
+    rasterizer.move_to(Point { x: x0, y: y0 });
+    rasterizer.line_to(Point { x: x1, y: y0 });
+    rasterizer.line_to(Point { x: x1, y: y1 });
+    rasterizer.line_to(Point { x: x0, y: y1 });
+    rasterizer.close();
+}
+
+// four cubic quarters, which stay within a fraction of a pixel of the circle at this size
+fn add_circle(rasterizer: &mut Rasterizer, center: Point, radius: f32)
+{
+    let handle = radius * 0.552_284_8;
+    let point = |x: f32, y: f32| Point { x: center.x + x, y: center.y + y };
+
+    rasterizer.move_to(point(radius, 0.0));
+    rasterizer.cubic_to(point(radius, handle), point(handle, radius), point(0.0, radius));
+    rasterizer.cubic_to(point(-handle, radius), point(-radius, handle), point(-radius, 0.0));
+    rasterizer.cubic_to(point(-radius, -handle), point(-handle, -radius), point(0.0, -radius));
+    rasterizer.cubic_to(point(handle, -radius), point(radius, -handle), point(radius, 0.0));
+    rasterizer.close();
+}
+
+#[test]
+fn rect_edges_are_partially_covered()
+{
+    let mut rasterizer = Rasterizer::new(10, 10);
+    add_rect(&mut rasterizer, 2.5, 1.0, 6.5, 5.0);
+
+    let mask = rasterizer.rasterize(FillRule::NonZero);
+
+    assert_eq!(coverage(&mask, 1, 3), 0);
+    assert_eq!(coverage(&mask, 2, 3), 128);
+    assert_eq!(coverage(&mask, 4, 3), 255);
+    assert_eq!(coverage(&mask, 6, 3), 128);
+    assert_eq!(coverage(&mask, 7, 3), 0);
+
+    assert_eq!(coverage(&mask, 4, 0), 0);
+    assert_eq!(coverage(&mask, 4, 1), 255);
+    assert_eq!(coverage(&mask, 4, 4), 255);
+    assert_eq!(coverage(&mask, 4, 5), 0);
+}
+
+#[test]
+fn shapes_are_placed_relative_to_the_origin()
+{
+    let mut rasterizer = Rasterizer::new_at(Point { x: 10.0, y: 20.0 }, 4, 4);
+    add_rect(&mut rasterizer, 11.0, 21.0, 13.0, 23.0);
+
+    let mask = rasterizer.rasterize(FillRule::NonZero);
+
+    assert_eq!(coverage(&mask, 0, 0), 0);
+    assert_eq!(coverage(&mask, 1, 1), 255);
+    assert_eq!(coverage(&mask, 2, 2), 255);
+    assert_eq!(coverage(&mask, 3, 3), 0);
+}
+
+#[test]
+fn circle_coverage_matches_its_area()
+{
+    let mut rasterizer = Rasterizer::new(20, 20);
+    add_circle(&mut rasterizer, Point { x: 10.0, y: 10.0 }, 6.0);
+
+    let mask = rasterizer.rasterize(FillRule::NonZero);
+
+    assert_eq!(coverage(&mask, 10, 10), 255);
+    assert_eq!(coverage(&mask, 0, 0), 0);
+    assert_eq!(coverage(&mask, 10, 3), 0);
+
+    // the pixel the edge crosses on the diagonal is only partly covered
+    let edge = coverage(&mask, 14, 14);
+    assert!(edge > 0 && edge < 255, "{}", edge);
+
+    // curves are flattened into chords up to a tenth of a pixel inside them, which bounds the area lost along the perimeter
+    let area: f32 = mask.data.iter().map(|value| *value as f32 / 255.0).sum();
+    let expected = std::f32::consts::PI * 36.0;
+    let perimeter = std::f32::consts::PI * 12.0;
+    assert!(area <= expected && expected - area < perimeter * 0.1, "{} != {}", area, expected);
+}
+
+#[test]
+fn fill_rules_differ_inside_overlaps()
+{
+    let mut masks: Vec<Mask> = Vec::new();
+
+    for fill_rule in [FillRule::NonZero, FillRule::EvenOdd]
+    {
+        let mut rasterizer = Rasterizer::new(10, 10);
+        add_rect(&mut rasterizer, 1.0, 1.0, 9.0, 9.0);
+        add_rect(&mut rasterizer, 3.0, 3.0, 7.0, 7.0);
+
+        masks.push(rasterizer.rasterize(fill_rule));
+    }
+
+    assert_eq!(coverage(&masks[0], 5, 5), 255);
+    assert_eq!(coverage(&masks[1], 5, 5), 0);
+    assert_eq!(coverage(&masks[1], 2, 5), 255);
+}
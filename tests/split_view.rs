@@ -0,0 +1,55 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  split_view.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Checks how split views share space between panes.
+**
+***************************************************************/
+
+use nanoui::view;
+use nanoui::widget::{split_view, Orientation};
+
+fn assert_ratios(actual: &[f32], expected: &[f32])
+{
+    assert_eq!(actual.len(), expected.len());
+
+    for (actual, expected) in actual.iter().zip(expected.iter())
+    {
+        assert!((actual - expected).abs() < 0.001, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn added_panes_get_equal_shares()
+{
+    let split = split_view::create(Orientation::Horizontal);
+
+    for _ in 0..4
+    {
+        split_view::add_pane(split, view::create());
+    }
+
+    assert_ratios(&split_view::get_ratios(split), &[0.25, 0.25, 0.25, 0.25]);
+}
+
+#[test]
+fn a_pane_added_after_resizing_gets_one_share()
+{
+    let split = split_view::create(Orientation::Vertical);
+
+    split_view::add_pane(split, view::create());
+    split_view::add_pane(split, view::create());
+    split_view::set_ratios(split, &[0.8, 0.2]);
+
+    split_view::add_pane(split, view::create());
+
+    let ratios = split_view::get_ratios(split);
+    assert_ratios(&ratios[2..], &[1.0 / 3.0]);
+
+    // the others keep their proportions
+    assert!((ratios[0] / ratios[1] - 4.0).abs() < 0.001);
+}
@@ -0,0 +1,101 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  timer.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Fires timers and posted callbacks in virtual time
+**                 with advance_time.
+**
+***************************************************************/
+
+use nanoui::app::{self, clock};
+
+use std::time::Duration;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+fn milliseconds(value: u64) -> Duration
+{
+    return Duration::from_millis(value);
+}
+
+#[test]
+fn timers_fire_in_order_of_their_due_times()
+{
+    clock::set_manual(true);
+
+    let fired = Rc::new(RefCell::new(Vec::new()));
+
+    let fired_handle = fired.clone();
+    app::set_timeout(milliseconds(50), move || fired_handle.borrow_mut().push("timeout"));
+
+    let fired_handle = fired.clone();
+    let interval = app::set_interval(milliseconds(20), move || fired_handle.borrow_mut().push("interval"));
+
+    let fired_handle = fired.clone();
+    app::post_to_main_thread(move || fired_handle.borrow_mut().push("posted"));
+
+    assert!(app::has_pending());
+
+    app::advance_time(milliseconds(100));
+    assert_eq!(*fired.borrow(), vec!["posted", "interval", "interval", "timeout", "interval", "interval", "interval"]);
+
+    app::cancel(interval);
+    fired.borrow_mut().clear();
+
+    app::advance_time(milliseconds(100));
+    assert!(fired.borrow().is_empty());
+    assert!(!app::has_pending());
+
+    clock::set_manual(false);
+}
+
+#[test]
+fn timers_fire_at_their_due_time()
+{
+    clock::set_manual(true);
+
+    let start = clock::now();
+    let fired_at = Rc::new(RefCell::new(None));
+
+    let fired_handle = fired_at.clone();
+    app::set_timeout(milliseconds(30), move || *fired_handle.borrow_mut() = Some(clock::now()));
+
+    app::advance_time(milliseconds(29));
+    assert!(fired_at.borrow().is_none());
+
+    app::advance_time(milliseconds(100));
+    assert_eq!(*fired_at.borrow(), Some(start + milliseconds(30)));
+    assert_eq!(clock::now(), start + milliseconds(129));
+
+    clock::set_manual(false);
+}
+
+#[test]
+fn an_interval_can_cancel_itself()
+{
+    clock::set_manual(true);
+
+    let count = Rc::new(RefCell::new(0));
+    let timer = Rc::new(RefCell::new(0));
+
+    let count_handle = count.clone();
+    let timer_handle = timer.clone();
+    *timer.borrow_mut() = app::set_interval(milliseconds(10), move || {
+        *count_handle.borrow_mut() += 1;
+
+        if *count_handle.borrow() == 3
+        {
+            app::cancel(*timer_handle.borrow());
+        }
+    });
+
+    app::advance_time(milliseconds(100));
+    assert_eq!(*count.borrow(), 3);
+    assert!(!app::has_pending());
+
+    clock::set_manual(false);
+}
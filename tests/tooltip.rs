@@ -0,0 +1,103 @@
+/***************************************************************
+**
+** NanoKit Library Test File
+**
+** File         :  tooltip.rs
+** Crate        :  NanoUI
+** Project      :  NanoKit
+** Author       :  SH
+** Description  :  Checks the tooltip hover delay on the manual app
+**                 clock and that tooltips go away with the views
+**                 they belong to.
+**
+***************************************************************/
+
+use nanoui::widget::tooltip;
+use nanoui::{app, view, window};
+
+use nanowin::{Point, Rect, Size};
+
+use std::time::Duration;
+
+#[test]
+fn shows_after_the_delay_and_hides_on_leave()
+{
+    app::clock::set_manual(true);
+
+    let window = window::create();
+    let root = view::create();
+
+    window::set_root_view(window, root);
+    window::resize(window, Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 300.0, height: 200.0 } });
+
+    view::set_tooltip(root, Some("Hello tooltip"));
+    tooltip::set_delay(window, Duration::from_millis(500));
+
+    window::mouse_move(window, Point { x: 10.0, y: 10.0 });
+    assert!(app::has_pending());
+
+    window::render(window);
+    assert!(!tooltip::is_visible(window));
+
+    app::advance_time(Duration::from_millis(499));
+    window::render(window);
+    assert!(!tooltip::is_visible(window));
+
+    // the delay timer fires and the next frame shows the tooltip
+    app::advance_time(Duration::from_millis(1));
+    assert!(!app::has_pending());
+
+    window::render(window);
+    assert_eq!(tooltip::get_visible_text(window).as_deref(), Some("Hello tooltip"));
+
+    window::mouse_leave(window);
+    assert!(!tooltip::is_visible(window));
+
+    window::render(window);
+    assert!(!tooltip::is_visible(window));
+
+    app::clock::set_manual(false);
+}
+
+#[test]
+fn goes_away_with_the_window_content()
+{
+    app::clock::set_manual(true);
+
+    let window = window::create();
+    let root = view::create();
+    let child = view::create();
+
+    view::add_child(root, child);
+    window::set_root_view(window, root);
+    window::resize(window, Rect { origin: Point { x: 0.0, y: 0.0 }, size: Size { width: 300.0, height: 200.0 } });
+
+    view::set_tooltip(child, Some("Child tooltip"));
+    tooltip::set_delay(window, Duration::from_millis(100));
+
+    window::mouse_move(window, Point { x: 10.0, y: 10.0 });
+    app::advance_time(Duration::from_millis(100));
+    window::render(window);
+    assert!(tooltip::is_visible(window));
+
+    // removing the hovered view hides its tooltip
+    view::remove_child(root, child);
+    assert!(!tooltip::is_visible(window));
+    assert!(window::get_overlay_views(window).is_empty());
+
+    view::set_tooltip(root, Some("Root tooltip"));
+    window::mouse_move(window, Point { x: 20.0, y: 20.0 });
+    assert!(app::has_pending());
+
+    // destroying the root drops the popup and the pending delay
+    view::destroy(root);
+    assert!(!app::has_pending());
+    assert_eq!(window::get_root_view(window), None);
+
+    app::advance_time(Duration::from_millis(100));
+    window::render(window);
+    assert!(!tooltip::is_visible(window));
+    assert!(window::get_overlay_views(window).is_empty());
+
+    app::clock::set_manual(false);
+}